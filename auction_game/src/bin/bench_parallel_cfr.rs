@@ -0,0 +1,52 @@
+use auction_game::engines::algorithms::counterfactual_regret::CFR;
+use auction_game::engines::controllers::random_player::RandomPlayer;
+use auction_game::engines::q_values::regret::CFRPlus;
+use auction_game::engines::traits::PlayerController;
+use auction_game::models::game_state::GameState;
+use std::sync::Arc;
+use std::thread::available_parallelism;
+use std::time::Instant;
+
+fn main() {
+    // Times the sell phase solve by thread count and checks a fixed seed reproduces the tables
+    let no_players: u8 = 6;
+    let iterations: usize = 256;
+    let batch_iterations: usize = 4;
+    let seed: u64 = 17;
+    let mut controllers: Vec<RandomPlayer> = (0..no_players)
+        .map(|id| RandomPlayer::new(id, format!("Player_{id}")))
+        .collect();
+    let mut game_state = GameState::starting(no_players, 0);
+    game_state.reveal_auction();
+    while !game_state.bid_phase_end() {
+        let current_player = game_state.current_player();
+        let action = controllers[current_player as usize].decision(&game_state);
+        game_state = game_state.generate_next_state_bid(current_player, action);
+    }
+    println!("{game_state}");
+    let cores = available_parallelism().map(|n| n.get()).unwrap_or(1);
+    println!("Available cores: {cores}");
+
+    let mut serial_time: Option<f64> = None;
+    for threads in [1, 2, 4, 8, 16] {
+        let solve = || {
+            let mut cfr = CFR::new(Arc::new(CFRPlus {}), false)
+                .with_threads(threads, batch_iterations)
+                .with_seed(seed);
+            let start = Instant::now();
+            cfr.find_nash(&game_state, iterations);
+            (cfr.root_value().clone(), start.elapsed().as_secs_f64())
+        };
+        let (root_value, elapsed) = solve();
+        let (repeat_value, _) = solve();
+        let speedup = *serial_time.get_or_insert(elapsed) / elapsed;
+        println!(
+            "threads {:>2}: {:>7.2}s speedup {:>5.2} reproducible {} root value {:?}",
+            threads,
+            elapsed,
+            speedup,
+            root_value == repeat_value,
+            root_value
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use auction_game::engines::algorithms::best_response::BestResponse;
+use auction_game::engines::algorithms::counterfactual_regret::CFR;
+use auction_game::engines::controllers::random_player::RandomPlayer;
+use auction_game::engines::q_values::regret::{
+    CFRPlus, DiscountedCFR, LinearCFR, PredictiveCFRPlus, RegretUpdater, VanillaCFR,
+};
+use auction_game::engines::strategies::average::{Average, AverageWeighting, StrategyKind};
+use auction_game::engines::traits::PlayerController;
+use auction_game::models::game_state::GameState;
+use rand::thread_rng;
+use std::sync::Arc;
+use std::time::Instant;
+
+fn main() {
+    // Solves the same sell subgame with every regret updater and tracks the exact NashConv
+    let no_players: u8 = 6;
+    let rounds_left: usize = 2;
+    let iterations: usize = 2000;
+    let interval: usize = 250;
+    let mut controllers: Vec<RandomPlayer> = (0..no_players)
+        .map(|id| RandomPlayer::new(id, format!("Player_{id}")))
+        .collect();
+    let mut game_state = GameState::starting(no_players, 0);
+    game_state.reveal_auction();
+    while !game_state.bid_phase_end() {
+        let current_player = game_state.current_player();
+        let action = controllers[current_player as usize].decision(&game_state);
+        game_state = game_state.generate_next_state_bid(current_player, action);
+    }
+    while game_state.get_player_properties(0).len() > rounds_left || game_state.auction_end() {
+        game_state = if game_state.auction_end() {
+            game_state.generate_next_state_reveal()
+        } else {
+            let aggregate_sales: Vec<u8> = (0..no_players)
+                .map(|player| controllers[player as usize].decision(&game_state))
+                .collect();
+            game_state.manual_next_state_sell(aggregate_sales)
+        };
+    }
+    println!("{game_state}");
+
+    let discounted = DiscountedCFR::default();
+    let updaters: Vec<(Arc<dyn RegretUpdater>, Average)> = vec![
+        (
+            Arc::new(VanillaCFR {}),
+            Average::new(AverageWeighting::Uniform),
+        ),
+        (Arc::new(CFRPlus {}), Average::new(AverageWeighting::Linear)),
+        (
+            Arc::new(LinearCFR {}),
+            Average::new(AverageWeighting::Linear),
+        ),
+        (
+            Arc::new(DiscountedCFR::default()),
+            Average::new(discounted.average_weighting()),
+        ),
+        (
+            Arc::new(PredictiveCFRPlus {}),
+            Average::new(AverageWeighting::Linear),
+        ),
+    ];
+    // Searches to the end of the game with every reveal enumerated, so the NashConv is exact
+    let best_response = BestResponse::new(rounds_left as u8, 1000, 1000, 1);
+    for (updater, average) in updaters {
+        let name = updater.name();
+        let mut cfr = CFR::new(updater, false)
+            .with_average(average)
+            .with_exploitability(best_response.clone(), interval);
+        let start = Instant::now();
+        let mut curve: Vec<String> = Vec::with_capacity(iterations / interval);
+        for _ in 0..iterations / interval {
+            cfr.find_nash(&game_state, interval);
+            let exploitability = cfr
+                .last_exploitability()
+                .expect("Exploitability was measured");
+            let current = best_response
+                .clone()
+                .with_strategy_kind(StrategyKind::Current)
+                .exploitability(&cfr, &game_state, &mut thread_rng());
+            curve.push(format!(
+                "{:.3}/{:.3}",
+                exploitability.nash_conv, current.nash_conv
+            ));
+        }
+        println!(
+            "{:<28} NashConv average/current every {} iterations: [{}] in {:?}",
+            name,
+            interval,
+            curve.join(", "),
+            start.elapsed()
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use auction_game::engines::algorithms::counterfactual_regret::CFR;
+use auction_game::engines::controllers::random_player::RandomPlayer;
+use auction_game::engines::q_values::regret::CFRPlus;
+use auction_game::engines::traits::PlayerController;
+use auction_game::models::game_state::GameState;
+use auction_game::tui::cfr_dashboard::CFRDashboard;
+use std::env;
+use std::sync::Arc;
+
+fn main() {
+    // cfr_dashboard [sell|bid] [max iterations]
+    let args: Vec<String> = env::args().collect();
+    let phase = args.get(1).map(String::as_str).unwrap_or("sell");
+    let no_players: u8 = 6;
+    let mut game_state = GameState::starting(no_players, 0);
+    game_state.reveal_auction();
+    let (chunk_iterations, default_iterations) = if phase == "bid" {
+        (1000, 200000)
+    } else {
+        let mut controllers: Vec<RandomPlayer> = (0..no_players)
+            .map(|id| RandomPlayer::new(id, format!("Player_{id}")))
+            .collect();
+        while !game_state.bid_phase_end() {
+            let current_player = game_state.current_player();
+            let action = controllers[current_player as usize].decision(&game_state);
+            game_state = game_state.generate_next_state_bid(current_player, action);
+        }
+        // Solves from the first sell auction rather than the chance node before it
+        if game_state.auction_end() {
+            game_state = game_state.generate_next_state_reveal();
+        }
+        (25, 2000)
+    };
+    let max_iterations: usize = args
+        .get(2)
+        .and_then(|iterations| iterations.parse().ok())
+        .unwrap_or(default_iterations);
+    let mut cfr = CFR::new(Arc::new(CFRPlus {}), false);
+    let dashboard =
+        CFRDashboard::new(chunk_iterations, max_iterations).with_exploitability_every(4);
+    let history = dashboard
+        .run(&mut cfr, &game_state)
+        .expect("Failed to run the dashboard");
+    println!(
+        "Ran {} iterations{}, last NashConv {:?}, root value {:?}",
+        history.iterations,
+        if history.stopped { " (stopped)" } else { "" },
+        history
+            .exploitability
+            .last()
+            .map(|(_, nash_conv)| nash_conv),
+        cfr.root_value()
+    );
+}
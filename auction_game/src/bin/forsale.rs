@@ -0,0 +1,560 @@
+use auction_game::engines::algorithms::best_response::BestResponse;
+use auction_game::engines::algorithms::counterfactual_regret::CFR;
+use auction_game::engines::controllers::terminal_player::HumanPlayer;
+use auction_game::engines::q_values::regret::CFRPlus;
+use auction_game::engines::registry::{ControllerRegistry, ControllerSpec};
+use auction_game::engines::scorers::sell_phase_score::SellPhaseScore;
+use auction_game::engines::strategies::average::StrategyKind;
+use auction_game::engines::traits::PlayerController;
+use auction_game::game_modes::observers::{PrintObserver, RecordObserver};
+use auction_game::game_modes::referee::{Fallback, Referee};
+use auction_game::game_modes::runner::{GameRunner, PhaseLimit};
+use auction_game::game_modes::standard::StandardGame;
+use auction_game::game_modes::traits::{Game, GameObserver};
+use auction_game::models::enums::GamePhase;
+use auction_game::models::game_record::GameRecord;
+use auction_game::models::game_state::GameState;
+use auction_game::models::rule_set::RuleSet;
+use auction_game::tournaments::duplicate::DuplicateMatch;
+use auction_game::tournaments::tournament::{final_scores, Entrant, Tournament};
+use auction_game::tui::replay::ReplayViewer;
+use clap::{Args, Parser, Subcommand};
+use helper::logger::init_logger;
+use log::LevelFilter;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Parser)]
+#[command(name = "forsale", about = "Play, compare and analyse For Sale bots")]
+struct Cli {
+    #[command(flatten)]
+    shared: SharedArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct SharedArgs {
+    /// Seed of the deal, random and printed when not given
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+    /// Number of players, 3 to 6
+    #[arg(short = 'n', long = "players", global = true, default_value_t = 6,
+        value_parser = clap::value_parser!(u8).range(3..=6))]
+    players: u8,
+    /// Bot spec such as maxn:rounds=2,samples=50, repeat to fill seats in turn (see `forsale bots`)
+    #[arg(short, long = "bot", global = true)]
+    bots: Vec<String>,
+    /// Milliseconds a bot may think per move before the referee plays the fallback for it
+    #[arg(long, global = true)]
+    move_time_ms: Option<u64>,
+    /// Seconds a bot may think over the whole game
+    #[arg(long, global = true)]
+    game_time_s: Option<u64>,
+    /// Move played for a bot that runs out of time, panics or moves illegally: pass, random or
+    /// forfeit
+    #[arg(long, global = true, default_value = "pass")]
+    fallback: Fallback,
+    /// Directory to write a record of every game played to, one .fsg file per game
+    #[arg(long, global = true)]
+    record: Option<PathBuf>,
+    /// Log level of the file in ./logs
+    #[arg(long, global = true, default_value = "info")]
+    log_level: LevelFilter,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Play against the bots in the terminal
+    Play {
+        /// Seat of the human player
+        #[arg(long, default_value_t = 0)]
+        seat: u8,
+    },
+    /// Watch the bots play each other
+    Selfplay {
+        #[arg(long, default_value_t = 1)]
+        games: usize,
+    },
+    /// Rate the bots over many games
+    Tournament {
+        #[arg(long, default_value_t = 200)]
+        games: usize,
+        /// Player counts to cycle through, each 3 to 6, the --players count when not given
+        #[arg(long, value_delimiter = ',', conflicts_with = "duplicate",
+            value_parser = clap::value_parser!(u8).range(3..=6))]
+        player_counts: Vec<u8>,
+        /// Replays every deal over every seating of the bots instead
+        #[arg(long)]
+        duplicate: bool,
+        #[arg(long, default_value = "tournament_results.tsv")]
+        out: PathBuf,
+    },
+    /// Show what every bot plays in a position
+    Analyze {
+        /// Bids played from the start of the deal, in turn order
+        #[arg(long, value_delimiter = ',')]
+        bids: Vec<u8>,
+    },
+    /// Solve the first sell round after the bots bid, with its exploitability
+    SolveSell {
+        #[arg(long, default_value_t = 300)]
+        iters: usize,
+        /// Stops once the NashConv falls to this
+        #[arg(long)]
+        target: Option<f32>,
+    },
+    /// Play one game and print every move and position, or step through a recorded game
+    Replay {
+        /// Game record to browse, as written by --record
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// CFR iterations behind each sell phase evaluation of the record
+        #[arg(long, default_value_t = 20)]
+        eval_iters: usize,
+    },
+    /// List the bots and their parameters
+    Bots,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let registry = ControllerRegistry::default();
+    let shared = &cli.shared;
+    let seed = shared.seed.unwrap_or_else(|| thread_rng().random());
+    let command_name = match &cli.command {
+        Command::Play { .. } => "play",
+        Command::Selfplay { .. } => "selfplay",
+        Command::Tournament { .. } => "tournament",
+        Command::Analyze { .. } => "analyze",
+        Command::SolveSell { .. } => "solve-sell",
+        Command::Replay { .. } => "replay",
+        Command::Bots => {
+            print!("{}", registry.help());
+            return;
+        }
+    };
+    // Whether the command records games and referees bots
+    let (records, referees) = match &cli.command {
+        Command::Play { .. } | Command::Selfplay { .. } | Command::SolveSell { .. } => (true, true),
+        Command::Replay { file: None, .. } => (true, true),
+        Command::Tournament { .. } => (false, true),
+        Command::Analyze { .. } | Command::Replay { file: Some(_), .. } | Command::Bots => {
+            (false, false)
+        }
+    };
+    reject_flags(shared, command_name, records, referees);
+    init_logger(shared.log_level, &format!("forsale_{command_name}_{seed}"));
+    println!("forsale {command_name} with seed {seed}");
+    match &cli.command {
+        Command::Play { seat } => play(&registry, shared, seed, *seat),
+        Command::Selfplay { games } => selfplay(&registry, shared, seed, *games),
+        Command::Tournament {
+            games,
+            player_counts,
+            duplicate,
+            out,
+        } => tournament(
+            &registry,
+            shared,
+            seed,
+            *games,
+            player_counts,
+            *duplicate,
+            out,
+        ),
+        Command::Analyze { bids } => analyze(&registry, shared, seed, bids),
+        Command::SolveSell { iters, target } => {
+            solve_sell(&registry, shared, seed, *iters, *target)
+        }
+        Command::Replay {
+            file: Some(file),
+            eval_iters,
+        } => browse(file, *eval_iters),
+        Command::Replay { file: None, .. } => replay(&registry, shared, seed),
+        Command::Bots => {}
+    }
+}
+
+// Parses every --bot, or the defaults when none were given, exiting with the help on a bad spec
+fn specs(
+    registry: &ControllerRegistry,
+    shared: &SharedArgs,
+    defaults: &[&str],
+) -> Vec<ControllerSpec> {
+    let specs: Vec<String> = match shared.bots.is_empty() {
+        true => defaults.iter().map(|spec| spec.to_string()).collect(),
+        false => shared.bots.clone(),
+    };
+    specs
+        .iter()
+        .map(|spec| {
+            registry.parse(spec).unwrap_or_else(|error| {
+                eprintln!("{error}\n\n{}", registry.help());
+                process::exit(2);
+            })
+        })
+        .collect()
+}
+
+// Seat i gets the i-th spec, starting over when there are fewer specs than seats
+fn seat_controllers(specs: &[ControllerSpec], no_players: u8) -> Vec<Box<dyn PlayerController>> {
+    (0..no_players)
+        .map(|seat| specs[seat as usize % specs.len()].build(seat, no_players))
+        .collect()
+}
+
+// Bot spec of every seat, as seat_controllers fills them
+fn seat_specs(specs: &[ControllerSpec], no_players: u8) -> Vec<String> {
+    (0..no_players)
+        .map(|seat| specs[seat as usize % specs.len()].spec().to_string())
+        .collect()
+}
+
+// Exits when a flag was given that the command has no games to apply to
+fn reject_flags(shared: &SharedArgs, command_name: &str, records: bool, referees: bool) {
+    let mut flags: Vec<&str> = Vec::new();
+    if !records && shared.record.is_some() {
+        flags.push("--record");
+    }
+    if !referees {
+        if shared.move_time_ms.is_some() {
+            flags.push("--move-time-ms");
+        }
+        if shared.game_time_s.is_some() {
+            flags.push("--game-time-s");
+        }
+        if shared.fallback != Fallback::Pass {
+            flags.push("--fallback");
+        }
+    }
+    if !flags.is_empty() {
+        eprintln!("{} cannot be used with {command_name}", flags.join(", "));
+        process::exit(2);
+    }
+}
+
+// Observer writing the game to <record>/<game_id>.fsg when --record is given
+fn recorder(
+    shared: &SharedArgs,
+    game_id: &str,
+    players: Vec<String>,
+) -> Option<Box<dyn GameObserver>> {
+    let dir = shared.record.as_ref()?;
+    if let Err(error) = fs::create_dir_all(dir) {
+        eprintln!("Could not create {}: {error}", dir.display());
+        process::exit(1);
+    }
+    let path = dir.join(format!("{game_id}.fsg"));
+    println!("Recording to {}", path.display());
+    let recorder = RecordObserver::new(path)
+        .with_event(game_id.to_string())
+        .with_players(players);
+    Some(Box::new(recorder))
+}
+
+fn recorded(game: StandardGame, shared: &SharedArgs, players: Vec<String>) -> StandardGame {
+    match recorder(shared, &game.game_id, players) {
+        Some(recorder) => game.with_observer(recorder),
+        None => game,
+    }
+}
+
+// Runs a seeded game the way play and selfplay deal it, refereed and recorded by the flags
+fn runner(
+    controllers: Vec<Box<dyn PlayerController>>,
+    shared: &SharedArgs,
+    seed: u64,
+    game_id: &str,
+    players: Vec<String>,
+) -> GameRunner {
+    let runner = GameRunner::new(controllers)
+        .with_seed(seed)
+        .with_random_starting_player(true)
+        .with_referee(referee(shared));
+    match recorder(shared, game_id, players) {
+        Some(recorder) => runner.with_observer(recorder),
+        None => runner,
+    }
+}
+
+fn referee(shared: &SharedArgs) -> Referee {
+    let mut referee = Referee::default().with_fallback(shared.fallback);
+    if let Some(move_time_ms) = shared.move_time_ms {
+        referee = referee.with_move_time_limit(Duration::from_millis(move_time_ms));
+    }
+    if let Some(game_time_s) = shared.game_time_s {
+        referee = referee.with_game_time_budget(Duration::from_secs(game_time_s));
+    }
+    referee
+}
+
+fn print_incidents(game: &StandardGame) {
+    for incident in game.incidents() {
+        println!("Referee: {incident}");
+    }
+}
+
+// Same deal as play and selfplay with the same seed
+fn dealt(seed: u64, no_players: u8) -> GameState {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut game_state = RuleSet::standard(no_players).deal_with_rng(true, &mut rng);
+    game_state.reveal_auction();
+    game_state
+}
+
+fn print_scores(game_state: &GameState, names: &[String]) {
+    for (player, score) in final_scores(game_state).iter().enumerate() {
+        println!("Player {} {:<28} {:>3}", player + 1, names[player], score);
+    }
+}
+
+fn play(registry: &ControllerRegistry, shared: &SharedArgs, seed: u64, seat: u8) {
+    if seat >= shared.players {
+        eprintln!("--seat must be below --players {}", shared.players);
+        process::exit(2);
+    }
+    let specs = specs(registry, shared, &["heuristic"]);
+    let mut controllers = seat_controllers(&specs, shared.players);
+    controllers[seat as usize] = Box::new(HumanPlayer::new(seat, "Brave Human".to_string()));
+    let names: Vec<String> = controllers
+        .iter()
+        .map(|controller| controller.nickname())
+        .collect();
+    let mut players = seat_specs(&specs, shared.players);
+    players[seat as usize] = "human".to_string();
+    let game = StandardGame::new(format!("play_{seed}"), shared.log_level, controllers, true)
+        .with_seed(seed)
+        .with_referee(referee(shared));
+    let mut game = recorded(game, shared, players);
+    game.game_run();
+    print_incidents(&game);
+    if let Some(final_state) = game.final_state() {
+        println!("{final_state}");
+        println!("Game has concluded!");
+        print_scores(final_state, &names);
+    }
+}
+
+fn selfplay(registry: &ControllerRegistry, shared: &SharedArgs, seed: u64, games: usize) {
+    let specs = specs(registry, shared, &["heuristic"]);
+    let mut totals: Vec<u32> = vec![0; shared.players as usize];
+    for game in 0..games {
+        let game_seed = seed.wrapping_add(game as u64);
+        let controllers = seat_controllers(&specs, shared.players);
+        let names: Vec<String> = controllers
+            .iter()
+            .map(|controller| controller.nickname())
+            .collect();
+        let self_play = StandardGame::new(
+            format!("selfplay_{game_seed}"),
+            shared.log_level,
+            controllers,
+            true,
+        )
+        .with_seed(game_seed)
+        .with_referee(referee(shared));
+        let mut self_play = recorded(self_play, shared, seat_specs(&specs, shared.players));
+        self_play.game_run();
+        if let Some(final_state) = self_play.final_state() {
+            println!("Game {} seed {}", game + 1, game_seed);
+            print_incidents(&self_play);
+            print_scores(final_state, &names);
+            for (player, score) in final_scores(final_state).iter().enumerate() {
+                totals[player] += *score as u32;
+            }
+        }
+    }
+    if games > 1 {
+        println!("Average score per seat");
+        for (player, total) in totals.iter().enumerate() {
+            println!("Player {} {:.1}", player + 1, *total as f32 / games as f32);
+        }
+    }
+}
+
+fn tournament(
+    registry: &ControllerRegistry,
+    shared: &SharedArgs,
+    seed: u64,
+    games: usize,
+    player_counts: &[u8],
+    duplicate: bool,
+    out: &PathBuf,
+) {
+    let specs = specs(
+        registry,
+        shared,
+        &[
+            "heuristic:preset=balanced",
+            "heuristic:preset=cautious",
+            "heuristic:preset=aggressive",
+            "heuristic:preset=hoarder",
+            "random",
+        ],
+    );
+    let entrants: Vec<Entrant> = specs.iter().map(ControllerSpec::entrant).collect();
+    let written = if duplicate {
+        let duplicate_match = DuplicateMatch::new(entrants, shared.players, games)
+            .with_seed(seed)
+            .with_referee(referee(shared));
+        println!(
+            "{} deals over {} seatings",
+            games,
+            duplicate_match.games_per_deal()
+        );
+        let report = duplicate_match.run();
+        print!("{}", report.leaderboard());
+        report.write(out)
+    } else {
+        let player_counts = match player_counts.is_empty() {
+            true => vec![shared.players],
+            false => player_counts.to_vec(),
+        };
+        let standings = Tournament::new(entrants, games)
+            .with_player_counts(player_counts)
+            .with_seed(seed)
+            .with_referee(referee(shared))
+            .run();
+        print!("{}", standings.leaderboard());
+        standings.write(out)
+    };
+    match written {
+        Ok(()) => println!("Results written to {}", out.display()),
+        Err(error) => eprintln!("Could not write {}: {error}", out.display()),
+    }
+}
+
+fn analyze(registry: &ControllerRegistry, shared: &SharedArgs, seed: u64, bids: &[u8]) {
+    let specs = specs(registry, shared, &["heuristic", "mcts:iters=2000"]);
+    let mut game_state = dealt(seed, shared.players);
+    for &bid in bids.iter() {
+        if game_state.game_phase() != GamePhase::Bid {
+            eprintln!("The bid phase ended before bid {bid}");
+            process::exit(2);
+        }
+        let player = game_state.current_player();
+        if !game_state.legal_moves_bid(player).contains(&bid) {
+            eprintln!(
+                "Player {} cannot bid {bid}, legal bids are {:?}",
+                player + 1,
+                game_state.legal_moves_bid(player)
+            );
+            process::exit(2);
+        }
+        game_state = game_state.manual_next_state_bid(player, bid);
+        if game_state.auction_end() && !game_state.game_end() {
+            game_state = game_state.generate_next_state_reveal();
+        }
+    }
+    println!("{game_state}");
+    let players: Vec<u8> = match game_state.game_phase() {
+        GamePhase::Bid => vec![game_state.current_player()],
+        GamePhase::Sell => (0..shared.players).collect(),
+    };
+    for &player in players.iter() {
+        println!(
+            "Player {} legal moves {:?}",
+            player + 1,
+            game_state.legal_moves(player)
+        );
+        for spec in specs.iter() {
+            let mut controller = spec.build(player, shared.players);
+            let start = Instant::now();
+            let action = controller.decision(&game_state);
+            println!(
+                "  {:<32} plays {:>2} in {:?}",
+                spec.spec(),
+                action,
+                start.elapsed()
+            );
+        }
+    }
+}
+
+fn solve_sell(
+    registry: &ControllerRegistry,
+    shared: &SharedArgs,
+    seed: u64,
+    iterations: usize,
+    target: Option<f32>,
+) {
+    let specs = specs(registry, shared, &["heuristic"]);
+    let controllers = seat_controllers(&specs, shared.players);
+    let players = seat_specs(&specs, shared.players);
+    let mut runner = runner(
+        controllers,
+        shared,
+        seed,
+        &format!("solve_sell_{seed}"),
+        players,
+    )
+    .with_phase_limit(PhaseLimit::BidOnly);
+    let mut game_state = runner.run();
+    for incident in runner.incidents() {
+        println!("Referee: {incident}");
+    }
+    if game_state.auction_end() {
+        game_state = game_state.generate_next_state_reveal();
+    }
+    println!("{game_state}");
+    let mut cfr = CFR::new(Arc::new(CFRPlus {}), false)
+        .with_exploitability(BestResponse::default(), 50)
+        .with_seed(seed);
+    let start = Instant::now();
+    let iterations = match target {
+        Some(target) => cfr.find_nash_until(&game_state, target, iterations),
+        None => cfr.find_nash(&game_state, iterations),
+    };
+    println!("{iterations} iterations in {:?}", start.elapsed());
+    let exploitability = cfr.exploitability(&game_state);
+    println!(
+        "NashConv {:.3}, {:.3} per player",
+        exploitability.nash_conv,
+        exploitability.per_player()
+    );
+    for player in 0..shared.players {
+        let (moves, strategy) = cfr.policy(&game_state, player, StrategyKind::Average);
+        let policy: Vec<String> = moves
+            .iter()
+            .zip(strategy.iter())
+            .map(|(property, probability)| format!("{property}:{probability:.2}"))
+            .collect();
+        println!("Player {} sells {}", player + 1, policy.join(" "));
+    }
+}
+
+fn replay(registry: &ControllerRegistry, shared: &SharedArgs, seed: u64) {
+    let specs = specs(registry, shared, &["heuristic"]);
+    let controllers = seat_controllers(&specs, shared.players);
+    let players = seat_specs(&specs, shared.players);
+    let mut runner = runner(
+        controllers,
+        shared,
+        seed,
+        &format!("replay_{seed}"),
+        players,
+    )
+    .with_observer(Box::new(PrintObserver {}));
+    let final_state = runner.run();
+    print_scores(&final_state, runner.nicknames());
+}
+
+fn browse(file: &PathBuf, eval_iters: usize) {
+    let viewer = GameRecord::load(file).and_then(ReplayViewer::new);
+    let mut viewer = viewer
+        .unwrap_or_else(|error| {
+            eprintln!("Cannot replay {}: {error}", file.display());
+            process::exit(2);
+        })
+        .with_sell_phase_score(SellPhaseScore::new(eval_iters));
+    if let Err(error) = viewer.run() {
+        eprintln!("Terminal error: {error}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,68 @@
+use auction_game::engines::controllers::random_player::RandomPlayer;
+use auction_game::engines::tablebases::sell_tablebase::SellTablebase;
+use auction_game::engines::traits::PlayerController;
+use auction_game::models::game_state::GameState;
+use helper::logger::init_logger;
+use log::{info, LevelFilter};
+use std::env;
+use std::path::PathBuf;
+use std::time::Instant;
+
+fn main() {
+    // Adds the sell endgames reached from a few random games to the tablebase on disk. This only
+    // covers positions those games reach, each solved exactly over every deal they allow.
+    // The tablebase path is the first argument, a file in the temp dir when not given.
+    init_logger(LevelFilter::Info, "generate_tablebase");
+    let no_players: u8 = 6;
+    let no_games: usize = 3;
+    let path = env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| env::temp_dir().join("sell_endgame.tb"));
+    // A file that fails its checks is reported rather than overwritten
+    let mut tablebase = match path.exists() {
+        true => SellTablebase::load(&path).expect("Failed to load tablebase"),
+        false => SellTablebase::default(),
+    };
+    let mut controllers: Vec<Box<dyn PlayerController>> = (0..no_players)
+        .map(|id| {
+            Box::new(RandomPlayer::new(id, format!("Player_{id}"))) as Box<dyn PlayerController>
+        })
+        .collect();
+    let start = Instant::now();
+    for game in 0..no_games {
+        let mut game_state = GameState::starting(no_players, 0);
+        game_state.reveal_auction();
+        while !game_state.bid_phase_end() {
+            let current_player = game_state.current_player();
+            let action = controllers[current_player as usize].decision(&game_state);
+            game_state = game_state.generate_next_state_bid(current_player, action);
+        }
+        // Random sales until the endgame starts
+        while !tablebase.covers(&game_state) || !game_state.auction_end() {
+            game_state = if game_state.auction_end() {
+                game_state.generate_next_state_reveal()
+            } else {
+                let aggregate_sales: Vec<u8> = (0..no_players)
+                    .map(|player| controllers[player as usize].decision(&game_state))
+                    .collect();
+                game_state.manual_next_state_sell(aggregate_sales)
+            };
+        }
+        let new_entries = tablebase.generate(&game_state);
+        info!(
+            "Game {}: added {} entries, tablebase has {} entries after {:?}",
+            game + 1,
+            new_entries,
+            tablebase.len(),
+            start.elapsed()
+        );
+    }
+    tablebase.save(&path).expect("Failed to save tablebase");
+    println!(
+        "Saved {} entries from the endgames of {} random games to {:?}",
+        tablebase.len(),
+        no_games,
+        path
+    );
+}
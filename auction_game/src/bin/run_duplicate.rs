@@ -0,0 +1,47 @@
+use auction_game::engines::controllers::heuristic_player::{HeuristicPersonality, HeuristicPlayer};
+use auction_game::engines::traits::PlayerController;
+use auction_game::tournaments::duplicate::DuplicateMatch;
+use auction_game::tournaments::tournament::{Entrant, Tournament};
+use std::env;
+use std::sync::Arc;
+
+fn heuristic(name: &'static str) -> Entrant {
+    Entrant::new(
+        name.to_string(),
+        Arc::new(move |id, _| {
+            let personality = HeuristicPersonality::preset(name).unwrap();
+            Box::new(HeuristicPlayer::new(id, format!("{name}_{id}")).with_personality(personality))
+                as Box<dyn PlayerController>
+        }),
+    )
+}
+
+fn main() {
+    // Two close presets, two seats each, compared on duplicate deals and on as many plain games
+    let deals: usize = env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(50);
+    let entrants = vec![heuristic("balanced"), heuristic("hoarder")];
+    let duplicate = DuplicateMatch::new(entrants.clone(), 4, deals).with_seed(42);
+    let report = duplicate.run();
+    println!(
+        "Duplicate: {} deals x {} seatings",
+        deals,
+        duplicate.games_per_deal()
+    );
+    print!("{}", report.leaderboard());
+    let path = "duplicate_results.tsv";
+    match report.write(path) {
+        Ok(()) => println!("Results written to {path}"),
+        Err(error) => eprintln!("Could not write {path}: {error}"),
+    }
+
+    let games = deals * duplicate.games_per_deal();
+    let standings = Tournament::new(entrants, games)
+        .with_player_counts(vec![4])
+        .with_seed(42_000)
+        .run();
+    println!("Shuffled deals: {games} games");
+    print!("{}", standings.leaderboard());
+}
@@ -0,0 +1,57 @@
+use auction_game::engines::controllers::heuristic_player::{HeuristicPersonality, HeuristicPlayer};
+use auction_game::engines::traits::PlayerController;
+use auction_game::tournaments::sprt::{Sprt, SprtDecision};
+use auction_game::tournaments::tournament::Entrant;
+use std::env;
+use std::sync::Arc;
+
+fn heuristic(name: String) -> Entrant {
+    let personality = HeuristicPersonality::preset(&name).unwrap_or_else(|| {
+        panic!(
+            "Unknown preset {name}, expected one of {:?}",
+            HeuristicPersonality::preset_names()
+        )
+    });
+    Entrant::new(
+        name.clone(),
+        Arc::new(move |id, _| {
+            Box::new(
+                HeuristicPlayer::new(id, format!("{name}_{id}"))
+                    .with_personality(personality.clone()),
+            ) as Box<dyn PlayerController>
+        }),
+    )
+}
+
+fn main() {
+    // run_sprt [candidate preset] [baseline preset] [elo0] [elo1]
+    let args: Vec<String> = env::args().collect();
+    let candidate = args.get(1).cloned().unwrap_or("cautious".to_string());
+    let baseline = args.get(2).cloned().unwrap_or("balanced".to_string());
+    let elo0: f64 = args.get(3).and_then(|arg| arg.parse().ok()).unwrap_or(0.0);
+    let elo1: f64 = args.get(4).and_then(|arg| arg.parse().ok()).unwrap_or(10.0);
+    let sprt = Sprt::new(heuristic(candidate.clone()), heuristic(baseline.clone()))
+        .with_hypotheses(elo0, elo1)
+        .with_error_bounds(0.05, 0.05)
+        .with_max_deals(5000)
+        .with_seed(43);
+    let (lower, upper) = sprt.bounds();
+    println!("SPRT {candidate} vs {baseline}: H0 elo <= {elo0}, H1 elo >= {elo1}, LLR bounds [{lower:.2}, {upper:.2}]");
+    let status = sprt.run(|status| {
+        if status.deals % 10 == 0 || status.decision != SprtDecision::Continue {
+            println!(
+                "deals {:>5} games {:>6} score {:.3} elo {:>+7.1} LLR {:>+6.2}",
+                status.deals,
+                status.games,
+                status.score,
+                status.elo(),
+                status.llr
+            );
+        }
+    });
+    match status.decision {
+        SprtDecision::AcceptH1 => println!("H1 accepted: {candidate} is stronger"),
+        SprtDecision::AcceptH0 => println!("H0 accepted: {candidate} is not stronger"),
+        SprtDecision::Continue => println!("No decision after {} deals", status.deals),
+    }
+}
@@ -0,0 +1,43 @@
+use auction_game::engines::registry::ControllerRegistry;
+use auction_game::tournaments::tournament::{Entrant, Tournament};
+use std::env;
+use std::process;
+
+fn main() {
+    // run_tournament [games] [bot spec ...], every heuristic preset and a random player by default
+    let args: Vec<String> = env::args().collect();
+    let games: usize = args.get(1).and_then(|arg| arg.parse().ok()).unwrap_or(200);
+    let mut specs: Vec<String> = args.iter().skip(2).cloned().collect();
+    if specs.is_empty() {
+        specs = [
+            "heuristic:preset=balanced",
+            "heuristic:preset=cautious",
+            "heuristic:preset=aggressive",
+            "heuristic:preset=hoarder",
+            "random",
+        ]
+        .iter()
+        .map(|spec| spec.to_string())
+        .collect();
+    }
+    let registry = ControllerRegistry::default();
+    let entrants: Vec<Entrant> = specs
+        .iter()
+        .map(|spec| {
+            registry.entrant(spec).unwrap_or_else(|error| {
+                eprintln!("{error}\n\n{}", registry.help());
+                process::exit(1);
+            })
+        })
+        .collect();
+    let tournament = Tournament::new(entrants, games)
+        .with_player_counts(vec![3, 4, 5, 6])
+        .with_seed(41);
+    let standings = tournament.run();
+    print!("{}", standings.leaderboard());
+    let path = "tournament_results.tsv";
+    match standings.write(path) {
+        Ok(()) => println!("Results written to {path}"),
+        Err(error) => eprintln!("Could not write {path}: {error}"),
+    }
+}
@@ -0,0 +1,226 @@
+use auction_game::engines::algorithms::counterfactual_regret::CFR;
+use auction_game::engines::beliefs::belief_tracker::{BeliefTracker, PlayerBelief};
+use auction_game::engines::controllers::random_player::RandomPlayer;
+use auction_game::engines::q_values::regret::CFRPlus;
+use auction_game::engines::strategies::average::StrategyKind;
+use auction_game::engines::traits::PlayerController;
+use auction_game::models::enums::{GamePhase, Property};
+use auction_game::models::game_state::GameState;
+use rand::seq::IndexedRandom;
+use rand::{thread_rng, Rng};
+use std::sync::Arc;
+
+fn main() {
+    let no_players: u8 = 6;
+    let mut controllers: Vec<RandomPlayer> = (0..no_players)
+        .map(|id| RandomPlayer::new(id, format!("Random_{id}")))
+        .collect();
+    // Tracks a whole game from public moves only and checks it against the real state
+    let mut tracker = BeliefTracker::new(no_players);
+    let mut late_tracker: Option<BeliefTracker> = None;
+    let mut sell_start: Option<GameState> = None;
+    let mut checked: usize = 0;
+    let mut game_state = GameState::starting(no_players, 0);
+    game_state.reveal_auction();
+    while !game_state.game_end() {
+        if game_state.auction_end() {
+            game_state = game_state.generate_next_state_reveal();
+            continue;
+        }
+        tracker.observe_auction(&game_state);
+        for player in 0..no_players {
+            let belief = tracker.player(player);
+            assert_eq!(
+                belief.known_properties(),
+                game_state.get_player_properties(player).as_slice()
+            );
+            let coins = game_state.get_player_coins(player);
+            assert_eq!(belief.coins(), (coins, coins));
+        }
+        if game_state.game_phase() == GamePhase::Sell {
+            assert_eq!(
+                tracker.holdings_encoding(),
+                game_state.get_holdings_encoding()
+            );
+        }
+        checked += 1;
+        game_state = match game_state.game_phase() {
+            GamePhase::Bid => {
+                let player = game_state.current_player();
+                let action = controllers[player as usize].decision(&game_state);
+                tracker.observe_bid(&game_state, player, action);
+                game_state.manual_next_state_bid(player, action)
+            }
+            GamePhase::Sell => {
+                if late_tracker.is_none() {
+                    // A seat joining at the start of the sell phase only knows its own cards
+                    let own: Vec<Property> = game_state.get_player_properties(0).clone();
+                    let players: Vec<PlayerBelief> = (0..no_players)
+                        .map(|player| match player {
+                            0 => {
+                                let coins = game_state.get_player_coins(0);
+                                PlayerBelief::new(own.clone(), 0, coins, coins, Vec::new())
+                            }
+                            _ => PlayerBelief::new(
+                                Vec::new(),
+                                game_state.get_player_properties(player).len(),
+                                0,
+                                GameState::starting_coins(no_players),
+                                Vec::new(),
+                            ),
+                        })
+                        .collect();
+                    let unseen: Vec<Property> = GameState::all_properties()
+                        .into_iter()
+                        .filter(|property| !own.contains(property))
+                        .collect();
+                    late_tracker = Some(BeliefTracker::from_beliefs(
+                        players,
+                        unseen,
+                        GameState::all_checks(),
+                        GamePhase::Sell,
+                    ));
+                    sell_start = Some(game_state.clone());
+                }
+                let sales: Vec<u8> = controllers
+                    .iter_mut()
+                    .map(|controller| controller.decision(&game_state))
+                    .collect();
+                tracker.observe_sales(&game_state, &sales);
+                if let Some(late_tracker) = &mut late_tracker {
+                    late_tracker.observe_sales(&game_state, &sales);
+                }
+                game_state.manual_next_state_sell(sales)
+            }
+        };
+    }
+    println!("Beliefs matched the game at all {checked} decision points");
+
+    let late_tracker = late_tracker.expect("The game reached the sell phase");
+    let sell_start = sell_start.expect("The game reached the sell phase");
+    let unknown: Vec<usize> = (0..no_players)
+        .map(|player| late_tracker.player(player).unknown_properties())
+        .collect();
+    println!("Late tracker unknown holdings at the end: {unknown:?}");
+
+    // CFR policy of seat 0 at the sell start, from the real state and from beliefs alone
+    let mut joined = BeliefTracker::from_beliefs(
+        (0..no_players)
+            .map(|player| {
+                let coins = sell_start.get_player_coins(player);
+                match player {
+                    0 => PlayerBelief::new(
+                        sell_start.get_player_properties(0).clone(),
+                        0,
+                        coins,
+                        coins,
+                        Vec::new(),
+                    ),
+                    _ => PlayerBelief::new(
+                        Vec::new(),
+                        sell_start.get_player_properties(player).len(),
+                        coins,
+                        coins,
+                        Vec::new(),
+                    ),
+                }
+            })
+            .collect(),
+        GameState::all_properties()
+            .into_iter()
+            .filter(|property| !sell_start.get_player_properties(0).contains(property))
+            .collect(),
+        GameState::all_checks(),
+        GamePhase::Sell,
+    );
+    joined.observe_reveal(GamePhase::Sell, sell_start.get_auction_pool());
+    println!(
+        "Chance P1 holds property 30: {:.3}",
+        joined.holding_probability(1, 30)
+    );
+    let mut cfr = CFR::new(Arc::new(CFRPlus {}), false);
+    cfr.find_nash(&sell_start, 100);
+    let exact = cfr.policy(&sell_start, 0, StrategyKind::Average);
+    let believed = cfr.belief_policy(&joined, &sell_start, 0, StrategyKind::Average, 8);
+    println!("Seat 0 policy from the state:   {exact:?}");
+    println!("Seat 0 policy from the beliefs: {believed:?}");
+
+    // With 4 players 2 of each deck are set aside, so determinised worlds must keep the real
+    // deck sizes and still play out to the end
+    let no_players: u8 = 4;
+    let mut controllers: Vec<RandomPlayer> = (0..no_players)
+        .map(|id| RandomPlayer::new(id, format!("Random_{id}")))
+        .collect();
+    let mut rng = thread_rng();
+    let mut tracker = BeliefTracker::new(no_players);
+    let mut worlds: usize = 0;
+    let mut game_state = GameState::starting(no_players, 0);
+    game_state.reveal_auction();
+    while !game_state.game_end() {
+        if game_state.auction_end() {
+            game_state = game_state.generate_next_state_reveal();
+            continue;
+        }
+        tracker.observe_auction(&game_state);
+        let world = tracker.determinise(&game_state, &mut rng);
+        assert_eq!(
+            world.get_remaining_properties().len(),
+            game_state.get_remaining_properties().len()
+        );
+        assert_eq!(
+            world.get_remaining_checks().len(),
+            game_state.get_remaining_checks().len()
+        );
+        // Information sets are public, so every world sampled from them keys the same node
+        let mut shuffled = game_state.clone();
+        shuffled.determinise(&mut rng);
+        for sampled in [&world, &shuffled] {
+            assert_eq!(
+                sampled.get_information_set_encoding(),
+                game_state.get_information_set_encoding()
+            );
+        }
+        play_out(world, &mut rng);
+        worlds += 1;
+        game_state = match game_state.game_phase() {
+            GamePhase::Bid => {
+                let player = game_state.current_player();
+                let action = controllers[player as usize].decision(&game_state);
+                tracker.observe_bid(&game_state, player, action);
+                game_state.manual_next_state_bid(player, action)
+            }
+            GamePhase::Sell => {
+                let sales: Vec<u8> = controllers
+                    .iter_mut()
+                    .map(|controller| controller.decision(&game_state))
+                    .collect();
+                tracker.observe_sales(&game_state, &sales);
+                game_state.manual_next_state_sell(sales)
+            }
+        };
+    }
+    println!("Played out {worlds} determinised 4 player worlds");
+}
+
+// Random moves from game_state to the end of the game
+fn play_out<R: Rng>(mut game_state: GameState, rng: &mut R) {
+    while !game_state.game_end() {
+        if game_state.auction_end() {
+            game_state = game_state.generate_next_state_reveal();
+            continue;
+        }
+        game_state = match game_state.game_phase() {
+            GamePhase::Bid => {
+                let player = game_state.current_player();
+                let action = *game_state.legal_moves_bid(player).choose(rng).unwrap();
+                game_state.manual_next_state_bid(player, action)
+            }
+            GamePhase::Sell => {
+                let sales: Vec<u8> = (0..game_state.no_players())
+                    .map(|player| *game_state.legal_moves_sell(player).choose(rng).unwrap())
+                    .collect();
+                game_state.manual_next_state_sell(sales)
+            }
+        };
+    }
+}
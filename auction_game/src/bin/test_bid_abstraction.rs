@@ -0,0 +1,82 @@
+use auction_game::engines::abstractions::bid_abstraction::BidAbstraction;
+use auction_game::engines::abstractions::progressive_widening::ProgressiveWidening;
+use auction_game::engines::algorithms::ismcts::{SearchBudget, ISMCTS};
+use auction_game::engines::algorithms::maxn_player::MaxNPlayer;
+use auction_game::engines::rollouts::random_rollout::RandomRollout;
+use auction_game::models::game_state::GameState;
+use rand::seq::IndexedRandom;
+use rand::thread_rng;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn main() {
+    // Compares full-width search with the abstracted search on the same random bid positions
+    let no_positions: usize = 10;
+    let mut rng = thread_rng();
+    let mut maxn_time = [Duration::ZERO; 2];
+    let mut maxn_leaves = [0u64; 2];
+    let mut maxn_agreement: usize = 0;
+    let mut mcts_time = [Duration::ZERO; 2];
+    let mut mcts_agreement: usize = 0;
+    for position in 0..no_positions {
+        let mut game_state = GameState::starting(6, 0);
+        game_state.reveal_auction();
+        // Random opening moves so the positions are spread across the bid phase
+        while game_state.round_no() < (position % 4) as u8 || game_state.auction_end() {
+            if game_state.auction_end() {
+                game_state = game_state.generate_next_state_reveal();
+                continue;
+            }
+            let player = game_state.current_player();
+            let action = *game_state.legal_moves(player).choose(&mut rng).unwrap();
+            game_state = game_state.manual_next_state_bid(player, action);
+        }
+        let player = game_state.current_player();
+
+        let mut full_maxn = MaxNPlayer::new(player, "full".to_string(), false, false);
+        let mut abstract_maxn = MaxNPlayer::new(player, "abstract".to_string(), false, false)
+            .with_bid_abstraction(BidAbstraction::default());
+        let start = Instant::now();
+        let full_action = full_maxn.maximax_round(&game_state, 1, false, 0);
+        maxn_time[0] += start.elapsed();
+        maxn_leaves[0] += full_maxn.last_leaf_node_count();
+        let start = Instant::now();
+        let abstract_action = abstract_maxn.maximax_round(&game_state, 1, false, 0);
+        maxn_time[1] += start.elapsed();
+        maxn_leaves[1] += abstract_maxn.last_leaf_node_count();
+        if full_action == abstract_action {
+            maxn_agreement += 1;
+        }
+
+        let budget = SearchBudget::Iterations(4000);
+        let full_mcts = ISMCTS::new(Arc::new(RandomRollout {}), budget, 0.7, 1, None, false);
+        let widened_mcts = ISMCTS::new(Arc::new(RandomRollout {}), budget, 0.7, 1, None, false)
+            .with_bid_abstraction(BidAbstraction::default())
+            .with_progressive_widening(ProgressiveWidening::default());
+        let start = Instant::now();
+        let full_action = full_mcts.best_action(&game_state, player);
+        mcts_time[0] += start.elapsed();
+        let start = Instant::now();
+        let widened_action = widened_mcts.best_action(&game_state, player);
+        mcts_time[1] += start.elapsed();
+        if full_action == widened_action {
+            mcts_agreement += 1;
+        }
+        println!(
+            "Position {}: legal moves: {} abstract moves: {}",
+            position + 1,
+            game_state.legal_moves(player).len(),
+            BidAbstraction::default()
+                .abstract_moves(&game_state.legal_moves(player))
+                .len()
+        );
+    }
+    println!(
+        "MaxN full: {:?} {} leaves | abstract: {:?} {} leaves | same move {}/{}",
+        maxn_time[0], maxn_leaves[0], maxn_time[1], maxn_leaves[1], maxn_agreement, no_positions
+    );
+    println!(
+        "ISMCTS full: {:?} | abstract + widening: {:?} | same move {}/{}",
+        mcts_time[0], mcts_time[1], mcts_agreement, no_positions
+    );
+}
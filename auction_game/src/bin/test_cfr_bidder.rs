@@ -0,0 +1,105 @@
+use auction_game::engines::algorithms::counterfactual_regret::CFR;
+use auction_game::engines::algorithms::maxn_player::MaxNPlayer;
+use auction_game::engines::constants::VALUE_PER_PROPERTY;
+use auction_game::engines::controllers::cfr_bidder::CFRBidder;
+use auction_game::engines::q_values::regret::CFRPlus;
+use auction_game::engines::traits::PlayerController;
+use auction_game::models::enums::{GamePhase, Player};
+use auction_game::models::game_state::GameState;
+use std::env;
+use std::fs;
+use std::sync::Arc;
+use std::time::Instant;
+
+fn bid_phase_value(game_state: &GameState, player: Player) -> f32 {
+    let properties: u16 = game_state
+        .get_player_properties(player)
+        .iter()
+        .map(|&property| property as u16)
+        .sum();
+    game_state.get_player_coins(player) as f32 + properties as f32 * VALUE_PER_PROPERTY
+}
+
+fn main() {
+    // Trains the abstracted bid strategy offline, checks it survives a round trip through a
+    // file in the temp dir, then plays bid phases with it in seat 0 against MaxN searching one
+    // round ahead in every other seat
+    let no_players: u8 = 6;
+    let training_deals: usize = 10;
+    let iterations_per_deal: usize = 20000;
+    let no_games: usize = 6;
+
+    let start = Instant::now();
+    let mut cfr = CFR::new(Arc::new(CFRPlus {}), false);
+    for deal in 0..training_deals {
+        let mut game_state = GameState::starting(no_players, deal as u8 % no_players);
+        game_state.reveal_auction();
+        cfr.find_nash(&game_state, iterations_per_deal);
+        println!(
+            "Deal {}: {} information sets root value: {:?}",
+            deal + 1,
+            cfr.no_information_sets(),
+            cfr.root_value()
+        );
+    }
+    let path = env::temp_dir().join("test_cfr_bidder.cfr");
+    cfr.save(&path).expect("Failed to save CFR table");
+    let loaded = CFR::new(Arc::new(CFRPlus {}), false)
+        .load(&path)
+        .expect("Failed to load CFR table");
+    fs::remove_file(&path).expect("Failed to remove CFR table");
+    assert_eq!(loaded.no_information_sets(), cfr.no_information_sets());
+    let cfr = loaded;
+    println!(
+        "Training took: {:?} for {} information sets",
+        start.elapsed(),
+        cfr.no_information_sets()
+    );
+
+    let mut bidder = CFRBidder::with_cfr(0, "CFR".to_string(), cfr).with_iterations(5000, 300);
+    let mut maxn_players: Vec<MaxNPlayer> = (1..no_players)
+        .map(|player| MaxNPlayer::new(player, format!("MaxN {}", player), false, false))
+        .collect();
+    let mut cfr_total: f32 = 0.0;
+    let mut maxn_total: f32 = 0.0;
+    for game in 0..no_games {
+        let mut game_state = GameState::starting(no_players, game as u8 % no_players);
+        game_state.reveal_auction();
+        while game_state.game_phase() == GamePhase::Bid {
+            if game_state.auction_end() {
+                game_state = game_state.generate_next_state_reveal();
+                continue;
+            }
+            let player = game_state.current_player();
+            let action = if player == 0 {
+                bidder.decision(&game_state)
+            } else {
+                maxn_players[player as usize - 1].maximax_round(&game_state, 1, false, 0)
+            };
+            game_state = game_state.manual_next_state_bid(player, action);
+        }
+        let cfr_value = bid_phase_value(&game_state, 0);
+        let maxn_value: f32 = (1..no_players)
+            .map(|player| bid_phase_value(&game_state, player))
+            .sum::<f32>()
+            / (no_players - 1) as f32;
+        cfr_total += cfr_value;
+        maxn_total += maxn_value;
+        println!(
+            "Game {}: CFR {:.2} | MaxN average {:.2}",
+            game + 1,
+            cfr_value,
+            maxn_value
+        );
+    }
+    println!(
+        "Average bid phase value over {} games: CFR {:.2} | MaxN {:.2}",
+        no_games,
+        cfr_total / no_games as f32,
+        maxn_total / no_games as f32
+    );
+    println!(
+        "Information sets after play: {}",
+        bidder.cfr().no_information_sets()
+    );
+}
@@ -0,0 +1,116 @@
+use auction_game::engines::algorithms::counterfactual_regret::CFR;
+use auction_game::engines::controllers::random_player::RandomPlayer;
+use auction_game::engines::q_values::regret::CFRPlus;
+use auction_game::engines::strategies::average::StrategyKind;
+use auction_game::engines::tablebases::sell_tablebase::SellTablebase;
+use auction_game::engines::traits::PlayerController;
+use auction_game::models::game_state::GameState;
+use rand::thread_rng;
+use std::env;
+use std::fs;
+use std::sync::Arc;
+
+fn main() {
+    // Saves a solved sell phase ending and loads it into a fresh solver, which has to play the same
+    // strategy from the same iteration
+    let no_players: u8 = 4;
+    let mut controllers: Vec<RandomPlayer> = (0..no_players)
+        .map(|id| RandomPlayer::new(id, format!("Player_{id}")))
+        .collect();
+    let mut game_state = GameState::starting(no_players, 0);
+    game_state.reveal_auction();
+    while !game_state.bid_phase_end() {
+        let current_player = game_state.current_player();
+        let action = controllers[current_player as usize].decision(&game_state);
+        game_state = game_state.generate_next_state_bid(current_player, action);
+    }
+    // Random sales until three rounds remain keeps the table small
+    while game_state.get_player_properties(0).len() > 3 || game_state.auction_end() {
+        game_state = if game_state.auction_end() {
+            game_state.generate_next_state_reveal()
+        } else {
+            let sales: Vec<u8> = (0..no_players)
+                .map(|player| controllers[player as usize].decision(&game_state))
+                .collect();
+            game_state.manual_next_state_sell(sales)
+        };
+    }
+    let solver = || CFR::new(Arc::new(CFRPlus {}), false);
+    assert_eq!(
+        solver().rule_set_fingerprint(),
+        solver().rule_set_fingerprint(),
+        "The rule set fingerprint has to be the same for every solver"
+    );
+    let mut cfr = solver();
+    cfr.find_nash(&game_state, 50);
+    let path = env::temp_dir().join("test_cfr_tables.cfr");
+    cfr.save(&path).expect("Failed to save CFR table");
+    let loaded = solver().load(&path).expect("Failed to load CFR table");
+    fs::remove_file(&path).expect("Failed to remove CFR table");
+    assert_eq!(loaded.iteration(), cfr.iteration());
+    for player in 0..no_players {
+        for kind in [StrategyKind::Current, StrategyKind::Average] {
+            let (moves, strategy) = cfr.policy(&game_state, player, kind);
+            let (loaded_moves, loaded_strategy) = loaded.policy(&game_state, player, kind);
+            assert_eq!(moves, loaded_moves);
+            for (probability, loaded_probability) in strategy.iter().zip(loaded_strategy.iter()) {
+                assert!(
+                    (probability - loaded_probability).abs() < 1e-5,
+                    "Player {player} {kind:?} strategy changed from {strategy:?} to {loaded_strategy:?}"
+                );
+            }
+        }
+    }
+    println!(
+        "Saved and loaded {} iterations, every root strategy matched",
+        loaded.iteration()
+    );
+
+    // The tablebase has to come back entry for entry and refuse a file changed on disk, solved
+    // from a round later to keep it small
+    let sales: Vec<u8> = (0..no_players)
+        .map(|player| controllers[player as usize].decision(&game_state))
+        .collect();
+    let mut game_state = game_state.manual_next_state_sell(sales);
+    game_state = game_state.generate_next_state_reveal();
+    let mut tablebase = SellTablebase::new(2, 1000);
+    tablebase.generate(&game_state);
+    // Every check still to come goes to someone and is drawn evenly from the unseen ones, whatever
+    // the real deck holds
+    let unseen = game_state.unseen_checks();
+    let to_come = game_state.get_auction_pool().iter().sum::<u8>() as f32
+        + game_state.get_remaining_checks().len() as f32 * unseen.iter().sum::<u8>() as f32
+            / unseen.len() as f32;
+    let total: f32 = tablebase.probe(&game_state).unwrap().values.iter().sum();
+    assert!(
+        (total - to_come).abs() < 1e-2,
+        "The tablebase hands out {total} checks instead of {to_come}"
+    );
+    let mut world = game_state.clone();
+    world.determinise(&mut thread_rng());
+    assert!(
+        tablebase.probe(&world).is_some(),
+        "Another deck with the same public state has to hit the same entry"
+    );
+    let path = env::temp_dir().join("test_cfr_tables.tb");
+    tablebase.save(&path).expect("Failed to save tablebase");
+    let loaded = SellTablebase::load(&path).expect("Failed to load tablebase");
+    assert_eq!(loaded.len(), tablebase.len());
+    assert_eq!(loaded.max_rounds(), tablebase.max_rounds());
+    let entry = tablebase.probe(&game_state).unwrap();
+    let loaded_entry = loaded.probe(&game_state).unwrap();
+    assert_eq!(entry.values, loaded_entry.values);
+    assert_eq!(entry.strategies, loaded_entry.strategies);
+    let text = fs::read_to_string(&path).expect("Failed to read tablebase");
+    let tampered = text.replacen('\t', "\t1", 1);
+    fs::write(&path, tampered).expect("Failed to write tablebase");
+    assert!(
+        SellTablebase::load(&path).is_err(),
+        "A changed tablebase has to fail its checksum"
+    );
+    fs::remove_file(&path).expect("Failed to remove tablebase");
+    println!(
+        "Saved and loaded {} tablebase entries, a changed file was refused",
+        loaded.len()
+    );
+}
@@ -0,0 +1,92 @@
+use auction_game::engines::algorithms::best_response::BestResponse;
+use auction_game::engines::algorithms::counterfactual_regret::CFR;
+use auction_game::engines::controllers::random_player::RandomPlayer;
+use auction_game::engines::q_values::regret::CFRPlus;
+use auction_game::engines::traits::PlayerController;
+use auction_game::models::game_state::GameState;
+use std::sync::Arc;
+use std::time::Instant;
+
+fn main() {
+    // Tracks NashConv while CFR solves the first and the second to last sell round of a random game
+    let no_players: u8 = 6;
+    let mut controllers: Vec<RandomPlayer> = (0..no_players)
+        .map(|id| RandomPlayer::new(id, format!("Player_{id}")))
+        .collect();
+    let mut game_state = GameState::starting(no_players, 0);
+    game_state.reveal_auction();
+    while !game_state.bid_phase_end() {
+        let current_player = game_state.current_player();
+        let action = controllers[current_player as usize].decision(&game_state);
+        game_state = game_state.generate_next_state_bid(current_player, action);
+    }
+    if game_state.auction_end() {
+        game_state = game_state.generate_next_state_reveal();
+    }
+    let sell_start = game_state.clone();
+    // Random sales until two rounds remain, where the best response searches to the end
+    while game_state.get_player_properties(0).len() > 2 || game_state.auction_end() {
+        game_state = if game_state.auction_end() {
+            game_state.generate_next_state_reveal()
+        } else {
+            let aggregate_sales: Vec<u8> = (0..no_players)
+                .map(|player| controllers[player as usize].decision(&game_state))
+                .collect();
+            game_state.manual_next_state_sell(aggregate_sales)
+        };
+    }
+    for (name, root, best_response) in [
+        ("Sell start", sell_start, BestResponse::default()),
+        (
+            "Two rounds left",
+            game_state,
+            BestResponse::new(2, 400, 64, 1),
+        ),
+    ] {
+        println!("{name}");
+        {
+            let mut cfr = CFR::new(Arc::new(CFRPlus {}), false)
+                .with_exploitability(best_response.clone(), 50);
+            let start = Instant::now();
+            for _ in 0..4 {
+                // Each call checks the exploitability once, after its 50th iteration
+                cfr.find_nash(&root, 50);
+                let exploitability = cfr
+                    .last_exploitability()
+                    .expect("Exploitability was measured");
+                println!(
+                    "NashConv: {:.3} per player: {:.3} policy values: {:?} after {:?}",
+                    exploitability.nash_conv,
+                    exploitability.per_player(),
+                    exploitability.policy_values,
+                    start.elapsed()
+                );
+            }
+        }
+        let mut cfr =
+            CFR::new(Arc::new(CFRPlus {}), false).with_exploitability(best_response.clone(), 25);
+        let start = Instant::now();
+        let iterations = cfr.find_nash_until(&root, 1.0, 300);
+        println!(
+            "Early stopping at NashConv 1.0 ran {} iterations in {:?}",
+            iterations,
+            start.elapsed()
+        );
+        // Seeded solves sample their checks from the seed too, so they stop at the same place
+        let seeded_runs: Vec<(usize, f32)> = (0..2)
+            .map(|_| {
+                let mut cfr = CFR::new(Arc::new(CFRPlus {}), false)
+                    .with_seed(7)
+                    .with_exploitability(best_response.clone(), 25);
+                let iterations = cfr.find_nash_until(&root, 1.0, 50);
+                let nash_conv = cfr
+                    .last_exploitability()
+                    .expect("Exploitability was measured")
+                    .nash_conv;
+                (iterations, nash_conv)
+            })
+            .collect();
+        assert_eq!(seeded_runs[0], seeded_runs[1]);
+        println!("Seeded runs agree: {:?}", seeded_runs[0]);
+    }
+}
@@ -0,0 +1,166 @@
+use auction_game::engines::controllers::heuristic_player::HeuristicPlayer;
+use auction_game::engines::controllers::random_player::RandomPlayer;
+use auction_game::engines::traits::PlayerController;
+use auction_game::game_modes::observers::RecordObserver;
+use auction_game::game_modes::runner::{GameRunner, PhaseLimit};
+use auction_game::models::game_record::{GameRecord, RecordedMove};
+use auction_game::models::game_state::GameState;
+use auction_game::models::rule_set::RuleSet;
+use auction_game::tournaments::tournament::final_scores;
+use std::env;
+use std::fs;
+
+// Everything a record pins down, the unrevealed decks being left out of it
+fn assert_same_table(recorded: &GameState, played: &GameState, at: &str) {
+    assert_eq!(
+        recorded.get_path_encoding(),
+        played.get_path_encoding(),
+        "{at}: path"
+    );
+    assert_eq!(recorded.game_phase(), played.game_phase(), "{at}: phase");
+    assert_eq!(
+        recorded.current_player(),
+        played.current_player(),
+        "{at}: player"
+    );
+    assert_eq!(recorded.turn_no(), played.turn_no(), "{at}: turn");
+    assert_eq!(recorded.get_coins(), played.get_coins(), "{at}: coins");
+    assert_eq!(
+        recorded.get_auction_pool(),
+        played.get_auction_pool(),
+        "{at}: auction pool"
+    );
+    assert_eq!(
+        recorded.get_active_bids(),
+        played.get_active_bids(),
+        "{at}: bids"
+    );
+    for player in 0..played.no_players() {
+        assert_eq!(
+            recorded.get_player_properties(player),
+            played.get_player_properties(player),
+            "{at}: properties of player {player}"
+        );
+        assert_eq!(
+            recorded.get_player_checks(player),
+            played.get_player_checks(player),
+            "{at}: checks of player {player}"
+        );
+    }
+}
+
+// Plays a recorded game, reads the record back from disk and replays it next to the runner's
+// history. Returns the number of states compared.
+fn round_trip(rule_set: RuleSet, phase_limit: PhaseLimit, seed: u64) -> usize {
+    let no_players = rule_set.no_players();
+    let controllers: Vec<Box<dyn PlayerController>> = (0..no_players)
+        .map(|id| -> Box<dyn PlayerController> {
+            match id % 2 {
+                0 => Box::new(RandomPlayer::new(id, format!("random_{id}"))),
+                _ => Box::new(HeuristicPlayer::new(id, format!("heuristic_{id}"))),
+            }
+        })
+        .collect();
+    let path = env::temp_dir().join(format!("test_game_records_{no_players}_{seed}.fsr"));
+    let mut runner = GameRunner::new(controllers)
+        .with_rule_set(rule_set)
+        .with_seed(seed)
+        .with_random_starting_player(true)
+        .with_phase_limit(phase_limit)
+        .with_observer(Box::new(
+            RecordObserver::new(path.clone()).with_event(format!("test_{seed}")),
+        ));
+    let final_state = runner.run();
+    let record = GameRecord::load(&path).expect("Failed to load the written record");
+    fs::remove_file(&path).expect("Failed to remove the record");
+    let at = format!("{no_players} players {phase_limit:?} seed {seed}");
+
+    assert_eq!(record.rule_set, rule_set, "{at}: rule set");
+    assert_eq!(record.seed, Some(seed), "{at}: seed");
+    assert_eq!(record.players, runner.nicknames(), "{at}: players");
+    assert_eq!(
+        GameRecord::parse(&record.to_string()).as_ref(),
+        Ok(&record),
+        "{at}: a record has to read back as written"
+    );
+    match final_state.game_end() {
+        true => assert_eq!(
+            record.result,
+            Some(final_scores(&final_state)),
+            "{at}: result"
+        ),
+        false => assert_eq!(record.result, None, "{at}: unfinished result"),
+    }
+
+    let states = record
+        .states()
+        .unwrap_or_else(|error| panic!("{at}: {error}"));
+    let history = runner.history();
+    // A sell only game starts its history after the random bids, which the record still holds
+    assert!(
+        states.len() >= history.len(),
+        "{at}: {} recorded states for {} played",
+        states.len(),
+        history.len()
+    );
+    if phase_limit != PhaseLimit::SellOnly {
+        assert_eq!(states.len(), history.len(), "{at}: state count");
+    }
+    let skipped = states.len() - history.len();
+    for (state_no, (recorded, played)) in states[skipped..].iter().zip(history).enumerate() {
+        assert_same_table(recorded, played, &format!("{at} state {state_no}"));
+    }
+    history.len()
+}
+
+fn main() {
+    let mut games = 0;
+    let mut compared = 0;
+    for no_players in 3..=6 {
+        for phase_limit in [PhaseLimit::Full, PhaseLimit::BidOnly, PhaseLimit::SellOnly] {
+            for seed in 0..3 {
+                compared += round_trip(RuleSet::standard(no_players), phase_limit, seed);
+                games += 1;
+            }
+        }
+    }
+    // Starting coins are a header of their own
+    compared += round_trip(
+        RuleSet::standard(4).with_starting_coins(10),
+        PhaseLimit::Full,
+        7,
+    );
+    games += 1;
+    println!("Replayed {games} written records, {compared} states matched the games played");
+
+    // Seats past the player count are refused wherever the count is given
+    let header = "[Players \"4\"]\n[Start \"P1\"]\n";
+    assert!(GameRecord::parse(header).is_ok());
+    for text in [
+        "[Players \"4\"]\n[Start \"P5\"]\n",
+        "[Start \"P5\"]\n[Players \"4\"]\n",
+        "[Players \"4\"]\n[P5 \"random\"]\n",
+        "[P5 \"random\"]\n[Players \"3\"]\n",
+        "[Players \"4\"]\n[Start \"P0\"]\n",
+    ] {
+        assert!(GameRecord::parse(text).is_err(), "{text} has to be refused");
+    }
+    let mut record = GameRecord::parse(header).unwrap();
+    record.starting_player = 4;
+    assert!(
+        record.states().is_err(),
+        "A record built by hand is checked too"
+    );
+    record.starting_player = 0;
+    record.moves = vec![
+        RecordedMove::Reveal(vec![1, 2, 3, 3]),
+        RecordedMove::Bid {
+            player: 7,
+            action: 1,
+        },
+    ];
+    assert!(record.states().is_err());
+    record.moves = vec![RecordedMove::Sales(vec![1, 2, 3, 4])];
+    assert!(record.states().is_err());
+    println!("Malformed records are refused rather than replayed");
+}
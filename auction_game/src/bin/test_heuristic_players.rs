@@ -0,0 +1,77 @@
+use auction_game::engines::controllers::heuristic_player::{HeuristicPersonality, HeuristicPlayer};
+use auction_game::engines::controllers::random_player::RandomPlayer;
+use auction_game::engines::traits::PlayerController;
+use auction_game::models::enums::GamePhase;
+use auction_game::models::game_state::GameState;
+
+fn play_game(controllers: &mut [Box<dyn PlayerController>]) -> Vec<u8> {
+    let no_players = controllers.len() as u8;
+    let mut game_state = GameState::starting(no_players, 0);
+    game_state.reveal_auction();
+    while !game_state.game_end() {
+        if game_state.auction_end() {
+            game_state = game_state.generate_next_state_reveal();
+            continue;
+        }
+        game_state = match game_state.game_phase() {
+            GamePhase::Bid => {
+                let player = game_state.current_player();
+                let action = controllers[player as usize].decision(&game_state);
+                game_state.generate_next_state_bid(player, action)
+            }
+            GamePhase::Sell => {
+                let sales: Vec<u8> = controllers
+                    .iter_mut()
+                    .map(|controller| controller.decision(&game_state))
+                    .collect();
+                game_state.manual_next_state_sell(sales)
+            }
+        };
+    }
+    (0..no_players)
+        .map(|player| {
+            game_state.get_player_checks(player).iter().sum::<u8>()
+                + game_state.get_player_coins(player)
+        })
+        .collect()
+}
+
+fn main() {
+    // Every preset and a random player, rotated through the seats
+    let games: usize = 120;
+    let mut names: Vec<String> = HeuristicPersonality::preset_names()
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    names.push("random".to_string());
+    names.push("balanced".to_string());
+    let no_players = names.len();
+    let mut totals: Vec<u32> = vec![0; no_players];
+    for game in 0..games {
+        let mut controllers: Vec<Box<dyn PlayerController>> = (0..no_players)
+            .map(|seat| {
+                let entrant = (seat + game) % no_players;
+                let id = seat as u8;
+                let nickname = format!("{}_{}", names[entrant], seat);
+                match HeuristicPersonality::preset(&names[entrant]) {
+                    Some(personality) => {
+                        Box::new(HeuristicPlayer::new(id, nickname).with_personality(personality))
+                            as Box<dyn PlayerController>
+                    }
+                    None => Box::new(RandomPlayer::new(id, nickname)),
+                }
+            })
+            .collect();
+        for (seat, score) in play_game(&mut controllers).iter().enumerate() {
+            totals[(seat + game) % no_players] += *score as u32;
+        }
+    }
+    for (entrant, name) in names.iter().enumerate() {
+        println!(
+            "{:<12} entrant {} averaged {:.1}",
+            name,
+            entrant,
+            totals[entrant] as f32 / games as f32
+        );
+    }
+}
@@ -14,8 +14,8 @@ fn test_maximax_round() {
     let mut game_state = GameState::starting(no_players, 0);
     game_state.reveal_auction_manual(vec![1, 2, 3, 4, 5, 30]);
     info!("Initial GameState: {}", game_state);
-    let mut player = MaxNPlayer::new(0, "Bob".to_string());
-    let output = player.maximax_round(&game_state, 1, true, 1, true);
+    let mut player = MaxNPlayer::new(0, "Bob".to_string(), false, true);
+    let output = player.maximax_round(&game_state, 1, true, 1);
     info!("Best move is: {}", output);
     info!("END");
 }
@@ -0,0 +1,23 @@
+use auction_game::engines::controllers::mcts_player::MCTSPlayer;
+use auction_game::engines::controllers::random_player::RandomPlayer;
+use auction_game::engines::traits::PlayerController;
+use auction_game::game_modes::standard::StandardGame;
+use auction_game::game_modes::traits::Game;
+use log::LevelFilter;
+
+fn main() {
+    // TODO: Compare against MaxN once bots can be rated
+    let no_players: u8 = 6;
+    let mut controllers: Vec<Box<dyn PlayerController>> = Vec::with_capacity(no_players as usize);
+    controllers.push(Box::new(MCTSPlayer::new(0, "MCTS".to_string())));
+    for id in 1..no_players {
+        controllers.push(Box::new(RandomPlayer::new(id, format!("Player_{id}"))));
+    }
+    let mut game = StandardGame::new(
+        "test_mcts".to_string(),
+        LevelFilter::Info,
+        controllers,
+        true,
+    );
+    game.game_run();
+}
@@ -0,0 +1,114 @@
+use auction_game::engines::algorithms::ismcts::{SearchBudget, ISMCTS};
+use auction_game::engines::controllers::mcts_player::MCTSPlayer;
+use auction_game::engines::controllers::random_player::RandomPlayer;
+use auction_game::engines::opponent_models::observed_play::ObservedPlay;
+use auction_game::engines::opponent_models::threshold_model::ThresholdModel;
+use auction_game::engines::rollouts::random_rollout::RandomRollout;
+use auction_game::engines::traits::PlayerController;
+use auction_game::models::enums::GamePhase;
+use auction_game::models::game_state::GameState;
+use std::sync::Arc;
+
+// Plays one game, telling every controller about every move, and returns the final scores
+fn play_game(controllers: &mut [Box<dyn PlayerController>]) -> Vec<u8> {
+    let no_players = controllers.len() as u8;
+    let mut game_state = GameState::starting(no_players, 0);
+    game_state.reveal_auction();
+    while !game_state.game_end() {
+        if game_state.auction_end() {
+            game_state = game_state.generate_next_state_reveal();
+            continue;
+        }
+        game_state = match game_state.game_phase() {
+            GamePhase::Bid => {
+                let player = game_state.current_player();
+                let action = controllers[player as usize].decision(&game_state);
+                for controller in controllers.iter_mut() {
+                    controller.observe_bid(&game_state, player, action);
+                }
+                game_state.generate_next_state_bid(player, action)
+            }
+            GamePhase::Sell => {
+                let sales: Vec<u8> = controllers
+                    .iter_mut()
+                    .map(|controller| controller.decision(&game_state))
+                    .collect();
+                for controller in controllers.iter_mut() {
+                    controller.observe_sales(&game_state, &sales);
+                }
+                game_state.manual_next_state_sell(sales)
+            }
+        };
+    }
+    (0..no_players)
+        .map(|player| {
+            game_state.get_player_checks(player).iter().sum::<u8>()
+                + game_state.get_player_coins(player)
+        })
+        .collect()
+}
+
+fn main() {
+    let no_players: u8 = 6;
+    let games: usize = 4;
+    // Fits the model to random players and compares it with the prior
+    let mut observed_play = ObservedPlay::new(no_players);
+    let prior = ThresholdModel::fit(&observed_play);
+    for _ in 0..20 {
+        let mut game_state = GameState::starting(no_players, 0);
+        game_state.reveal_auction();
+        let mut controllers: Vec<RandomPlayer> = (0..no_players)
+            .map(|id| RandomPlayer::new(id, format!("Random_{id}")))
+            .collect();
+        while !game_state.bid_phase_end() {
+            let player = game_state.current_player();
+            let action = controllers[player as usize].decision(&game_state);
+            observed_play.observe_bid(&game_state, player, action);
+            game_state = game_state.generate_next_state_bid(player, action);
+        }
+    }
+    let fitted = ThresholdModel::fit(&observed_play);
+    println!(
+        "Prior pass threshold with 5 to gain and 14 coins: {:.2}",
+        prior.bid_model(0).pass_threshold(5.0, 14)
+    );
+    for player in 0..no_players {
+        let model = fitted.bid_model(player);
+        println!(
+            "P{} from {} bids: pass threshold {:.2}, mean extra raise {:.2}",
+            player,
+            model.observations(),
+            model.pass_threshold(5.0, 14),
+            model.mean_extra_raise()
+        );
+    }
+
+    // MCTS with and without opponent modelling against random players
+    for modelled in [false, true] {
+        let mut total: u32 = 0;
+        for _ in 0..games {
+            let search = ISMCTS::new(
+                Arc::new(RandomRollout {}),
+                SearchBudget::Iterations(1000),
+                0.7,
+                1,
+                None,
+                false,
+            );
+            let mut player = MCTSPlayer::with_search(0, "MCTS".to_string(), search);
+            if modelled {
+                player = player.with_opponent_modelling(no_players);
+            }
+            let mut controllers: Vec<Box<dyn PlayerController>> = vec![Box::new(player)];
+            for id in 1..no_players {
+                controllers.push(Box::new(RandomPlayer::new(id, format!("Random_{id}"))));
+            }
+            total += play_game(&mut controllers)[0] as u32;
+        }
+        println!(
+            "MCTS {} opponent modelling averaged {:.1} against random players",
+            if modelled { "with" } else { "without" },
+            total as f32 / games as f32
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use auction_game::engines::controllers::random_player::RandomPlayer;
+use auction_game::engines::traits::PlayerController;
+use auction_game::game_modes::observers::RecordObserver;
+use auction_game::game_modes::referee::{is_legal, pass_move, Fallback, Referee, Violation};
+use auction_game::game_modes::runner::{GameRunner, SeatStatus};
+use auction_game::models::enums::{Coins, Player};
+use auction_game::models::game_record::{GameRecord, RecordedMove};
+use auction_game::models::game_state::GameState;
+use auction_game::tournaments::tournament::{play_seeded, Entrant};
+use std::env;
+use std::fs;
+use std::panic;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Copy, Clone, Debug)]
+enum Misbehaviour {
+    IllegalMove,
+    PanicOnDecision,
+    // Moves legally but panics when told about another seat's bid
+    PanicOnObserve,
+    // Sleeps before every legal move
+    Slow(Duration),
+}
+
+// Random legal moves spoiled in one way
+struct Misbehaving {
+    random: RandomPlayer,
+    misbehaviour: Misbehaviour,
+}
+
+impl Misbehaving {
+    fn new(id: Player, misbehaviour: Misbehaviour) -> Self {
+        Misbehaving {
+            random: RandomPlayer::new(id, format!("misbehaving_{id}")),
+            misbehaviour,
+        }
+    }
+}
+
+impl PlayerController for Misbehaving {
+    fn nickname(&self) -> String {
+        self.random.nickname()
+    }
+    fn decision(&mut self, game_state: &GameState) -> u8 {
+        match self.misbehaviour {
+            Misbehaviour::IllegalMove => u8::MAX,
+            Misbehaviour::PanicOnDecision => panic!("misbehaving on purpose"),
+            Misbehaviour::PanicOnObserve => self.random.decision(game_state),
+            Misbehaviour::Slow(delay) => {
+                thread::sleep(delay);
+                self.random.decision(game_state)
+            }
+        }
+    }
+    fn observe_bid(&mut self, _game_state: &GameState, _player: Player, _action: Coins) {
+        if let Misbehaviour::PanicOnObserve = self.misbehaviour {
+            panic!("misbehaving on purpose");
+        }
+    }
+}
+
+// Seat 0 misbehaves, the others move at random
+fn misbehaving_runner(
+    no_players: u8,
+    misbehaviour: Misbehaviour,
+    referee: Referee,
+    seed: u64,
+) -> GameRunner {
+    let controllers: Vec<Box<dyn PlayerController>> = (0..no_players)
+        .map(|id| -> Box<dyn PlayerController> {
+            match id {
+                0 => Box::new(Misbehaving::new(id, misbehaviour)),
+                _ => Box::new(RandomPlayer::new(id, format!("random_{id}"))),
+            }
+        })
+        .collect();
+    GameRunner::new(controllers)
+        .with_seed(seed)
+        .with_referee(referee)
+}
+
+// Every illegal move becomes an incident and the fallback move is played in its place
+fn check_fallbacks() {
+    for fallback in [Fallback::Pass, Fallback::RandomMove] {
+        let referee = Referee::default().with_fallback(fallback);
+        let mut runner = misbehaving_runner(4, Misbehaviour::IllegalMove, referee, 3);
+        let game_state = runner.run();
+        assert!(game_state.game_end());
+        assert!(!runner.incidents().is_empty());
+        for incident in runner.incidents() {
+            assert_eq!(incident.player, 0);
+            assert_eq!(incident.violation, Violation::IllegalMove(u8::MAX));
+            assert_eq!(incident.fallback, fallback);
+            let played = runner
+                .history()
+                .iter()
+                .find(|game_state| game_state.turn_no() == incident.turn)
+                .expect("No state for the incident's turn");
+            let replacement = incident.replacement.expect("A fallback move was played");
+            assert!(is_legal(played, 0, replacement));
+            if fallback == Fallback::Pass {
+                assert_eq!(replacement, pass_move(played, 0));
+            }
+        }
+        assert_eq!(runner.seat_status()[0], SeatStatus::Playing);
+    }
+
+    let referee = Referee::default().with_fallback(Fallback::RandomMove);
+    let mut runner = misbehaving_runner(4, Misbehaviour::PanicOnDecision, referee, 5);
+    assert!(runner.run().game_end());
+    assert!(runner.incidents().iter().all(|incident| incident.violation
+        == Violation::Panic("misbehaving on purpose".to_string())
+        && incident.replacement.is_some()));
+
+    // Panics while being told about a move replace nothing
+    let mut runner = misbehaving_runner(4, Misbehaviour::PanicOnObserve, Referee::default(), 5);
+    assert!(runner.run().game_end());
+    assert!(!runner.incidents().is_empty());
+    assert!(runner
+        .incidents()
+        .iter()
+        .all(|incident| incident.replacement.is_none()));
+}
+
+// A forfeiting seat is penalised once, passes from then on and scores 0 in tournaments
+fn check_forfeit() {
+    let referee = Referee::default().with_fallback(Fallback::Forfeit);
+    let mut runner = misbehaving_runner(4, Misbehaviour::IllegalMove, referee, 9);
+    assert!(runner.run().game_end());
+    assert_eq!(runner.incidents().len(), 1);
+    assert_eq!(runner.seat_status()[0], SeatStatus::Forfeited);
+    assert_eq!(runner.forfeits(), vec![true, false, false, false]);
+
+    let entrants = vec![
+        Entrant::new(
+            "misbehaving".to_string(),
+            Arc::new(|id, _| Box::new(Misbehaving::new(id, Misbehaviour::IllegalMove))),
+        ),
+        Entrant::new(
+            "random".to_string(),
+            Arc::new(|id, _| Box::new(RandomPlayer::new(id, format!("random_{id}")))),
+        ),
+    ];
+    let result = play_seeded(&entrants, 9, vec![1, 0, 1, 1], referee);
+    assert_eq!(result.scores[1], 0, "A forfeited seat has to score 0");
+    assert!(result
+        .scores
+        .iter()
+        .enumerate()
+        .all(|(seat, &score)| seat == 1 || score > 0));
+    let result = play_seeded(&entrants, 9, vec![1, 0, 1, 1], Referee::default());
+    assert!(result.scores[1] > 0, "Passing for a seat is not a forfeit");
+}
+
+// A seat over its time is retired, its moves made by the fallback for the rest of the game
+fn check_time_limits() {
+    let referee = Referee::default().with_move_time_limit(Duration::from_millis(20));
+    let slow = Misbehaviour::Slow(Duration::from_millis(200));
+    let mut runner = misbehaving_runner(3, slow, referee, 1);
+    assert!(runner.run().game_end());
+    assert_eq!(runner.incidents().len(), 1);
+    assert!(matches!(
+        runner.incidents()[0].violation,
+        Violation::MoveTimeout { .. }
+    ));
+    assert_eq!(runner.seat_status()[0], SeatStatus::Retired);
+
+    let referee = Referee::default().with_game_time_budget(Duration::from_millis(50));
+    let slow = Misbehaviour::Slow(Duration::from_millis(10));
+    let mut runner = misbehaving_runner(3, slow, referee, 1);
+    assert!(runner.run().game_end());
+    assert_eq!(runner.incidents().len(), 1);
+    assert!(matches!(
+        runner.incidents()[0].violation,
+        Violation::GameTimeout { .. }
+    ));
+    assert_eq!(runner.seat_status()[0], SeatStatus::Retired);
+    assert!(runner.time_used()[0] >= Duration::from_millis(50));
+
+    // Within the limits nothing happens
+    let referee = Referee::default()
+        .with_move_time_limit(Duration::from_secs(5))
+        .with_game_time_budget(Duration::from_secs(60));
+    let mut runner = misbehaving_runner(3, Misbehaviour::Slow(Duration::ZERO), referee, 1);
+    assert!(runner.run().game_end());
+    assert!(runner.incidents().is_empty());
+    assert!(runner
+        .seat_status()
+        .iter()
+        .all(|&status| status == SeatStatus::Playing));
+}
+
+// Incidents reach the record as comments, in the order they happened
+fn check_recorded_incidents() {
+    let path = env::temp_dir().join("test_referee.fsr");
+    let referee = Referee::default().with_fallback(Fallback::RandomMove);
+    let mut runner = misbehaving_runner(5, Misbehaviour::IllegalMove, referee, 2)
+        .with_observer(Box::new(RecordObserver::new(path.clone())));
+    runner.run();
+    let record = GameRecord::load(&path).expect("Failed to load the record");
+    fs::remove_file(&path).expect("Failed to remove the record");
+    let comments: Vec<String> = record
+        .moves
+        .iter()
+        .filter_map(|recorded_move| match recorded_move {
+            RecordedMove::Comment(comment) => Some(comment.clone()),
+            _ => None,
+        })
+        .collect();
+    let incidents: Vec<String> = runner
+        .incidents()
+        .iter()
+        .map(|incident| incident.to_string())
+        .collect();
+    assert_eq!(comments, incidents);
+    record.states().expect("Fallback moves have to replay");
+}
+
+fn main() {
+    // Keeps the deliberate panics out of the output
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if info.payload().downcast_ref::<&str>() != Some(&"misbehaving on purpose") {
+            default_hook(info);
+        }
+    }));
+    check_fallbacks();
+    println!("Illegal moves and panics are replaced by the fallback");
+    check_forfeit();
+    println!("Forfeits pass out the game and score 0");
+    check_time_limits();
+    println!("Seats over their move limit or game budget are retired");
+    check_recorded_incidents();
+    println!("Incidents are recorded as comments");
+}
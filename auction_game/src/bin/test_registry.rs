@@ -0,0 +1,153 @@
+use auction_game::engines::controllers::heuristic_player::{HeuristicPersonality, SellPolicy};
+use auction_game::engines::registry::{heuristic_personality, ControllerRegistry};
+use auction_game::engines::tablebases::sell_tablebase::SellTablebase;
+use std::env;
+use std::fs;
+
+// Personality the registry builds for spec, which has to parse
+fn personality(registry: &ControllerRegistry, spec: &str) -> HeuristicPersonality {
+    let spec = registry
+        .parse(spec)
+        .unwrap_or_else(|error| panic!("{spec}: {error}"));
+    heuristic_personality(&spec)
+}
+
+fn assert_rejected(registry: &ControllerRegistry, spec: &str, expected: &str) {
+    match registry.parse(spec) {
+        Ok(_) => panic!("{spec} has to be rejected"),
+        Err(error) => assert!(
+            error.contains(expected),
+            "{spec} was rejected with '{error}', expected it to mention '{expected}'"
+        ),
+    }
+}
+
+fn main() {
+    let registry = ControllerRegistry::default();
+
+    // Every bot builds from its defaults for every player count
+    for bot in registry.bots() {
+        let spec = registry
+            .parse(bot.name)
+            .unwrap_or_else(|error| panic!("{}: {error}", bot.name));
+        assert_eq!(spec.bot(), bot.name);
+        assert!(bot.params.iter().all(|param| !spec.is_given(param.name)));
+        for param in bot.params.iter() {
+            assert_eq!(spec.text(param.name), param.default);
+        }
+        for no_players in 3..=6 {
+            for id in 0..no_players {
+                registry
+                    .build(bot.name, id, no_players)
+                    .unwrap_or_else(|error| panic!("{}: {error}", bot.name));
+            }
+        }
+    }
+    println!(
+        "All {} bots build from their defaults",
+        registry.bots().len()
+    );
+
+    let spec = registry
+        .parse(" mcts:iters=200 , rollout=greedy,beliefs=true")
+        .unwrap();
+    assert_eq!(spec.spec(), "mcts:iters=200 , rollout=greedy,beliefs=true");
+    assert_eq!(spec.value::<u32>("iters"), 200);
+    assert_eq!(spec.text("rollout"), "greedy");
+    assert!(spec.value::<bool>("beliefs"));
+    assert!(!spec.is_given("threads"));
+    assert_eq!(spec.value::<u32>("threads"), 1);
+    assert_eq!(spec.entrant().name(), spec.spec());
+
+    assert_rejected(&registry, "alphazero", "Unknown bot 'alphazero'");
+    assert_rejected(&registry, "mcts:depth=3", "no parameter 'depth'");
+    assert_rejected(&registry, "mcts:iters", "Expected key=value");
+    assert_rejected(&registry, "mcts:iters=many", "expects a whole number");
+    assert_rejected(&registry, "mcts:iters=-1", "expects a whole number");
+    assert_rejected(&registry, "mcts:exploration=inf", "expects a number");
+    assert_rejected(&registry, "mcts:beliefs=yes", "expects true or false");
+    assert_rejected(
+        &registry,
+        "mcts:rollout=smart",
+        "expects one of random, greedy",
+    );
+    assert_rejected(&registry, "mcts:iters=1,iters=2", "given twice");
+    assert_rejected(
+        &registry,
+        "mcts:iters=500,time_ms=100",
+        "iters and time_ms are alternative budgets",
+    );
+    registry.parse("mcts:time_ms=100").unwrap();
+    println!("Malformed specs are rejected");
+
+    // Knobs not given keep the preset's, given ones override it
+    for name in HeuristicPersonality::preset_names() {
+        let preset = HeuristicPersonality::preset(name).unwrap();
+        let built = personality(&registry, &format!("heuristic:preset={name}"));
+        assert_eq!(built.max_price_per_value(), preset.max_price_per_value());
+        assert_eq!(built.min_gain(), preset.min_gain());
+        assert_eq!(
+            built.coin_reserve_per_round(),
+            preset.coin_reserve_per_round()
+        );
+        assert_eq!(built.raise_step(), preset.raise_step());
+        assert_eq!(built.sell_policy(), preset.sell_policy());
+        let built = personality(
+            &registry,
+            &format!("heuristic:preset={name},max_price=0.5,raise=2"),
+        );
+        assert_eq!(built.max_price_per_value(), 0.5);
+        assert_eq!(built.raise_step(), 2);
+        assert_eq!(built.min_gain(), preset.min_gain());
+    }
+    assert_eq!(
+        personality(&registry, "heuristic").sell_policy(),
+        HeuristicPersonality::default().sell_policy()
+    );
+    assert_eq!(
+        personality(&registry, "heuristic:sell=cheapest").sell_policy(),
+        SellPolicy::Cheapest
+    );
+    println!("Heuristic presets keep the knobs not given");
+
+    // threshold reaches a threshold policy whether it was given or came with the preset
+    assert_eq!(
+        personality(&registry, "heuristic:sell=threshold").sell_policy(),
+        SellPolicy::Threshold(12)
+    );
+    assert_eq!(
+        personality(&registry, "heuristic:sell=threshold,threshold=9").sell_policy(),
+        SellPolicy::Threshold(9)
+    );
+    assert_eq!(
+        personality(&registry, "heuristic:preset=aggressive,threshold=10").sell_policy(),
+        SellPolicy::Threshold(10)
+    );
+    assert_eq!(
+        personality(&registry, "heuristic:preset=aggressive,sell=cheapest").sell_policy(),
+        SellPolicy::Cheapest
+    );
+    for spec in [
+        "heuristic:threshold=10",
+        "heuristic:preset=cautious,threshold=10",
+        "heuristic:sell=matched,threshold=10",
+        "heuristic:preset=aggressive,sell=cheapest,threshold=10",
+    ] {
+        assert_rejected(&registry, spec, "threshold needs sell=threshold");
+    }
+    println!("threshold applies to threshold sell policies and is rejected elsewhere");
+
+    // A tablebase has to load when the spec is parsed, not when a seat is built
+    assert_rejected(&registry, "maxn:tablebase=", "expects a file path");
+    let path = env::temp_dir().join("test_registry.tb");
+    let spec = format!("maxn:tablebase={}", path.display());
+    assert_rejected(&registry, &spec, "maxn tablebase");
+    SellTablebase::default()
+        .save(&path)
+        .expect("Failed to save tablebase");
+    registry
+        .build(&spec, 0, 6)
+        .unwrap_or_else(|error| panic!("{spec}: {error}"));
+    fs::remove_file(&path).expect("Failed to remove tablebase");
+    println!("maxn loads a tablebase it is given");
+}
@@ -0,0 +1,76 @@
+use auction_game::engines::controllers::random_player::RandomPlayer;
+use auction_game::engines::traits::PlayerController;
+use auction_game::game_modes::runner::{GameRunner, PhaseLimit};
+use auction_game::models::enums::GamePhase;
+
+fn random_runner(no_players: u8, seed: u64) -> GameRunner {
+    let controllers: Vec<Box<dyn PlayerController>> = (0..no_players)
+        .map(|id| -> Box<dyn PlayerController> {
+            Box::new(RandomPlayer::new(id, format!("random_{id}")))
+        })
+        .collect();
+    GameRunner::new(controllers)
+        .with_seed(seed)
+        .with_random_starting_player(true)
+}
+
+// The same seed has to deal the same cards and pick the same starting player
+fn check_seeded_runs() {
+    for no_players in 3..=6 {
+        let mut first = random_runner(no_players, 11);
+        let mut second = random_runner(no_players, 11);
+        assert!(first.run().game_end());
+        assert!(second.run().game_end());
+        assert_eq!(first.played_seed(), Some(11));
+        let (first_deal, second_deal) = (&first.history()[0], &second.history()[0]);
+        assert_eq!(
+            first_deal.get_remaining_properties(),
+            second_deal.get_remaining_properties(),
+            "{no_players} players: the same seed dealt different properties"
+        );
+        assert_eq!(
+            first_deal.get_remaining_checks(),
+            second_deal.get_remaining_checks(),
+            "{no_players} players: the same seed dealt different checks"
+        );
+        assert_eq!(
+            first_deal.current_player(),
+            second_deal.current_player(),
+            "{no_players} players: the same seed picked different starting players"
+        );
+    }
+    let mut runner = random_runner(4, 0).with_phase_limit(PhaseLimit::BidOnly);
+    let game_state = runner.run();
+    assert_eq!(game_state.game_phase(), GamePhase::Sell);
+    assert!(!game_state.game_end(), "BidOnly has to stop before selling");
+    let mut runner = random_runner(4, 0).with_phase_limit(PhaseLimit::SellOnly);
+    let game_state = runner.run();
+    assert!(game_state.game_end());
+    assert!(
+        runner
+            .history()
+            .iter()
+            .all(|game_state| game_state.game_phase() == GamePhase::Sell),
+        "SellOnly has to start from the sell phase"
+    );
+}
+
+// A start position is played on from as given
+fn check_start_position() {
+    let mut runner = random_runner(5, 4).with_phase_limit(PhaseLimit::BidOnly);
+    let sell_start = runner.run();
+    let mut runner = random_runner(5, 4).with_start_position(sell_start.clone());
+    assert!(runner.run().game_end());
+    assert_eq!(
+        runner.history()[0].get_path_encoding(),
+        sell_start.get_path_encoding()
+    );
+    assert_eq!(runner.history()[0].get_coins(), sell_start.get_coins());
+}
+
+fn main() {
+    check_seeded_runs();
+    println!("Seeded runs repeat and the phase limits hold");
+    check_start_position();
+    println!("Start positions are played on from");
+}
@@ -2,7 +2,6 @@ use ahash::AHashMap;
 use auction_game::engines::algorithms::maxn_player::MaxNPlayer;
 use auction_game::game_modes::self_play::SelfPlay;
 use auction_game::game_modes::traits::Game;
-use auction_game::models::enums::GamePhase;
 use auction_game::models::game_state::GameState;
 use helper::logger::init_logger;
 use log::{info, LevelFilter};
@@ -25,7 +24,10 @@ fn test_self_play() {
     let no_players: u8 = 6;
     let mut controllers: AHashMap<u8, MaxNPlayer> = AHashMap::with_capacity(no_players as usize);
     for i in 0..no_players {
-        controllers.insert(i, MaxNPlayer::new(i, format!("P{i}").to_string()));
+        controllers.insert(
+            i,
+            MaxNPlayer::new(i, format!("P{i}").to_string(), false, true),
+        );
     }
     let mut game_state = GameState::starting(no_players, 0);
     info!("GameState: {}", game_state);
@@ -41,7 +43,7 @@ fn test_self_play() {
         let current_player = game_state.current_player();
         let mut best_move: u8 = 0;
         if let Some(player_control) = controllers.get_mut(&current_player) {
-            best_move = player_control.maximax_round(&game_state, 1, false, 0, true);
+            best_move = player_control.maximax_round(&game_state, 1, false, 0);
         }
         info!("Player: {} chose to do: {}", current_player + 1, best_move);
         game_state = game_state.generate_next_state_bid(current_player, best_move);
@@ -61,7 +63,10 @@ fn replicate_issue() {
     let no_players: u8 = 6;
     let mut controllers: AHashMap<u8, MaxNPlayer> = AHashMap::with_capacity(no_players as usize);
     for i in 0..no_players {
-        controllers.insert(i, MaxNPlayer::new(i, format!("P{i}").to_string()));
+        controllers.insert(
+            i,
+            MaxNPlayer::new(i, format!("P{i}").to_string(), false, true),
+        );
     }
     let mut game_state = GameState::starting(no_players, 0);
     info!("GameState: {}", game_state);
@@ -72,8 +77,8 @@ fn replicate_issue() {
     game_state = game_state.generate_next_state_bid(3, 0);
     game_state = game_state.generate_next_state_bid(4, 0);
     info!("Initial GameState: {}", game_state);
-    let mut player = MaxNPlayer::new(0, "Bob".to_string());
-    let output = player.maximax_round(&game_state, 1, true, 1, true);
+    let mut player = MaxNPlayer::new(0, "Bob".to_string(), false, true);
+    let output = player.maximax_round(&game_state, 1, true, 1);
     info!("Best move is: {}", output);
     info!("END");
 }
@@ -0,0 +1,33 @@
+use auction_game::engines::controllers::heuristic_player::HeuristicPlayer;
+use auction_game::tournaments::sprt::{Sprt, SprtDecision};
+use auction_game::tournaments::tournament::Entrant;
+use std::sync::Arc;
+
+fn heuristic(name: &str) -> Entrant {
+    Entrant::new(
+        name.to_string(),
+        Arc::new(|id, _| Box::new(HeuristicPlayer::new(id, format!("heuristic_{id}")))),
+    )
+}
+
+fn main() {
+    // A deterministic bot against itself has to score exactly even on every deal, which the
+    // extra seat of an odd player count would break unless the lineup is mirrored too
+    for (no_players, games_per_deal) in [(3, 6), (4, 6), (5, 20)] {
+        let sprt = Sprt::new(heuristic("candidate"), heuristic("baseline"))
+            .with_no_players(no_players)
+            .with_min_deals(100)
+            .with_max_deals(3)
+            .with_seed(5);
+        let status = sprt.run(|status| {
+            assert_eq!(
+                status.score, 0.5,
+                "{no_players} players: a bot has to tie itself"
+            );
+        });
+        assert_eq!(status.deals, 3);
+        assert_eq!(status.games, 3 * games_per_deal, "{no_players} players");
+        assert_eq!(status.decision, SprtDecision::Continue);
+        println!("{no_players} players: {games_per_deal} games per deal, even score");
+    }
+}
@@ -0,0 +1,58 @@
+use crate::models::enums::Coins;
+
+// Keeps passing, the minimum raise and a few larger raises instead of every raise up to the
+// player's wealth. Buckets are offsets above the minimum raise.
+#[derive(Clone, Debug)]
+pub struct BidAbstraction {
+    buckets: Vec<Coins>,
+    include_all_in: bool,
+}
+
+impl BidAbstraction {
+    pub fn new(mut buckets: Vec<Coins>, include_all_in: bool) -> Self {
+        buckets.sort_unstable();
+        buckets.dedup();
+        BidAbstraction {
+            buckets,
+            include_all_in,
+        }
+    }
+    pub fn buckets(&self) -> &Vec<Coins> {
+        &self.buckets
+    }
+    pub fn include_all_in(&self) -> bool {
+        self.include_all_in
+    }
+    // Returns pass first, then raises in increasing size so progressive widening reveals the
+    // cheaper raises before the larger ones
+    pub fn abstract_moves(&self, legal_moves: &[Coins]) -> Vec<Coins> {
+        debug_assert!(
+            !legal_moves.is_empty() && legal_moves[0] == 0,
+            "Bid legal moves should always start with a pass, got: {:?}",
+            legal_moves
+        );
+        if legal_moves.len() <= 2 {
+            return legal_moves.to_vec();
+        }
+        let minimum_raise: Coins = legal_moves[1];
+        let wealth: Coins = legal_moves[legal_moves.len() - 1];
+        let mut moves: Vec<Coins> = Vec::with_capacity(self.buckets.len() + 2);
+        moves.push(0);
+        for &offset in &self.buckets {
+            let raise = minimum_raise.saturating_add(offset);
+            if raise <= wealth && !moves.contains(&raise) {
+                moves.push(raise);
+            }
+        }
+        if self.include_all_in && !moves.contains(&wealth) {
+            moves.push(wealth);
+        }
+        moves
+    }
+}
+
+impl Default for BidAbstraction {
+    fn default() -> Self {
+        BidAbstraction::new(vec![0, 1, 2, 4, 8], true)
+    }
+}
@@ -0,0 +1,86 @@
+use crate::models::enums::{Coins, GamePhase, Player, Property};
+use crate::models::game_state::GameState;
+
+// Groups property values into buckets so bid positions that only differ by similar cards share
+// an information set. The mover's coins and bid and the highest bid stay exact as they decide
+// which raises are legal.
+#[derive(Clone, Debug)]
+pub struct CardAbstraction {
+    bucket_size: Property,
+    holdings_bucket_size: u16,
+    coins_bucket_size: Coins,
+}
+
+impl CardAbstraction {
+    pub fn new(bucket_size: Property, holdings_bucket_size: u16, coins_bucket_size: Coins) -> Self {
+        CardAbstraction {
+            bucket_size: bucket_size.max(1),
+            holdings_bucket_size: holdings_bucket_size.max(1),
+            coins_bucket_size: coins_bucket_size.max(1),
+        }
+    }
+    pub fn bucket_size(&self) -> Property {
+        self.bucket_size
+    }
+    pub fn holdings_bucket_size(&self) -> u16 {
+        self.holdings_bucket_size
+    }
+    pub fn coins_bucket_size(&self) -> Coins {
+        self.coins_bucket_size
+    }
+    pub fn bucket(&self, property: Property) -> u8 {
+        property.saturating_sub(1) / self.bucket_size
+    }
+    // Keyed from the point of view of the player to move so every seat shares the same strategy
+    pub fn bid_information_set(&self, game_state: &GameState) -> String {
+        debug_assert!(
+            game_state.game_phase() == GamePhase::Bid && !game_state.auction_end(),
+            "Bid information sets are only keyed while an auction is in progress"
+        );
+        let no_players = game_state.no_players();
+        let current_player = game_state.current_player();
+        let pool: String = game_state
+            .get_auction_pool()
+            .iter()
+            .map(|&property| self.bucket(property).to_string())
+            .collect();
+        let active_bids = game_state.get_active_bids();
+        let highest_bid = active_bids.iter().max().copied().unwrap_or(0);
+        let holdings = |player: Player| -> u16 {
+            game_state
+                .get_player_properties(player)
+                .iter()
+                .map(|&property| property as u16)
+                .sum()
+        };
+        // Opponents are summarised by how many are still in the auction and how much the richest
+        // of them could still bid, which is what decides whether a raise holds
+        let opponents: Vec<Player> = (0..no_players)
+            .filter(|&player| {
+                player != current_player && game_state.get_active_players()[player as usize]
+            })
+            .collect();
+        let richest_opponent: Coins = opponents
+            .iter()
+            .map(|&player| game_state.get_player_coins(player) + active_bids[player as usize])
+            .max()
+            .unwrap_or(0);
+        format!(
+            "Bid|r{}|a{}|h{}|c{}:{}:{}|o{}:{}",
+            game_state.get_remaining_properties().len(),
+            pool,
+            highest_bid,
+            game_state.get_player_coins(current_player),
+            active_bids[current_player as usize],
+            holdings(current_player) / self.holdings_bucket_size,
+            opponents.len(),
+            richest_opponent / self.coins_bucket_size
+        )
+    }
+}
+
+impl Default for CardAbstraction {
+    fn default() -> Self {
+        CardAbstraction::new(5, 15, 4)
+    }
+}
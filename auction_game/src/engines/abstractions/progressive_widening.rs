@@ -0,0 +1,33 @@
+// Number of children a node may consider grows as coefficient * visits^exponent
+#[derive(Copy, Clone, Debug)]
+pub struct ProgressiveWidening {
+    coefficient: f32,
+    exponent: f32,
+}
+
+impl ProgressiveWidening {
+    pub fn new(coefficient: f32, exponent: f32) -> Self {
+        debug_assert!(
+            coefficient > 0.0,
+            "Progressive widening coefficient must be positive, got {coefficient}"
+        );
+        debug_assert!(
+            (0.0..=1.0).contains(&exponent),
+            "Progressive widening exponent must be in [0, 1], got {exponent}"
+        );
+        ProgressiveWidening {
+            coefficient,
+            exponent,
+        }
+    }
+    pub fn allowed_children(&self, visits: u32, no_children: usize) -> usize {
+        let allowed = (self.coefficient * (visits.max(1) as f32).powf(self.exponent)).ceil();
+        (allowed as usize).clamp(1, no_children.max(1))
+    }
+}
+
+impl Default for ProgressiveWidening {
+    fn default() -> Self {
+        ProgressiveWidening::new(2.0, 0.5)
+    }
+}
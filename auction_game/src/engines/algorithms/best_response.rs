@@ -0,0 +1,306 @@
+use crate::engines::algorithms::counterfactual_regret::CFR;
+use crate::engines::strategies::average::StrategyKind;
+use crate::engines::utils::sample_strategy_with;
+use crate::models::enums::{GamePhase, Player};
+use crate::models::game_state::GameState;
+use ahash::AHashMap;
+use itertools::Itertools;
+use rand::Rng;
+
+#[derive(Clone, Debug)]
+pub struct Exploitability {
+    // Checks each player collects when every player follows the CFR strategies
+    pub policy_values: Vec<f32>,
+    // Checks each player collects by best responding while the others keep their strategies
+    pub best_response_values: Vec<f32>,
+    // Sum of what every player gains by deviating, zero at a Nash equilibrium
+    pub nash_conv: f32,
+}
+
+impl Exploitability {
+    pub fn per_player(&self) -> f32 {
+        self.nash_conv / self.policy_values.len().max(1) as f32
+    }
+}
+
+// Best response to the strategies stored in a CFR table over the sell phase.
+// Holdings are public, so a best response only has to maximise over its own sales against the
+// others' strategies at every public state. Chance reveals, drawn from every check not turned up
+// yet as the ones set aside are as unknown as the deck, and opponents' joint sales are
+// enumerated when there are few enough of them and sampled otherwise from rng. After max_rounds
+// the responder falls back to the strategies and the value is estimated by playouts, so the
+// result is exact once max_rounds covers the rest of the game and every limit holds. Otherwise it is an
+// estimate: deviating for only max_rounds understates what a best response gains, and sampling
+// adds noise. Policy and best response values are taken over the same samples, so the noise
+// does not bias their difference.
+#[derive(Clone, Debug)]
+pub struct BestResponse {
+    max_rounds: u8,
+    chance_samples: usize,
+    opponent_samples: usize,
+    playouts: usize,
+    strategy_kind: StrategyKind,
+}
+
+impl BestResponse {
+    pub fn new(
+        max_rounds: u8,
+        chance_samples: usize,
+        opponent_samples: usize,
+        playouts: usize,
+    ) -> Self {
+        BestResponse {
+            max_rounds,
+            chance_samples: chance_samples.max(1),
+            opponent_samples: opponent_samples.max(1),
+            playouts: playouts.max(1),
+            strategy_kind: StrategyKind::Average,
+        }
+    }
+    // Which of the CFR strategies is evaluated, the average one by default
+    pub fn with_strategy_kind(mut self, strategy_kind: StrategyKind) -> Self {
+        self.strategy_kind = strategy_kind;
+        self
+    }
+
+    pub fn exploitability<R: Rng>(
+        &self,
+        cfr: &CFR,
+        game_state: &GameState,
+        rng: &mut R,
+    ) -> Exploitability {
+        debug_assert!(
+            game_state.game_phase() == GamePhase::Sell,
+            "Best responses are only computed for the sell phase"
+        );
+        let mut policy_values: Vec<f32> = Vec::with_capacity(game_state.no_players() as usize);
+        let mut best_response_values: Vec<f32> =
+            Vec::with_capacity(game_state.no_players() as usize);
+        for player in 0..game_state.no_players() {
+            let (policy_value, best_response_value) =
+                self.values(cfr, game_state, player, self.max_rounds, rng);
+            policy_values.push(policy_value);
+            best_response_values.push(best_response_value);
+        }
+        let nash_conv = best_response_values
+            .iter()
+            .zip(policy_values.iter())
+            .map(|(best_response, policy)| best_response - policy)
+            .sum();
+        Exploitability {
+            policy_values,
+            best_response_values,
+            nash_conv,
+        }
+    }
+
+    // Checks player still collects from game_state following its strategy and best responding,
+    // both over the same sampled reveals, opponent sales and playouts
+    pub fn values<R: Rng>(
+        &self,
+        cfr: &CFR,
+        game_state: &GameState,
+        player: Player,
+        rounds: u8,
+        rng: &mut R,
+    ) -> (f32, f32) {
+        if game_state.game_end() {
+            return (0.0, 0.0);
+        }
+        if game_state.auction_end() {
+            let reveals = self.reveals(game_state, rng);
+            return reveals
+                .iter()
+                .map(|(next_state, probability)| {
+                    let (policy_value, best_response_value) =
+                        self.values(cfr, next_state, player, rounds, rng);
+                    (
+                        probability * policy_value,
+                        probability * best_response_value,
+                    )
+                })
+                .fold((0.0, 0.0), |(policy, best), (p, b)| (policy + p, best + b));
+        }
+        if rounds == 0 {
+            let playout_value = self.playout_value(cfr, game_state, player, rng);
+            return (playout_value, playout_value);
+        }
+        let policies: Vec<(Vec<u8>, Vec<f32>)> = (0..game_state.no_players())
+            .map(|opponent| cfr.policy(game_state, opponent, self.strategy_kind))
+            .collect();
+        let opponent_sales = self.opponent_sales(&policies, player, rng);
+        let (own_moves, own_strategy) = &policies[player as usize];
+        let checks_before: u8 = game_state.get_player_checks(player).iter().sum();
+        // (policy value, best response value) of each of player's sales
+        let action_values: Vec<(f32, f32)> = own_moves
+            .iter()
+            .map(|&action| {
+                opponent_sales
+                    .iter()
+                    .map(|(sales, probability)| {
+                        let mut player_choices = sales.clone();
+                        player_choices[player as usize] = action;
+                        let next_state = game_state.manual_next_state_sell(player_choices);
+                        let gained = (next_state.get_player_checks(player).iter().sum::<u8>()
+                            - checks_before) as f32;
+                        let (policy_value, best_response_value) =
+                            self.values(cfr, &next_state, player, rounds - 1, rng);
+                        (
+                            probability * (gained + policy_value),
+                            probability * (gained + best_response_value),
+                        )
+                    })
+                    .fold((0.0, 0.0), |(policy, best), (p, b)| (policy + p, best + b))
+            })
+            .collect();
+        let policy_value = own_strategy
+            .iter()
+            .zip(action_values.iter())
+            .map(|(s, (v, _))| s * v)
+            .sum();
+        let best_response_value = action_values
+            .iter()
+            .map(|(_, v)| *v)
+            .fold(f32::MIN, f32::max);
+        (policy_value, best_response_value)
+    }
+
+    // Every joint sale of the other players with its probability, the responder's entry is a
+    // placeholder
+    fn opponent_sales<R: Rng>(
+        &self,
+        policies: &[(Vec<u8>, Vec<f32>)],
+        player: Player,
+        rng: &mut R,
+    ) -> Vec<(Vec<u8>, f32)> {
+        let joint_count: usize = policies
+            .iter()
+            .enumerate()
+            .filter(|&(opponent, _)| opponent != player as usize)
+            .map(|(_, (moves, _))| moves.len())
+            .product();
+        if joint_count <= self.opponent_samples {
+            policies
+                .iter()
+                .enumerate()
+                .map(|(opponent, (moves, strategy))| {
+                    if opponent == player as usize {
+                        vec![(0, 1.0)]
+                    } else {
+                        moves
+                            .iter()
+                            .copied()
+                            .zip(strategy.iter().copied())
+                            .collect()
+                    }
+                })
+                .multi_cartesian_product()
+                .map(|joint| {
+                    let probability = joint.iter().map(|(_, p)| p).product();
+                    (
+                        joint.into_iter().map(|(action, _)| action).collect(),
+                        probability,
+                    )
+                })
+                .filter(|(_, probability)| *probability > 0.0)
+                .collect()
+        } else {
+            let probability = 1.0 / self.opponent_samples as f32;
+            (0..self.opponent_samples)
+                .map(|_| {
+                    let sales = policies
+                        .iter()
+                        .enumerate()
+                        .map(|(opponent, (moves, strategy))| {
+                            if opponent == player as usize {
+                                0
+                            } else {
+                                moves[sample_strategy_with(strategy, rng).min(moves.len() - 1)]
+                            }
+                        })
+                        .collect();
+                    (sales, probability)
+                })
+                .collect()
+        }
+    }
+
+    // The deck is an even draw from the unseen checks, so every reveal is too
+    fn reveals<R: Rng>(&self, game_state: &GameState, rng: &mut R) -> Vec<(GameState, f32)> {
+        let no_players = game_state.no_players() as usize;
+        let unseen_checks = game_state.unseen_checks();
+        if Self::binomial(unseen_checks.len(), no_players) <= self.chance_samples {
+            let mut reveals: AHashMap<Vec<u8>, usize> = AHashMap::new();
+            let mut total: usize = 0;
+            for combination in unseen_checks.iter().copied().combinations(no_players) {
+                let mut reveal = combination;
+                reveal.sort_unstable();
+                *reveals.entry(reveal).or_insert(0) += 1;
+                total += 1;
+            }
+            reveals
+                .into_iter()
+                .map(|(reveal, count)| {
+                    let mut next_state = game_state.clone();
+                    next_state.reveal_auction_unseen(reveal);
+                    (next_state, count as f32 / total as f32)
+                })
+                .collect()
+        } else {
+            let probability = 1.0 / self.chance_samples as f32;
+            (0..self.chance_samples)
+                .map(|_| {
+                    let mut sampled_state = game_state.clone();
+                    sampled_state.determinise(rng);
+                    (sampled_state.generate_next_state_reveal(), probability)
+                })
+                .collect()
+        }
+    }
+
+    // Average checks player collects when everyone plays out the CFR strategies
+    fn playout_value<R: Rng>(
+        &self,
+        cfr: &CFR,
+        game_state: &GameState,
+        player: Player,
+        rng: &mut R,
+    ) -> f32 {
+        let checks_before: u8 = game_state.get_player_checks(player).iter().sum();
+        let mut total: f32 = 0.0;
+        for _ in 0..self.playouts {
+            let mut playout_state = game_state.clone();
+            playout_state.determinise(rng);
+            while !playout_state.game_end() {
+                if playout_state.auction_end() {
+                    playout_state = playout_state.generate_next_state_reveal();
+                    continue;
+                }
+                let player_choices: Vec<u8> = (0..playout_state.no_players())
+                    .map(|seller| {
+                        let (moves, strategy) =
+                            cfr.policy(&playout_state, seller, self.strategy_kind);
+                        moves[sample_strategy_with(&strategy, rng).min(moves.len() - 1)]
+                    })
+                    .collect();
+                playout_state = playout_state.manual_next_state_sell(player_choices);
+            }
+            total +=
+                (playout_state.get_player_checks(player).iter().sum::<u8>() - checks_before) as f32;
+        }
+        total / self.playouts as f32
+    }
+
+    fn binomial(n: usize, k: usize) -> usize {
+        if k > n {
+            return 0;
+        }
+        (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+    }
+}
+
+impl Default for BestResponse {
+    fn default() -> Self {
+        BestResponse::new(1, 16, 64, 4)
+    }
+}
@@ -1,67 +1,216 @@
-use crate::engines::q_values::regret::Regret;
-use crate::engines::scorers::naive_round_score::NaiveRoundScore;
-use crate::engines::utils::{mixed_strategy_score, normalize, sample_strategy, update_average};
-use crate::game_modes::traits::Game;
+use crate::engines::abstractions::bid_abstraction::BidAbstraction;
+use crate::engines::abstractions::card_abstraction::CardAbstraction;
+use crate::engines::algorithms::best_response::{BestResponse, Exploitability};
+use crate::engines::beliefs::belief_tracker::BeliefTracker;
+use crate::engines::constants::VALUE_PER_PROPERTY;
+use crate::engines::q_values::regret::RegretUpdater;
+use crate::engines::strategies::average::{Average, StrategyKind};
+use crate::engines::utils::{
+    mixed_strategy_score, sample_strategy, sample_strategy_with, update_average,
+};
 use crate::models::enums::{GamePhase, Player};
 use crate::models::game_state::GameState;
-use ahash::AHashMap;
+use ahash::{AHashMap, AHashSet};
 use bimap::BiMap;
-use log::{debug, info};
-use rand::{thread_rng, Rng};
+use crossbeam::scope;
+use helper::hashing::{checksum, empty_checksum, update_checksum};
+use itertools::Itertools;
+use log::{debug, info, warn};
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
 
 pub struct CFR {
     move_map: AHashMap<String, Vec<BiMap<usize, u8>>>,
     strategy: AHashMap<String, Vec<Vec<f32>>>, // These are probabilities of taking an action
     regret: AHashMap<String, Vec<Vec<f32>>>,
-    value: AHashMap<String, Vec<Vec<f32>>>,
+    last_regret: AHashMap<String, Vec<Vec<f32>>>, // Only kept for predictive updaters
+    average_strategy: AHashMap<String, Vec<Vec<f32>>>, // Weighted sum of past strategies
+    average: Average,
+    value: AHashMap<String, Vec<Vec<f32>>>, // Average checks gained after taking an action
+    visits: AHashMap<String, Vec<usize>>,
+    root_value: Vec<f32>,
+    root_samples: Vec<usize>, // Traversals root_value averages, per player
     buffer: Vec<GameState>,
     alternating_update: bool,
+    regret_updater: Arc<dyn RegretUpdater>,
+    iteration: usize,
+    card_abstraction: CardAbstraction,
+    bid_abstraction: BidAbstraction,
+    exploration: f32,
+    best_response: BestResponse,
+    exploitability_interval: Option<usize>,
+    last_exploitability: Option<Exploitability>,
+    threads: usize,
+    batch_iterations: usize,
+    seed: Option<u64>,
 }
 
+// Iterations between exploitability checks when stopping early without a report interval
+const DEFAULT_EXPLOITABILITY_INTERVAL: usize = 50;
+const CFR_FILE_VERSION: u32 = 1;
+
 impl CFR {
-    pub fn new(alternating_update: bool) -> Self {
+    pub fn new(regret_updater: Arc<dyn RegretUpdater>, alternating_update: bool) -> Self {
         let move_map: AHashMap<String, Vec<BiMap<usize, u8>>> = AHashMap::with_capacity(1);
         let strategy: AHashMap<String, Vec<Vec<f32>>> = AHashMap::with_capacity(1);
         let regret: AHashMap<String, Vec<Vec<f32>>> = AHashMap::with_capacity(1);
         let value: AHashMap<String, Vec<Vec<f32>>> = AHashMap::with_capacity(1);
+        let visits: AHashMap<String, Vec<usize>> = AHashMap::with_capacity(1);
         let buffer: Vec<GameState> = Vec::with_capacity(1000);
         CFR {
             move_map,
             strategy,
             regret,
+            last_regret: AHashMap::with_capacity(1),
+            average_strategy: AHashMap::with_capacity(1),
+            average: Average::default(),
             value,
+            visits,
+            root_value: Vec::new(),
+            root_samples: Vec::new(),
             buffer,
             alternating_update,
+            regret_updater,
+            iteration: 0,
+            card_abstraction: CardAbstraction::default(),
+            bid_abstraction: BidAbstraction::default(),
+            exploration: 0.6,
+            best_response: BestResponse::default(),
+            exploitability_interval: None,
+            last_exploitability: None,
+            threads: 1,
+            batch_iterations: 1,
+            seed: None,
+        }
+    }
+    pub fn with_card_abstraction(mut self, card_abstraction: CardAbstraction) -> Self {
+        self.card_abstraction = card_abstraction;
+        self
+    }
+    pub fn with_bid_abstraction(mut self, bid_abstraction: BidAbstraction) -> Self {
+        self.bid_abstraction = bid_abstraction;
+        self
+    }
+    // Probability the traverser samples uniformly instead of from its strategy in the bid phase
+    pub fn with_exploration(mut self, exploration: f32) -> Self {
+        self.exploration = exploration;
+        self
+    }
+    // Measures the sell phase exploitability every interval iterations of find_nash
+    pub fn with_exploitability(mut self, best_response: BestResponse, interval: usize) -> Self {
+        self.best_response = best_response;
+        self.exploitability_interval = Some(interval.max(1));
+        self
+    }
+    pub fn last_exploitability(&self) -> Option<&Exploitability> {
+        self.last_exploitability.as_ref()
+    }
+    // Seeded solves draw the best response's samples from the seed and iteration, so their
+    // checks and where find_nash_until stops are reproducible
+    pub fn exploitability(&self, game_state: &GameState) -> Exploitability {
+        let seed = match self.seed {
+            Some(seed) => seed ^ (self.iteration as u64).rotate_left(32),
+            None => thread_rng().random(),
+        };
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.best_response
+            .exploitability(self, game_state, &mut rng)
+    }
+    // Runs batch_iterations iterations per thread between merges of the tables
+    pub fn with_threads(mut self, threads: usize, batch_iterations: usize) -> Self {
+        self.threads = threads.max(1);
+        self.batch_iterations = batch_iterations.max(1);
+        self
+    }
+    // Worker i samples with seed + i, so solves are reproducible
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+    pub fn with_average(mut self, average: Average) -> Self {
+        self.average = average;
+        self
+    }
+    pub fn regret_updater(&self) -> &Arc<dyn RegretUpdater> {
+        &self.regret_updater
+    }
+    // Iterations run over every call to find_nash
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+    pub fn no_information_sets(&self) -> usize {
+        self.strategy.len()
+    }
+
+    // Sell nodes hold every player's strategy. Bid nodes are abstracted and seen from the player
+    // to move, so they only hold one.
+    fn information_set(&self, game_state: &GameState) -> String {
+        match game_state.game_phase() {
+            GamePhase::Sell => game_state.get_information_set_encoding(),
+            GamePhase::Bid => self.card_abstraction.bid_information_set(game_state),
+        }
+    }
+    fn slot(game_state: &GameState, player: Player) -> usize {
+        match game_state.game_phase() {
+            GamePhase::Sell => player as usize,
+            GamePhase::Bid => {
+                debug_assert!(
+                    player == game_state.current_player(),
+                    "Only the player to move has a bid strategy"
+                );
+                0
+            }
         }
     }
 
     pub fn initialise_node(&mut self, game_state: &GameState) {
-        // Creates the strategies, q_values and move_map for each player in the state.
-        if game_state.game_phase() == GamePhase::Sell {
-            let path = game_state.get_path_encoding();
-            let no_players = game_state.no_players();
-            let no_moves = game_state.legal_moves(0).len(); // This is a shortcut that works for For Sale
-            let initial_strategies: Vec<Vec<f32>> =
-                vec![vec![1.0 / no_players as f32; no_moves]; no_players as usize];
-            let initial_value: Vec<Vec<f32>> = vec![vec![0.0; no_moves]; no_players as usize];
-            self.strategy
-                .insert(path.clone(), initial_strategies.clone());
-            self.regret.insert(path.clone(), initial_strategies);
-            self.value.insert(path.clone(), initial_value);
-            let mut move_map_vec: Vec<BiMap<usize, u8>> =
-                Vec::with_capacity(game_state.no_players() as usize);
-            for player in 0..no_players {
-                let player_legal_moves = game_state.legal_moves(player);
-                let mut player_move_map: BiMap<usize, u8> = BiMap::with_capacity(10);
-                for (index, action) in player_legal_moves.iter().enumerate() {
-                    player_move_map.insert(index, *action);
-                }
-                move_map_vec.push(player_move_map);
+        // Creates the strategies, regrets, values and move_map for each player acting in the
+        // information set of the state.
+        let key = self.information_set(game_state);
+        let acting_moves = self.acting_moves(game_state);
+        self.insert_node(key, &acting_moves);
+    }
+
+    // Moves of every slot of the information set of the state
+    fn acting_moves(&self, game_state: &GameState) -> Vec<Vec<u8>> {
+        match game_state.game_phase() {
+            GamePhase::Sell => (0..game_state.no_players())
+                .map(|player| game_state.legal_moves(player))
+                .collect(),
+            GamePhase::Bid => vec![self
+                .bid_abstraction
+                .abstract_moves(&game_state.legal_moves(game_state.current_player()))],
+        }
+    }
+
+    fn insert_node(&mut self, key: String, acting_moves: &[Vec<u8>]) {
+        let mut initial_strategies: Vec<Vec<f32>> = Vec::with_capacity(acting_moves.len());
+        let mut initial_value: Vec<Vec<f32>> = Vec::with_capacity(acting_moves.len());
+        let mut move_map_vec: Vec<BiMap<usize, u8>> = Vec::with_capacity(acting_moves.len());
+        for player_legal_moves in acting_moves.iter() {
+            let no_moves = player_legal_moves.len();
+            initial_strategies.push(vec![1.0 / no_moves as f32; no_moves]);
+            initial_value.push(vec![0.0; no_moves]);
+            let mut player_move_map: BiMap<usize, u8> = BiMap::with_capacity(10);
+            for (index, action) in player_legal_moves.iter().enumerate() {
+                player_move_map.insert(index, *action);
             }
-            self.move_map.insert(path.clone(), move_map_vec);
-        } else {
-            todo!("Focused on Greedy Baby for now");
+            move_map_vec.push(player_move_map);
+        }
+        self.strategy.insert(key.clone(), initial_strategies);
+        self.regret.insert(key.clone(), initial_value.clone());
+        self.average_strategy
+            .insert(key.clone(), initial_value.clone());
+        if self.regret_updater.predictive() {
+            self.last_regret.insert(key.clone(), initial_value.clone());
         }
+        self.value.insert(key.clone(), initial_value);
+        self.visits.insert(key.clone(), vec![0; acting_moves.len()]);
+        self.move_map.insert(key, move_map_vec);
     }
 
     pub fn add_game_state(&mut self, game_state: GameState) {
@@ -69,156 +218,799 @@ impl CFR {
     }
 
     pub fn game_state_added(&self, game_state: &GameState) -> bool {
-        self.strategy.get(&game_state.get_path_encoding()).is_some()
+        self.strategy
+            .contains_key(&self.information_set(game_state))
     }
 
+    // Samples an action from the average strategy
     pub fn get_mixed_strategy(&self, game_state: &GameState, player: Player) -> u8 {
-        let path = game_state.get_path_encoding();
-        if let Some(strategies) = self.strategy.get(&path) {
-            let index = sample_strategy(&strategies[player as usize]);
-            if let Some(move_maps) = self.move_map.get(&path) {
-                if let Some(action) = move_maps[player as usize].get_by_left(&index) {
-                    *action
-                } else {
-                    panic!("Failed to find appropriate action");
-                }
-            } else {
-                panic!("Failed to find move_map for player: {}", player);
+        self.sample_action(game_state, player, StrategyKind::Average)
+    }
+
+    pub fn sample_action(&self, game_state: &GameState, player: Player, kind: StrategyKind) -> u8 {
+        let (moves, strategy) = self.policy(game_state, player, kind);
+        moves[sample_strategy(&strategy).min(moves.len() - 1)]
+    }
+
+    // Legal moves of player with the probability the chosen strategy plays each. The average
+    // falls back to the current strategy before anything was accumulated, and unvisited
+    // information sets are uniform.
+    pub fn policy(
+        &self,
+        game_state: &GameState,
+        player: Player,
+        kind: StrategyKind,
+    ) -> (Vec<u8>, Vec<f32>) {
+        let key = self.information_set(game_state);
+        let slot = Self::slot(game_state, player);
+        if let (Some(strategies), Some(move_maps)) =
+            (self.strategy.get(&key), self.move_map.get(&key))
+        {
+            let moves: Vec<u8> = (0..strategies[slot].len())
+                .map(|index| {
+                    *move_maps[slot]
+                        .get_by_left(&index)
+                        .expect("Failed to find appropriate action in move_map")
+                })
+                .collect();
+            let average = || {
+                self.average_strategy
+                    .get(&key)
+                    .and_then(|cumulative| Average::strategy(&cumulative[slot]))
+                    .unwrap_or_else(|| strategies[slot].clone())
+            };
+            let strategy = match kind {
+                StrategyKind::Current => strategies[slot].clone(),
+                StrategyKind::Average => average(),
+                StrategyKind::Purified => Average::purify(&average()),
+            };
+            return (moves, strategy);
+        }
+        let moves = match game_state.game_phase() {
+            GamePhase::Sell => game_state.legal_moves(player),
+            GamePhase::Bid => self
+                .bid_abstraction
+                .abstract_moves(&game_state.legal_moves(player)),
+        };
+        let uniform = vec![1.0 / moves.len() as f32; moves.len()];
+        (moves, uniform)
+    }
+
+    // Policy of player when the holdings or coins of others are only believed. Exact beliefs
+    // give the information set directly, otherwise the policy is averaged over samples
+    // determinisations of the beliefs.
+    pub fn belief_policy(
+        &self,
+        belief: &BeliefTracker,
+        game_state: &GameState,
+        player: Player,
+        kind: StrategyKind,
+        samples: usize,
+    ) -> (Vec<u8>, Vec<f32>) {
+        let mut rng = thread_rng();
+        if belief.is_exact() {
+            return self.policy(&belief.determinise(game_state, &mut rng), player, kind);
+        }
+        let samples = samples.max(1);
+        let mut totals: AHashMap<u8, f32> = AHashMap::new();
+        for _ in 0..samples {
+            let (moves, strategy) =
+                self.policy(&belief.determinise(game_state, &mut rng), player, kind);
+            for (action, probability) in moves.into_iter().zip(strategy) {
+                *totals.entry(action).or_insert(0.0) += probability / samples as f32;
             }
-        } else {
-            panic!("Failed to find strategy!");
         }
+        let mut policy: Vec<(u8, f32)> = totals.into_iter().collect();
+        policy.sort_unstable_by_key(|(action, _)| *action);
+        policy.into_iter().unzip()
     }
 
-    pub fn find_nash(&mut self, initial_state: &GameState, iterations: usize) {
-        // Proper way is to simulate every outcome
+    // Expected checks each player still collects from the information set under the current
+    // strategies. Bid information sets only hold the value of the player to move.
+    pub fn expected_values(&self, game_state: &GameState) -> Option<Vec<f32>> {
+        let key = self.information_set(game_state);
+        let strategies = self.strategy.get(&key)?;
+        let values = self.value.get(&key)?;
+        Some(
+            strategies
+                .iter()
+                .zip(values.iter())
+                .map(|(strategy, value)| mixed_strategy_score(strategy, value))
+                .collect(),
+        )
+    }
 
-        // For each player, get regret
-        //      For each legal move
-        //      Simulate all other moves based on strategy
-        //      Update regret
-        // For all q_values update the strategy
+    // Average checks each player collected from the root of the last find_nash
+    pub fn root_value(&self) -> &Vec<f32> {
+        &self.root_value
+    }
 
-        self.initialise_node(&initial_state);
-        let path = initial_state.get_path_encoding();
-        let strategy_vec = match self.strategy.get_mut(&path) {
-            Some(strategy_vec) => strategy_vec,
-            None => panic!("Failed to find appropriate strategy"),
-        };
-        let regret_vec = match self.regret.get_mut(&path) {
-            Some(regret_vec) => regret_vec,
-            None => panic!("Failed to find appropriate q_value"),
+    // Traversals of each player behind root_value, so callers can pool several find_nash calls
+    pub fn root_samples(&self) -> &Vec<usize> {
+        &self.root_samples
+    }
+
+    // Sell phase: external sampling MCCFR over the rest of the sell phase.
+    // Each iteration samples one check order (chance), then every player in turn traverses
+    // all of their own actions while opponents' sales are sampled from their strategies.
+    // Utilities are the checks collected, so the game is constant sum.
+    // Bid phase: outcome sampling MCCFR over the rest of the bid phase.
+    // External sampling would branch on every one of the traverser's bids, and a player
+    // bids many times per round, so each iteration samples a single line of play instead
+    // and corrects the regrets by the probability of having sampled it.
+    // The sell phase is not searched, leaves are valued by the coins left plus the checks
+    // the properties are worth on average.
+    // Returns the number of iterations run
+    pub fn find_nash(&mut self, initial_state: &GameState, iterations: usize) -> usize {
+        self.solve(initial_state, iterations, None)
+    }
+
+    // Stops as soon as the NashConv of the current strategies falls to target, checked every
+    // exploitability interval. Bid phase solves always run max_iterations.
+    pub fn find_nash_until(
+        &mut self,
+        initial_state: &GameState,
+        target: f32,
+        max_iterations: usize,
+    ) -> usize {
+        self.solve(initial_state, max_iterations, Some(target))
+    }
+
+    // Fingerprint of everything an information set key depends on: the decks, the starting coins
+    // and cards dealt for every player count and the abstractions. Tables only load under the
+    // same rules.
+    pub fn rule_set_fingerprint(&self) -> u64 {
+        let properties = GameState::all_properties().iter().join(",");
+        let checks = GameState::all_checks().iter().join(",");
+        let mut rules = format!("{}:{}|", properties, checks);
+        for no_players in 3..=6 {
+            rules.push_str(&format!(
+                "{}:{}:{}|",
+                no_players,
+                GameState::starting_coins(no_players),
+                GameState::dealt_cards(no_players)
+            ));
+        }
+        rules.push_str(&format!(
+            "{:?}|{:?}",
+            self.card_abstraction, self.bid_abstraction
+        ));
+        checksum(rules.as_bytes())
+    }
+
+    // Text format with a header of metadata, one information set per line and a checksum of
+    // every line before it
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let mut hash = empty_checksum();
+        let mut write_line = |writer: &mut BufWriter<File>, line: String| -> std::io::Result<()> {
+            hash = update_checksum(hash, line.as_bytes());
+            hash = update_checksum(hash, b"\n");
+            writeln!(writer, "{}", line)
         };
-        let value_vec = match self.value.get_mut(&path) {
-            Some(value_vec) => value_vec,
-            None => panic!("Failed to find appropriate q_value"),
+        let join_slots = |slots: &Vec<Vec<f32>>| -> String {
+            slots
+                .iter()
+                .map(|slot| slot.iter().map(|v| v.to_string()).join(","))
+                .join(";")
         };
-        let move_map = match self.move_map.get(&path) {
-            Some(move_map) => move_map,
-            None => panic!("Failed to find appropriate move_map"),
+        write_line(&mut writer, "# For Sale CFR strategy table".to_string())?;
+        write_line(&mut writer, format!("version {}", CFR_FILE_VERSION))?;
+        write_line(&mut writer, format!("iterations {}", self.iteration))?;
+        write_line(
+            &mut writer,
+            format!("regret {}", self.regret_updater.name()),
+        )?;
+        write_line(
+            &mut writer,
+            format!("alternating_update {}", self.alternating_update),
+        )?;
+        write_line(
+            &mut writer,
+            format!("rule_set {:016x}", self.rule_set_fingerprint()),
+        )?;
+        write_line(&mut writer, format!("entries {}", self.strategy.len()))?;
+        for (key, strategy_vec) in self.strategy.iter() {
+            let moves = self.move_map[key]
+                .iter()
+                .map(|move_map| {
+                    (0..move_map.len())
+                        .map(|index| move_map.get_by_left(&index).copied().unwrap_or(0))
+                        .join(",")
+                })
+                .join(";");
+            let visits = self.visits[key].iter().join(",");
+            let last_regret = self
+                .last_regret
+                .get(key)
+                .map(join_slots)
+                .unwrap_or_default();
+            write_line(
+                &mut writer,
+                format!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                    key,
+                    moves,
+                    join_slots(strategy_vec),
+                    join_slots(&self.regret[key]),
+                    join_slots(&self.average_strategy[key]),
+                    join_slots(&self.value[key]),
+                    visits,
+                    last_regret
+                ),
+            )?;
+        }
+        writeln!(writer, "checksum {:016x}", hash)?;
+        writer.flush()?;
+        info!(
+            "Saved {} information sets after {} iterations to {:?}",
+            self.strategy.len(),
+            self.iteration,
+            path
+        );
+        Ok(())
+    }
+
+    // Loads a table saved by save into this solver, whose abstractions have to match the ones
+    // the table was solved with
+    pub fn load(mut self, path: &Path) -> std::io::Result<Self> {
+        let invalid =
+            |message: String| std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+        let reader = BufReader::new(File::open(path)?);
+        let mut hash = empty_checksum();
+        let mut version: Option<u32> = None;
+        let mut stored_checksum: Option<u64> = None;
+        let mut expected_entries: Option<usize> = None;
+        for line in reader.lines() {
+            let line = line?;
+            if let Some(value) = line.strip_prefix("checksum ") {
+                stored_checksum =
+                    Some(u64::from_str_radix(value, 16).map_err(|_| invalid(line.clone()))?);
+                break;
+            }
+            hash = update_checksum(hash, line.as_bytes());
+            hash = update_checksum(hash, b"\n");
+            if line.starts_with('#') || line.is_empty() {
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("version ") {
+                version = value.parse().ok();
+                if version != Some(CFR_FILE_VERSION) {
+                    return Err(invalid(format!("Unsupported CFR table version: {value}")));
+                }
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("iterations ") {
+                self.iteration = value.parse().map_err(|_| invalid(line.clone()))?;
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("regret ") {
+                if value != self.regret_updater.name() {
+                    warn!(
+                        "Table was solved with {} regrets, continuing with {}",
+                        value,
+                        self.regret_updater.name()
+                    );
+                }
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("alternating_update ") {
+                self.alternating_update = value.parse().map_err(|_| invalid(line.clone()))?;
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("rule_set ") {
+                let fingerprint = format!("{:016x}", self.rule_set_fingerprint());
+                if value != fingerprint {
+                    return Err(invalid(format!(
+                        "Table was solved under rule set {value}, this solver uses {fingerprint}"
+                    )));
+                }
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("entries ") {
+                expected_entries = Some(value.parse().map_err(|_| invalid(line.clone()))?);
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() != 8 {
+                return Err(invalid(format!("Malformed CFR table entry: {line}")));
+            }
+            let parse_slots = |field: &str| -> std::io::Result<Vec<Vec<f32>>> {
+                field
+                    .split(';')
+                    .map(|slot| {
+                        if slot.is_empty() {
+                            return Ok(Vec::new());
+                        }
+                        slot.split(',')
+                            .map(|v| v.parse::<f32>().map_err(|_| invalid(line.clone())))
+                            .collect()
+                    })
+                    .collect()
+            };
+            let key = fields[0].to_string();
+            let mut move_map_vec: Vec<BiMap<usize, u8>> = Vec::new();
+            for slot in fields[1].split(';') {
+                let mut player_move_map: BiMap<usize, u8> = BiMap::with_capacity(10);
+                for (index, action) in slot.split(',').filter(|a| !a.is_empty()).enumerate() {
+                    player_move_map
+                        .insert(index, action.parse().map_err(|_| invalid(line.clone()))?);
+                }
+                move_map_vec.push(player_move_map);
+            }
+            let visits: Vec<usize> = fields[6]
+                .split(',')
+                .map(|v| v.parse().map_err(|_| invalid(line.clone())))
+                .collect::<std::io::Result<Vec<usize>>>()?;
+            self.strategy.insert(key.clone(), parse_slots(fields[2])?);
+            self.regret.insert(key.clone(), parse_slots(fields[3])?);
+            self.average_strategy
+                .insert(key.clone(), parse_slots(fields[4])?);
+            self.value.insert(key.clone(), parse_slots(fields[5])?);
+            if self.regret_updater.predictive() {
+                let last_regret = if fields[7].is_empty() {
+                    self.regret[&key]
+                        .iter()
+                        .map(|slot| vec![0.0; slot.len()])
+                        .collect()
+                } else {
+                    parse_slots(fields[7])?
+                };
+                self.last_regret.insert(key.clone(), last_regret);
+            }
+            self.visits.insert(key.clone(), visits);
+            self.move_map.insert(key, move_map_vec);
+        }
+        if version.is_none() {
+            return Err(invalid("CFR table is missing its version".to_string()));
+        }
+        match stored_checksum {
+            Some(stored) if stored == hash => {}
+            Some(_) => return Err(invalid("CFR table checksum does not match".to_string())),
+            None => return Err(invalid("CFR table is missing its checksum".to_string())),
+        }
+        if expected_entries.is_some_and(|entries| entries != self.strategy.len()) {
+            return Err(invalid(format!(
+                "CFR table should hold {:?} entries but holds {}",
+                expected_entries,
+                self.strategy.len()
+            )));
+        }
+        info!(
+            "Loaded {} information sets after {} iterations from {:?}",
+            self.strategy.len(),
+            self.iteration,
+            path
+        );
+        Ok(self)
+    }
+
+    // Runs the iterations in batches when there are several threads. Every worker samples with
+    // its own seeded generator against the strategies at the start of the batch, and their
+    // updates are merged in worker order, so a fixed seed gives the same tables whatever the
+    // scheduling.
+    fn solve(
+        &mut self,
+        initial_state: &GameState,
+        iterations: usize,
+        target: Option<f32>,
+    ) -> usize {
+        let no_players = initial_state.no_players() as usize;
+        let base_seed: u64 = self.seed.unwrap_or_else(|| thread_rng().random());
+        let mut rngs: Vec<StdRng> = (0..self.threads)
+            .map(|worker| StdRng::seed_from_u64(base_seed.wrapping_add(worker as u64)))
+            .collect();
+        self.root_value = vec![0.0; no_players];
+        let mut root_samples: Vec<usize> = vec![0; no_players];
+        self.last_exploitability = None;
+        let interval = match (self.exploitability_interval, target) {
+            _ if initial_state.game_phase() == GamePhase::Bid => None,
+            (Some(interval), _) => Some(interval),
+            (None, Some(_)) => Some(DEFAULT_EXPLOITABILITY_INTERVAL),
+            (None, None) => None,
         };
-        for i in 0..iterations {
-            for update_player in 0..initial_state.no_players() as usize {
-                let legal_moves = &initial_state.legal_moves(update_player as u8);
-                let mut temp_scores: Vec<f32> = vec![0.0; legal_moves.len()];
-                for move_index in 0..legal_moves.len() {
-                    let mut aggregate_sales: Vec<u8> =
-                        Vec::with_capacity(initial_state.no_players() as usize);
-                    let action = legal_moves[move_index];
-                    for move_player in 0..initial_state.no_players() {
-                        if move_player == update_player as u8 {
-                            aggregate_sales.push(action);
-                        } else {
-                            let sampled_strategy_index =
-                                sample_strategy(&strategy_vec[move_player as usize]);
-                            let sampled_action: u8 = match move_map[move_player as usize]
-                                .get_by_left(&sampled_strategy_index)
-                            {
-                                Some(action) => *action,
-                                None => {
-                                    panic!("Failed to find appropriate action in move_map");
+        let mut iterations_run: usize = 0;
+        while iterations_run < iterations {
+            let batch = if self.threads == 1 {
+                1
+            } else {
+                (self.threads * self.batch_iterations).min(iterations - iterations_run)
+            };
+            let first_iteration = self.iteration + 1;
+            let mut touched: AHashSet<(String, usize)> = AHashSet::with_capacity(1000);
+            if self.threads == 1 {
+                let mut sampled_state = initial_state.clone();
+                sampled_state.determinise(&mut rngs[0]);
+                let traversers = Self::traversers(initial_state, first_iteration);
+                // Alternating updates let each traverser see the strategies the previous
+                // traversers just updated
+                let passes: Vec<Vec<Player>> = if self.alternating_update {
+                    traversers
+                        .iter()
+                        .map(|&traverser| vec![traverser])
+                        .collect()
+                } else {
+                    vec![traversers]
+                };
+                for pass in passes {
+                    let mut worker = Worker::new(self, &mut rngs[0]);
+                    worker.iterate(&sampled_state, first_iteration, &pass);
+                    let updates = worker.finish();
+                    self.merge(updates, &mut touched, &mut root_samples);
+                    self.refresh_strategies(&touched);
+                }
+            } else {
+                let per_worker = batch.div_ceil(self.threads);
+                let cfr: &CFR = self;
+                let all_updates: Vec<WorkerUpdates> = scope(|s| {
+                    let handles: Vec<_> = rngs
+                        .iter_mut()
+                        .enumerate()
+                        .map(|(worker_index, rng)| {
+                            let start = worker_index * per_worker;
+                            let end = ((worker_index + 1) * per_worker).min(batch);
+                            s.spawn(move |_| {
+                                let mut worker = Worker::new(cfr, rng);
+                                for offset in start..end {
+                                    let iteration = first_iteration + offset;
+                                    let mut sampled_state = initial_state.clone();
+                                    sampled_state.determinise(worker.rng);
+                                    let traversers = Self::traversers(initial_state, iteration);
+                                    worker.iterate(&sampled_state, iteration, &traversers);
                                 }
-                            };
-                            aggregate_sales.push(sampled_action);
-                        }
-                    }
-                    // Evaluate and update q_value based on action
-                    // TODO: The random choice is not working
-                    let game_state =
-                        initial_state.generate_next_state_sell(aggregate_sales.clone());
-                    let score: f32 =
-                        NaiveRoundScore::round_score_function(&game_state)[update_player];
-                    temp_scores[move_index] = score;
+                                worker.finish()
+                            })
+                        })
+                        .collect();
+                    handles
+                        .into_iter()
+                        .map(|handle| handle.join().expect("CFR worker panicked"))
+                        .collect()
+                })
+                .expect("CFR scope failed");
+                for updates in all_updates {
+                    self.merge(updates, &mut touched, &mut root_samples);
                 }
-                //     Regret matching
-                //     Get average utility
-                let average_score =
-                    mixed_strategy_score(&strategy_vec[update_player], &temp_scores);
-                temp_scores
-                    .iter_mut()
-                    .for_each(|s| *s = (*s - average_score).max(0.0));
-                // Calculating Regret
-                for (q, t) in regret_vec[update_player].iter_mut().zip(temp_scores.iter()) {
-                    // CFR
-                    // *q += t;
-                    // CFR+
-                    *q = (*q + t).max(0.0);
+                self.refresh_strategies(&touched);
+            }
+            let previous_run = iterations_run;
+            iterations_run += batch;
+            self.iteration += batch;
+            if previous_run / 1000 != iterations_run / 1000 || previous_run == 0 {
+                debug!("ITER: {} ROOT VALUE: {:?}", iterations_run, self.root_value);
+            }
+            if interval.is_some_and(|interval| previous_run / interval != iterations_run / interval)
+            {
+                let exploitability = self.exploitability(initial_state);
+                info!(
+                    "ITER: {} NASHCONV: {:.3} BEST RESPONSE: {:?} POLICY: {:?}",
+                    iterations_run,
+                    exploitability.nash_conv,
+                    exploitability.best_response_values,
+                    exploitability.policy_values
+                );
+                let converged = target.is_some_and(|target| exploitability.nash_conv <= target);
+                self.last_exploitability = Some(exploitability);
+                if converged {
+                    break;
                 }
-                if self.alternating_update {
-                    normalize(&mut strategy_vec[update_player], &regret_vec[update_player]);
+            }
+        }
+        if initial_state.game_phase() == GamePhase::Sell {
+            debug!("PLAYER STRATEGY");
+            if let Some(strategy_vec) = self
+                .strategy
+                .get(&initial_state.get_information_set_encoding())
+            {
+                for (player, strategy) in strategy_vec.iter().enumerate() {
+                    debug!("P{}: {:?}", player, strategy);
                 }
-                //     Update strategy
-                update_average(&mut value_vec[update_player], &temp_scores, i + 1);
             }
-            if !self.alternating_update {
-                for update_player in 0..initial_state.no_players() as usize {
-                    normalize(&mut strategy_vec[update_player], &regret_vec[update_player]);
+        }
+        self.root_samples = root_samples;
+        iterations_run
+    }
+
+    // External sampling in the sell phase updates every player on each iteration. Outcome
+    // sampling in the bid phase updates one player per iteration, in turn.
+    fn traversers(initial_state: &GameState, iteration: usize) -> Vec<Player> {
+        let no_players = initial_state.no_players();
+        match initial_state.game_phase() {
+            GamePhase::Sell => (0..no_players).collect(),
+            GamePhase::Bid => vec![((iteration - 1) % no_players as usize) as Player],
+        }
+    }
+
+    fn merge(
+        &mut self,
+        updates: WorkerUpdates,
+        touched: &mut AHashSet<(String, usize)>,
+        root_samples: &mut [usize],
+    ) {
+        for (key, acting_moves) in updates.new_nodes {
+            if !self.strategy.contains_key(&key) {
+                self.insert_node(key, &acting_moves);
+            }
+        }
+        for visit in updates.visits {
+            if let Some(cumulative_vec) = self.average_strategy.get_mut(&visit.key) {
+                for (slot, strategy, reach) in visit.averages.iter() {
+                    self.average.update_strategy(
+                        &mut cumulative_vec[*slot],
+                        strategy,
+                        *reach,
+                        visit.iteration,
+                    );
+                }
+            }
+            if let Some((slot, instant_regret, action_values)) = visit.regret {
+                if let Some(regret_vec) = self.regret.get_mut(&visit.key) {
+                    self.regret_updater.update(
+                        &mut regret_vec[slot],
+                        &instant_regret,
+                        visit.iteration,
+                    );
+                }
+                if let Some(last_regret_vec) = self.last_regret.get_mut(&visit.key) {
+                    last_regret_vec[slot] = instant_regret;
+                }
+                if let (Some(visits), Some(value_vec)) = (
+                    self.visits.get_mut(&visit.key),
+                    self.value.get_mut(&visit.key),
+                ) {
+                    visits[slot] += 1;
+                    update_average(&mut value_vec[slot], &action_values, visits[slot]);
+                }
+                touched.insert((visit.key, slot));
+            }
+        }
+        for (traverser, value) in updates.root_values {
+            root_samples[traverser] += 1;
+            self.root_value[traverser] +=
+                (value - self.root_value[traverser]) / root_samples[traverser] as f32;
+        }
+    }
+
+    fn refresh_strategies(&mut self, touched: &AHashSet<(String, usize)>) {
+        for (key, slot) in touched.iter() {
+            if let (Some(strategy_vec), Some(regret_vec)) =
+                (self.strategy.get_mut(key), self.regret.get(key))
+            {
+                let last_regret: &[f32] = match self.last_regret.get(key) {
+                    Some(last_regret_vec) => &last_regret_vec[*slot],
+                    None => &[],
+                };
+                self.regret_updater.strategy(
+                    &mut strategy_vec[*slot],
+                    &regret_vec[*slot],
+                    last_regret,
+                );
+            }
+        }
+    }
+
+    fn bid_phase_utility(game_state: &GameState, player: Player) -> f32 {
+        let properties: u16 = game_state
+            .get_player_properties(player)
+            .iter()
+            .map(|&property| property as u16)
+            .sum();
+        game_state.get_player_coins(player) as f32 + properties as f32 * VALUE_PER_PROPERTY
+    }
+}
+
+// Everything one worker changed, applied to the tables by CFR::merge
+#[derive(Default)]
+struct WorkerUpdates {
+    new_nodes: Vec<(String, Vec<Vec<u8>>)>,
+    visits: Vec<Visit>,
+    root_values: Vec<(usize, f32)>,
+}
+
+struct Visit {
+    key: String,
+    iteration: usize,
+    // Slot, strategy and reach weight added to the average strategy
+    averages: Vec<(usize, Vec<f32>, f32)>,
+    // Slot, instant regrets and sampled action values of the traverser
+    regret: Option<(usize, Vec<f32>, Vec<f32>)>,
+}
+
+// Traverses against a read only snapshot of the CFR tables and records its updates
+struct Worker<'a> {
+    cfr: &'a CFR,
+    rng: &'a mut StdRng,
+    new_nodes: AHashMap<String, Vec<Vec<u8>>>,
+    updates: WorkerUpdates,
+}
+
+impl<'a> Worker<'a> {
+    fn new(cfr: &'a CFR, rng: &'a mut StdRng) -> Self {
+        Worker {
+            cfr,
+            rng,
+            new_nodes: AHashMap::new(),
+            updates: WorkerUpdates::default(),
+        }
+    }
+
+    fn finish(mut self) -> WorkerUpdates {
+        self.updates.new_nodes = self.new_nodes.into_iter().collect();
+        self.updates
+    }
+
+    fn iterate(&mut self, sampled_state: &GameState, iteration: usize, traversers: &[Player]) {
+        for &traverser in traversers {
+            let value = match sampled_state.game_phase() {
+                GamePhase::Sell => self.traverse(sampled_state, traverser, iteration),
+                GamePhase::Bid => {
+                    self.outcome_sample(sampled_state, traverser, iteration, 1.0, 1.0)
                 }
+            };
+            self.updates.root_values.push((traverser as usize, value));
+        }
+    }
+
+    // Moves and current strategies of every slot, uniform for nodes not merged yet
+    fn node(&mut self, key: &str, game_state: &GameState) -> (Vec<Vec<u8>>, Vec<Vec<f32>>) {
+        if let (Some(strategies), Some(move_maps)) =
+            (self.cfr.strategy.get(key), self.cfr.move_map.get(key))
+        {
+            let moves: Vec<Vec<u8>> = move_maps
+                .iter()
+                .map(|move_map| {
+                    (0..move_map.len())
+                        .map(|index| {
+                            *move_map
+                                .get_by_left(&index)
+                                .expect("Failed to find appropriate action in move_map")
+                        })
+                        .collect()
+                })
+                .collect();
+            return (moves, strategies.clone());
+        }
+        let moves = self
+            .new_nodes
+            .entry(key.to_string())
+            .or_insert_with(|| self.cfr.acting_moves(game_state))
+            .clone();
+        let strategies = moves
+            .iter()
+            .map(|slot_moves| vec![1.0 / slot_moves.len() as f32; slot_moves.len()])
+            .collect();
+        (moves, strategies)
+    }
+
+    // Returns the sampled value of the traverser, weighted by the inverse probability of the
+    // traverser's own sampling along the line
+    fn outcome_sample(
+        &mut self,
+        game_state: &GameState,
+        traverser: Player,
+        iteration: usize,
+        opponents_reach: f32,
+        sample_reach: f32,
+    ) -> f32 {
+        if game_state.game_phase() == GamePhase::Sell {
+            return CFR::bid_phase_utility(game_state, traverser);
+        }
+        if game_state.auction_end() {
+            // Chance node, the sampled deck decides the reveal
+            return self.outcome_sample(
+                &game_state.generate_next_state_reveal(),
+                traverser,
+                iteration,
+                opponents_reach,
+                sample_reach,
+            );
+        }
+        let key = self.cfr.information_set(game_state);
+        let (moves, strategies) = self.node(&key, game_state);
+        let player = game_state.current_player();
+        let strategy = &strategies[0];
+        let no_moves = strategy.len();
+        let exploration = self.cfr.exploration;
+        let sample_policy: Vec<f32> = if player == traverser {
+            strategy
+                .iter()
+                .map(|s| exploration / no_moves as f32 + (1.0 - exploration) * s)
+                .collect()
+        } else {
+            strategy.clone()
+        };
+        let sampled_index = sample_strategy_with(&sample_policy, self.rng).min(no_moves - 1);
+        let next_state = game_state.manual_next_state_bid(player, moves[0][sampled_index]);
+        let next_opponents_reach = if player == traverser {
+            opponents_reach
+        } else {
+            opponents_reach * strategy[sampled_index]
+        };
+        let child_value = self.outcome_sample(
+            &next_state,
+            traverser,
+            iteration,
+            next_opponents_reach,
+            sample_reach * sample_policy[sampled_index],
+        );
+        if player != traverser {
+            // The player to move is an opponent of the traverser, so its own reach is in
+            // opponents_reach
+            self.updates.visits.push(Visit {
+                key,
+                iteration,
+                averages: vec![(0, strategy.clone(), opponents_reach / sample_reach)],
+                regret: None,
+            });
+            return child_value;
+        }
+        let mut action_values: Vec<f32> = vec![0.0; no_moves];
+        action_values[sampled_index] = child_value / sample_policy[sampled_index];
+        let node_value = mixed_strategy_score(strategy, &action_values);
+        let weight = opponents_reach / sample_reach;
+        let instant_regret: Vec<f32> = action_values
+            .iter()
+            .map(|v| (v - node_value) * weight)
+            .collect();
+        self.updates.visits.push(Visit {
+            key,
+            iteration,
+            averages: Vec::new(),
+            regret: Some((0, instant_regret, action_values)),
+        });
+        node_value
+    }
+
+    fn traverse(&mut self, game_state: &GameState, traverser: Player, iteration: usize) -> f32 {
+        if game_state.game_end() {
+            return 0.0;
+        }
+        if game_state.auction_end() {
+            // Chance node, the sampled check order decides the reveal
+            return self.traverse(
+                &game_state.generate_next_state_reveal(),
+                traverser,
+                iteration,
+            );
+        }
+        let key = game_state.get_information_set_encoding();
+        let (moves, strategies) = self.node(&key, game_state);
+        let traverser_index = traverser as usize;
+        // Opponents' strategies are sampled here with the probability they reach the node, so
+        // adding them unweighted by reach keeps the average unbiased
+        let mut averages: Vec<(usize, Vec<f32>, f32)> = Vec::with_capacity(strategies.len());
+        let mut aggregate_sales: Vec<u8> = Vec::with_capacity(strategies.len());
+        for (player, (player_moves, strategy)) in moves.iter().zip(strategies.iter()).enumerate() {
+            if player == traverser_index {
+                aggregate_sales.push(0);
+            } else {
+                averages.push((player, strategy.clone(), 1.0));
+                let sampled_index = sample_strategy_with(strategy, self.rng);
+                aggregate_sales.push(player_moves[sampled_index.min(player_moves.len() - 1)]);
             }
-            if i % 100 == 0 {
-                // println!("STRATEGY: ITER: {}", i);
-                // for player in 0..initial_state.no_players() as usize {
-                //     println!("P{}: {:?}", player, strategy_vec[player]);
-                // }
-                // println!("Q VALUE: ITER: {}", i);
-                // for player in 0..initial_state.no_players() as usize {
-                //     println!("P{}: {:?}", player, value_vec[player]);
-                // }
-                // let mut player_values: Vec<f32> = Vec::with_capacity(6);
-                // for player in 0..initial_state.no_players() as usize {
-                //     let mixed_policy_score =
-                //         mixed_strategy_score(&strategy_vec[player], &value_vec[player]);
-                //     player_values.push(mixed_policy_score);
-                // }
-                // println!("{:?}", player_values);
-
-                // ====== EXPLOITABILITY ======
-                // let mut total_exploitability: f32 = 0.0;
-                // for player in 0..initial_state.no_players() as usize {
-                //     let mixed_policy_score =
-                //         mixed_strategy_score(&strategy_vec[player], &value_vec[player]);
-                //     let max_q_value = value_vec[player]
-                //         .iter()
-                //         .max_by(|&a, &b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-                //         .cloned()
-                //         .unwrap_or(0.0);
-                //     total_exploitability += max_q_value - mixed_policy_score;
-                // }
-                // println!("ITER: {} % EXPLOITABILITY: {}", i, total_exploitability);
-            }
-        }
-        info!("PLAYER STRATEGY");
-        for player in 0..initial_state.no_players() as usize {
-            info!("P{}: {:?}", player, strategy_vec[player]);
         }
+        let checks_before: u8 = game_state.get_player_checks(traverser).iter().sum();
+        let mut action_values: Vec<f32> = vec![0.0; moves[traverser_index].len()];
+        for (action, action_value) in moves[traverser_index].iter().zip(action_values.iter_mut()) {
+            aggregate_sales[traverser_index] = *action;
+            let next_state = game_state.manual_next_state_sell(aggregate_sales.clone());
+            let checks_gained =
+                next_state.get_player_checks(traverser).iter().sum::<u8>() - checks_before;
+            *action_value = checks_gained as f32 + self.traverse(&next_state, traverser, iteration);
+        }
+        let node_value = mixed_strategy_score(&strategies[traverser_index], &action_values);
+        let instant_regret: Vec<f32> = action_values.iter().map(|v| v - node_value).collect();
+        self.updates.visits.push(Visit {
+            key,
+            iteration,
+            averages,
+            regret: Some((traverser_index, instant_regret, action_values)),
+        });
+        node_value
     }
 }
 
-// TODO: Make Struct with regret trait
 // TODO: Make Struct with strategy updating rule
-// TODO: Calculate Exploitability
-// TODO: Plot live exploitability in ratatui
-// TODO: Stop iteration when exploitability sufficiently small
 // https://arxiv.org/pdf/1407.5042
 // https://openreview.net/pdf?id=rJx4p3NYDB
 // ESCHER
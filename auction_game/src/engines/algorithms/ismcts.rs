@@ -0,0 +1,451 @@
+use crate::engines::abstractions::bid_abstraction::BidAbstraction;
+use crate::engines::abstractions::progressive_widening::ProgressiveWidening;
+use crate::engines::beliefs::belief_tracker::BeliefTracker;
+use crate::engines::traits::{OpponentModel, RolloutPolicy};
+use crate::engines::utils::sample_strategy_with;
+use crate::models::enums::{GamePhase, Player};
+use crate::models::game_state::GameState;
+use ahash::AHashMap;
+use crossbeam::scope;
+use log::info;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Copy, Clone, Debug)]
+pub enum SearchBudget {
+    Iterations(usize),
+    Time(Duration),
+}
+
+#[derive(Clone, Debug)]
+pub struct ActionStats {
+    pub action: u8,
+    pub visits: u32,
+    pub total_reward: f32,
+}
+
+impl ActionStats {
+    pub fn new(action: u8) -> Self {
+        ActionStats {
+            action,
+            visits: 0,
+            total_reward: 0.0,
+        }
+    }
+    pub fn mean_reward(&self) -> f32 {
+        if self.visits == 0 {
+            0.0
+        } else {
+            self.total_reward / self.visits as f32
+        }
+    }
+}
+
+struct Node {
+    game_phase: GamePhase,
+    visits: u32,
+    // Decoupled statistics, one list per player. In the bid phase only the player to move has any
+    stats: Vec<Vec<ActionStats>>,
+}
+
+// Information-set MCTS: each iteration determinises the hidden decks, so nodes keyed on the
+// path encoding (public history) are shared between every world consistent with it.
+pub struct ISMCTS {
+    rollout: Arc<dyn RolloutPolicy>,
+    budget: SearchBudget,
+    exploration: f32,
+    threads: usize,
+    seed: Option<u64>,
+    bool_log: bool,
+    bid_abstraction: Option<BidAbstraction>,
+    progressive_widening: Option<ProgressiveWidening>,
+    opponent_model: Option<Arc<dyn OpponentModel>>,
+    belief: Option<BeliefTracker>,
+    // Stops an iteration budget early when the clock runs out
+    time_limit: Option<Duration>,
+}
+
+impl ISMCTS {
+    pub fn new(
+        rollout: Arc<dyn RolloutPolicy>,
+        budget: SearchBudget,
+        exploration: f32,
+        threads: usize,
+        seed: Option<u64>,
+        bool_log: bool,
+    ) -> Self {
+        ISMCTS {
+            rollout,
+            budget,
+            exploration,
+            threads: threads.max(1),
+            seed,
+            bool_log,
+            bid_abstraction: None,
+            progressive_widening: None,
+            opponent_model: None,
+            belief: None,
+            time_limit: None,
+        }
+    }
+    pub fn with_bid_abstraction(mut self, bid_abstraction: BidAbstraction) -> Self {
+        self.bid_abstraction = Some(bid_abstraction);
+        self
+    }
+    // Bid nodes only consider their first few children and reveal larger raises as visits grow
+    pub fn with_progressive_widening(mut self, progressive_widening: ProgressiveWidening) -> Self {
+        self.progressive_widening = Some(progressive_widening);
+        self
+    }
+
+    // When searching for one player, the others pick their moves in the tree by sampling the
+    // model instead of by UCT
+    pub fn with_opponent_model(mut self, opponent_model: Arc<dyn OpponentModel>) -> Self {
+        self.opponent_model = Some(opponent_model);
+        self
+    }
+    pub fn set_opponent_model(&mut self, opponent_model: Option<Arc<dyn OpponentModel>>) {
+        self.opponent_model = opponent_model;
+    }
+
+    // Iterations determinise from the beliefs instead of only shuffling the decks
+    pub fn set_belief(&mut self, belief: Option<BeliefTracker>) {
+        self.belief = belief;
+    }
+
+    pub fn set_time_limit(&mut self, time_limit: Option<Duration>) {
+        self.time_limit = time_limit;
+    }
+
+    pub fn best_action(&self, initial_state: &GameState, player: Player) -> u8 {
+        if initial_state.auction_end() {
+            return 0;
+        }
+        let legal_moves = self.search_moves(initial_state, player);
+        if legal_moves.len() == 1 {
+            return legal_moves[0];
+        }
+        let root_stats = self.search_as(initial_state, Some(player));
+        Self::most_visited(&root_stats[player as usize]).unwrap_or(legal_moves[0])
+    }
+
+    pub fn best_joint_action(&self, initial_state: &GameState) -> Vec<u8> {
+        debug_assert!(
+            initial_state.game_phase() == GamePhase::Sell,
+            "Joint actions are only chosen in the sell phase"
+        );
+        let root_stats = self.search(initial_state);
+        (0..initial_state.no_players())
+            .map(|player| {
+                Self::most_visited(&root_stats[player as usize])
+                    .unwrap_or(initial_state.legal_moves(player)[0])
+            })
+            .collect()
+    }
+
+    pub fn search(&self, initial_state: &GameState) -> Vec<Vec<ActionStats>> {
+        self.search_as(initial_state, None)
+    }
+
+    // Runs one independent tree per thread (root parallelism) and sums their root statistics.
+    // Opponents of searcher are played by the opponent model if there is one.
+    fn search_as(
+        &self,
+        initial_state: &GameState,
+        searcher: Option<Player>,
+    ) -> Vec<Vec<ActionStats>> {
+        let start = Instant::now();
+        let base_seed: u64 = self.seed.unwrap_or_else(|| thread_rng().random());
+        let results: Vec<(Vec<Vec<ActionStats>>, usize)> = scope(|s| {
+            let handles: Vec<_> = (0..self.threads)
+                .map(|thread| {
+                    let seed = base_seed.wrapping_add(thread as u64);
+                    s.spawn(move |_| self.search_tree(initial_state, searcher, seed, start))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("ISMCTS worker panicked"))
+                .collect()
+        })
+        .expect("ISMCTS scope failed");
+
+        let mut root_stats: Vec<Vec<ActionStats>> =
+            vec![Vec::new(); initial_state.no_players() as usize];
+        let mut total_iterations: usize = 0;
+        for (thread_stats, iterations) in results {
+            total_iterations += iterations;
+            for (merged, player_stats) in root_stats.iter_mut().zip(thread_stats) {
+                for stats in player_stats {
+                    match merged.iter_mut().find(|m| m.action == stats.action) {
+                        Some(existing) => {
+                            existing.visits += stats.visits;
+                            existing.total_reward += stats.total_reward;
+                        }
+                        None => merged.push(stats),
+                    }
+                }
+            }
+        }
+        if self.bool_log {
+            info!(
+                "ISMCTS ran {} iterations on {} threads with {} rollouts for: {:?}",
+                total_iterations,
+                self.threads,
+                self.rollout.name(),
+                start.elapsed()
+            );
+            for (player, stats) in root_stats.iter().enumerate() {
+                if !stats.is_empty() {
+                    info!("ISMCTS root P{}: {:?}", player + 1, stats);
+                }
+            }
+        }
+        root_stats
+    }
+
+    fn search_tree(
+        &self,
+        initial_state: &GameState,
+        searcher: Option<Player>,
+        seed: u64,
+        start: Instant,
+    ) -> (Vec<Vec<ActionStats>>, usize) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut tree: AHashMap<String, Node> = AHashMap::with_capacity(10000);
+        let mut iterations: usize = 0;
+        while iterations == 0 || !self.budget_spent(iterations, start) {
+            self.iterate(initial_state, searcher, &mut tree, &mut rng);
+            iterations += 1;
+        }
+        let root_stats = match tree.remove(&initial_state.get_path_encoding()) {
+            Some(node) => node.stats,
+            None => vec![Vec::new(); initial_state.no_players() as usize],
+        };
+        (root_stats, iterations)
+    }
+
+    fn budget_spent(&self, iterations: usize, start: Instant) -> bool {
+        if self
+            .time_limit
+            .is_some_and(|time_limit| start.elapsed() >= time_limit)
+        {
+            return true;
+        }
+        match self.budget {
+            SearchBudget::Iterations(total) => iterations >= total.div_ceil(self.threads),
+            SearchBudget::Time(limit) => start.elapsed() >= limit,
+        }
+    }
+
+    fn iterate(
+        &self,
+        initial_state: &GameState,
+        searcher: Option<Player>,
+        tree: &mut AHashMap<String, Node>,
+        rng: &mut StdRng,
+    ) {
+        let mut game_state = match &self.belief {
+            Some(belief) => belief.determinise(initial_state, rng),
+            None => {
+                let mut game_state = initial_state.clone();
+                game_state.determinise(rng);
+                game_state
+            }
+        };
+        let mut visited: Vec<(String, Vec<Option<usize>>)> = Vec::with_capacity(64);
+        // Selection and expansion
+        while !game_state.game_end() {
+            if game_state.auction_end() {
+                // Chance node, the determinised deck decides the reveal
+                game_state = game_state.generate_next_state_reveal();
+                continue;
+            }
+            let path = game_state.get_path_encoding();
+            let expanded = !tree.contains_key(&path);
+            let node = tree
+                .entry(path.clone())
+                .or_insert_with(|| self.expand(&game_state));
+            let choices = self.select(node, &game_state, searcher, rng);
+            game_state = Self::apply(&game_state, node, &choices);
+            visited.push((path, choices));
+            if expanded {
+                break;
+            }
+        }
+        let rewards = self.simulate(game_state, rng);
+        // Backpropagation
+        for (path, choices) in visited {
+            if let Some(node) = tree.get_mut(&path) {
+                node.visits += 1;
+                for (player, choice) in choices.iter().enumerate() {
+                    if let Some(index) = choice {
+                        let stats = &mut node.stats[player][*index];
+                        stats.visits += 1;
+                        stats.total_reward += rewards[player];
+                    }
+                }
+            }
+        }
+    }
+
+    fn search_moves(&self, game_state: &GameState, player: Player) -> Vec<u8> {
+        let legal_moves = game_state.legal_moves(player);
+        match (&self.bid_abstraction, game_state.game_phase()) {
+            (Some(bid_abstraction), GamePhase::Bid) => bid_abstraction.abstract_moves(&legal_moves),
+            _ => legal_moves,
+        }
+    }
+
+    fn expand(&self, game_state: &GameState) -> Node {
+        let no_players = game_state.no_players();
+        let mut stats: Vec<Vec<ActionStats>> = vec![Vec::new(); no_players as usize];
+        match game_state.game_phase() {
+            GamePhase::Bid => {
+                let player = game_state.current_player();
+                stats[player as usize] = self
+                    .search_moves(game_state, player)
+                    .into_iter()
+                    .map(ActionStats::new)
+                    .collect();
+            }
+            GamePhase::Sell => {
+                for player in 0..no_players {
+                    stats[player as usize] = game_state
+                        .legal_moves(player)
+                        .into_iter()
+                        .map(ActionStats::new)
+                        .collect();
+                }
+            }
+        }
+        Node {
+            game_phase: game_state.game_phase(),
+            visits: 0,
+            stats,
+        }
+    }
+
+    // UCT for the player to move, decoupled UCT when every player sells simultaneously
+    fn select(
+        &self,
+        node: &Node,
+        game_state: &GameState,
+        searcher: Option<Player>,
+        rng: &mut StdRng,
+    ) -> Vec<Option<usize>> {
+        let log_visits = (node.visits.max(1) as f32).ln();
+        node.stats
+            .iter()
+            .enumerate()
+            .map(|(player, player_stats)| {
+                if player_stats.is_empty() {
+                    return None;
+                }
+                if let (Some(opponent_model), Some(searcher)) = (&self.opponent_model, searcher) {
+                    if player != searcher as usize {
+                        let moves: Vec<u8> = player_stats.iter().map(|s| s.action).collect();
+                        let policy = opponent_model.policy(game_state, player as Player, &moves);
+                        return Some(sample_strategy_with(&policy, rng).min(moves.len() - 1));
+                    }
+                }
+                let allowed_children = match (&self.progressive_widening, node.game_phase) {
+                    (Some(widening), GamePhase::Bid) => {
+                        widening.allowed_children(node.visits, player_stats.len())
+                    }
+                    _ => player_stats.len(),
+                };
+                let player_stats = &player_stats[..allowed_children];
+                if let Some(unvisited) = player_stats.iter().position(|s| s.visits == 0) {
+                    return Some(unvisited);
+                }
+                let mut best_index: usize = 0;
+                let mut best_value: f32 = f32::MIN;
+                for (index, stats) in player_stats.iter().enumerate() {
+                    let value = stats.mean_reward()
+                        + self.exploration * (log_visits / stats.visits as f32).sqrt();
+                    if value > best_value {
+                        best_index = index;
+                        best_value = value;
+                    }
+                }
+                Some(best_index)
+            })
+            .collect()
+    }
+
+    fn apply(game_state: &GameState, node: &Node, choices: &[Option<usize>]) -> GameState {
+        match game_state.game_phase() {
+            GamePhase::Bid => {
+                let player = game_state.current_player();
+                let index = choices[player as usize].expect("Player to move has no choice");
+                game_state.manual_next_state_bid(player, node.stats[player as usize][index].action)
+            }
+            GamePhase::Sell => {
+                let player_choices: Vec<u8> = choices
+                    .iter()
+                    .enumerate()
+                    .map(|(player, choice)| {
+                        node.stats[player][choice.expect("Every player sells simultaneously")]
+                            .action
+                    })
+                    .collect();
+                game_state.manual_next_state_sell(player_choices)
+            }
+        }
+    }
+
+    fn simulate(&self, mut game_state: GameState, rng: &mut StdRng) -> Vec<f32> {
+        while !game_state.game_end() {
+            if game_state.auction_end() {
+                game_state = game_state.generate_next_state_reveal();
+                continue;
+            }
+            game_state = match game_state.game_phase() {
+                GamePhase::Bid => {
+                    let player = game_state.current_player();
+                    let action = self.rollout.choose(&game_state, player, rng);
+                    game_state.manual_next_state_bid(player, action)
+                }
+                GamePhase::Sell => {
+                    let player_choices: Vec<u8> = (0..game_state.no_players())
+                        .map(|player| self.rollout.choose(&game_state, player, rng))
+                        .collect();
+                    game_state.manual_next_state_sell(player_choices)
+                }
+            };
+        }
+        Self::terminal_rewards(&game_state)
+    }
+
+    // Final score of each player relative to the winner, in [0, 1]
+    fn terminal_rewards(game_state: &GameState) -> Vec<f32> {
+        let totals: Vec<f32> = (0..game_state.no_players())
+            .map(|player| {
+                game_state
+                    .get_player_checks(player)
+                    .iter()
+                    .map(|&check| check as f32)
+                    .sum::<f32>()
+                    + game_state.get_player_coins(player) as f32
+            })
+            .collect();
+        let max_total = totals.iter().cloned().fold(f32::MIN, f32::max).max(1.0);
+        totals.iter().map(|total| total / max_total).collect()
+    }
+
+    fn most_visited(stats: &[ActionStats]) -> Option<u8> {
+        stats
+            .iter()
+            .max_by(|a, b| {
+                a.visits.cmp(&b.visits).then(
+                    a.mean_reward()
+                        .partial_cmp(&b.mean_reward())
+                        .unwrap_or(std::cmp::Ordering::Equal),
+                )
+            })
+            .map(|stats| stats.action)
+    }
+}
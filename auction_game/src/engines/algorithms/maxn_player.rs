@@ -1,30 +1,37 @@
+use crate::engines::abstractions::bid_abstraction::BidAbstraction;
 use crate::engines::constants::VALUE_PER_PROPERTY;
-use crate::engines::traits::PlayerController;
+use crate::engines::scorers::sell_phase_score::SellPhaseScore;
+use crate::engines::traits::OpponentModel;
 use crate::models::enums::{GamePhase, Player, Property};
 use crate::models::game_state::GameState;
 use ahash::AHashMap;
 use log::{debug, info, warn};
 use num_traits::float::FloatCore;
-use rand::rngs::ThreadRng;
+use rand::rngs::StdRng;
 use rand::seq::IndexedRandom;
-use rand::thread_rng;
+use rand::{thread_rng, SeedableRng};
 use std::cmp;
+use std::sync::Arc;
 use std::time::Instant;
 
 pub struct MaxNPlayer {
     id: u8,
     nickname: String,
-    rng: ThreadRng,
+    rng: StdRng,
     buffer: Vec<GameState>,
     // GameState encoding, Player Scores, number of child nodes remaining, average count
     scores: AHashMap<String, (GameState, Vec<f32>, usize, usize)>,
     bool_print: bool,
     bool_log: bool,
+    bid_abstraction: Option<BidAbstraction>,
+    sell_phase_score: Option<SellPhaseScore>,
+    opponent_model: Option<Arc<dyn OpponentModel>>,
+    last_leaf_node_count: u64,
 }
 
 impl MaxNPlayer {
     pub fn new(id: u8, nickname: String, bool_print: bool, bool_log: bool) -> Self {
-        let rng = thread_rng();
+        let rng = StdRng::from_rng(thread_rng());
         let buffer: Vec<GameState> = Vec::with_capacity(10000);
         let scores: AHashMap<String, (GameState, Vec<f32>, usize, usize)> =
             AHashMap::with_capacity(30000);
@@ -36,6 +43,52 @@ impl MaxNPlayer {
             scores,
             bool_print,
             bool_log,
+            bid_abstraction: None,
+            sell_phase_score: None,
+            opponent_model: None,
+            last_leaf_node_count: 0,
+        }
+    }
+    // Leaves that reach the sell phase are valued by playing out the sell phase instead of
+    // the round heuristic
+    pub fn with_sell_phase_score(mut self, sell_phase_score: SellPhaseScore) -> Self {
+        self.sell_phase_score = Some(sell_phase_score);
+        self
+    }
+    pub fn with_bid_abstraction(mut self, bid_abstraction: BidAbstraction) -> Self {
+        self.bid_abstraction = Some(bid_abstraction);
+        self
+    }
+    // Opponent nodes only expand the move the model expects instead of maximising the opponent
+    pub fn with_opponent_model(mut self, opponent_model: Arc<dyn OpponentModel>) -> Self {
+        self.opponent_model = Some(opponent_model);
+        self
+    }
+    pub fn set_opponent_model(&mut self, opponent_model: Option<Arc<dyn OpponentModel>>) {
+        self.opponent_model = opponent_model;
+    }
+    pub fn last_leaf_node_count(&self) -> u64 {
+        self.last_leaf_node_count
+    }
+    fn search_moves(&self, game_state: &GameState) -> Vec<u8> {
+        let current_player = game_state.current_player();
+        let legal_moves = game_state.legal_moves(current_player);
+        let moves = match &self.bid_abstraction {
+            Some(bid_abstraction) => bid_abstraction.abstract_moves(&legal_moves),
+            None => legal_moves,
+        };
+        match &self.opponent_model {
+            Some(opponent_model) if current_player != self.id && moves.len() > 1 => {
+                let policy = opponent_model.policy(game_state, current_player, &moves);
+                let expected_index = policy
+                    .iter()
+                    .enumerate()
+                    .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(cmp::Ordering::Equal))
+                    .map(|(index, _)| index)
+                    .unwrap_or(0);
+                vec![moves[expected_index]]
+            }
+            _ => moves,
         }
     }
     pub fn maximax_round(
@@ -50,7 +103,7 @@ impl MaxNPlayer {
         let terminal_round: u8 = initial_state.round_no() + rounds;
         let mut leaf_node_count: u64 = 0;
         let initial_path_encoding = initial_state.get_path_encoding();
-        let legal_moves = initial_state.legal_moves(initial_state.current_player());
+        let legal_moves = self.search_moves(initial_state);
         for action in &legal_moves {
             self.buffer
                 .push(initial_state.manual_next_state_bid(initial_state.current_player(), *action));
@@ -103,8 +156,19 @@ impl MaxNPlayer {
         self.get_best_action(initial_state, start, &leaf_node_count)
     }
 
+    fn leaf_score(&mut self, leaf_state: &GameState) -> Vec<f32> {
+        match &mut self.sell_phase_score {
+            Some(sell_phase_score)
+                if leaf_state.game_phase() == GamePhase::Sell && !leaf_state.game_end() =>
+            {
+                sell_phase_score.round_score_function(leaf_state)
+            }
+            _ => Self::round_score_function(leaf_state),
+        }
+    }
+
     fn deepen_search(&mut self, mut leaf_state: GameState) {
-        let legal_moves = leaf_state.legal_moves(leaf_state.current_player());
+        let legal_moves = self.search_moves(&leaf_state);
         let child_states_count: usize = legal_moves.len();
         for action in legal_moves {
             let child_state = leaf_state.manual_next_state_bid(leaf_state.current_player(), action);
@@ -129,7 +193,7 @@ impl MaxNPlayer {
     ) -> u8 {
         let mut best_action: u8 = 0;
         let mut best_score: f32 = f32::MIN;
-        for action in initial_state.legal_moves(initial_state.current_player()) {
+        for action in self.search_moves(initial_state) {
             let next_state =
                 initial_state.manual_next_state_bid(initial_state.current_player(), action);
             if let Some((_, score, _, _)) = self.scores.get(&next_state.get_path_encoding()) {
@@ -154,6 +218,7 @@ impl MaxNPlayer {
             }
         }
 
+        self.last_leaf_node_count = *leaf_node_count;
         info!("MAXN algo ran for: {:?}", start.elapsed());
         info!("Ended with leaf_nodes count: {}", leaf_node_count);
         best_action
@@ -165,7 +230,7 @@ impl MaxNPlayer {
         initial_path_encoding: &str,
         mut leaf_state: &mut GameState,
     ) {
-        let mut score = MaxNPlayer::round_score_function(&leaf_state);
+        let mut score = self.leaf_score(leaf_state);
         let mut parent_hash = leaf_state.get_parent_encoding();
         let mut update_parent_further = true;
         let mut remove_from_scores = false;
@@ -173,12 +238,7 @@ impl MaxNPlayer {
         if leaf_state.turn_no() == initial_state.turn_no() + 1 {
             self.scores.insert(
                 leaf_state.get_path_encoding(),
-                (
-                    leaf_state.clone(),
-                    Self::round_score_function(&leaf_state),
-                    0,
-                    0,
-                ),
+                (leaf_state.clone(), score.clone(), 0, 0),
             );
         }
 
@@ -0,0 +1,287 @@
+use crate::models::enums::{Check, Coins, GamePhase, Player, Property};
+use crate::models::game_state::GameState;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// What is known about one player: the properties seen going to it, how many more it holds
+// without us knowing which, its coins as a range and the checks it has won
+#[derive(Clone, Debug)]
+pub struct PlayerBelief {
+    known_properties: Vec<Property>,
+    unknown_properties: usize,
+    min_coins: Coins,
+    max_coins: Coins,
+    checks: Vec<Check>,
+}
+
+impl PlayerBelief {
+    pub fn new(
+        mut known_properties: Vec<Property>,
+        unknown_properties: usize,
+        min_coins: Coins,
+        max_coins: Coins,
+        mut checks: Vec<Check>,
+    ) -> Self {
+        debug_assert!(min_coins <= max_coins, "Coin range is empty");
+        known_properties.sort_unstable();
+        checks.sort_unstable();
+        PlayerBelief {
+            known_properties,
+            unknown_properties,
+            min_coins,
+            max_coins,
+            checks,
+        }
+    }
+    pub fn known_properties(&self) -> &[Property] {
+        &self.known_properties
+    }
+    pub fn unknown_properties(&self) -> usize {
+        self.unknown_properties
+    }
+    pub fn coins(&self) -> (Coins, Coins) {
+        (self.min_coins, self.max_coins)
+    }
+    pub fn checks(&self) -> &[Check] {
+        &self.checks
+    }
+    pub fn is_exact(&self) -> bool {
+        self.unknown_properties == 0 && self.min_coins == self.max_coins
+    }
+}
+
+// Beliefs over every player's holdings and coins built only from public events: reveals, bids
+// and passes, and the properties shown when checks are handed out.
+// Auctions are open, so tracking from the first reveal keeps every belief exact. A tracker
+// started part way through keeps the holdings it was not told about as unknown, each equally
+// likely to be any property not seen yet, and narrows coin ranges as players bid.
+#[derive(Clone, Debug)]
+pub struct BeliefTracker {
+    players: Vec<PlayerBelief>,
+    // Properties not seen anywhere yet, the deck together with every unknown holding
+    unseen_properties: Vec<Property>,
+    // Checks not turned up yet, the deck together with any set aside
+    unseen_checks: Vec<Check>,
+    game_phase: GamePhase,
+    // Sorted highest first like GameState's
+    auction_pool: Vec<u8>,
+    active_bids: Vec<Coins>,
+    active_players: Vec<bool>,
+}
+
+impl BeliefTracker {
+    pub fn new(no_players: u8) -> Self {
+        let starting_coins = GameState::starting_coins(no_players);
+        let players = (0..no_players)
+            .map(|_| PlayerBelief::new(Vec::new(), 0, starting_coins, starting_coins, Vec::new()))
+            .collect();
+        BeliefTracker::from_beliefs(
+            players,
+            GameState::all_properties(),
+            GameState::all_checks(),
+            GamePhase::Bid,
+        )
+    }
+    // For a tracker joining between auctions, unseen_properties being every property not known
+    // to be held or sold and unseen_checks every check not turned up yet
+    pub fn from_beliefs(
+        players: Vec<PlayerBelief>,
+        unseen_properties: Vec<Property>,
+        unseen_checks: Vec<Check>,
+        game_phase: GamePhase,
+    ) -> Self {
+        let no_players = players.len();
+        BeliefTracker {
+            players,
+            unseen_properties,
+            unseen_checks,
+            game_phase,
+            auction_pool: Vec::with_capacity(no_players),
+            active_bids: vec![0; no_players],
+            active_players: vec![true; no_players],
+        }
+    }
+    pub fn no_players(&self) -> u8 {
+        self.players.len() as u8
+    }
+    pub fn player(&self, player: Player) -> &PlayerBelief {
+        &self.players[player as usize]
+    }
+    pub fn unseen_properties(&self) -> &[Property] {
+        &self.unseen_properties
+    }
+    pub fn unseen_checks(&self) -> &[Check] {
+        &self.unseen_checks
+    }
+    pub fn is_exact(&self) -> bool {
+        self.players.iter().all(PlayerBelief::is_exact)
+    }
+    // Chance player holds property, unknown holdings being spread evenly over the unseen ones
+    pub fn holding_probability(&self, player: Player, property: Property) -> f32 {
+        let belief = &self.players[player as usize];
+        if belief.known_properties.contains(&property) {
+            return 1.0;
+        }
+        if belief.unknown_properties == 0 || !self.unseen_properties.contains(&property) {
+            return 0.0;
+        }
+        belief.unknown_properties as f32 / self.unseen_properties.len() as f32
+    }
+
+    pub fn observe_reveal(&mut self, game_phase: GamePhase, auction_pool: &[u8]) {
+        self.game_phase = game_phase;
+        let deck = match game_phase {
+            GamePhase::Bid => &mut self.unseen_properties,
+            GamePhase::Sell => &mut self.unseen_checks,
+        };
+        for card in auction_pool.iter() {
+            if let Some(position) = deck.iter().position(|unseen| unseen == card) {
+                deck.remove(position);
+            }
+        }
+        self.auction_pool = auction_pool.to_vec();
+        self.auction_pool.sort_unstable_by(|a, b| b.cmp(a));
+        self.active_bids.iter_mut().for_each(|bid| *bid = 0);
+        self.active_players
+            .iter_mut()
+            .for_each(|active| *active = true);
+    }
+    // Reads a newly revealed auction off the public state if the tracker has not seen it yet
+    pub fn observe_auction(&mut self, game_state: &GameState) {
+        if self.auction_pool.is_empty() && !game_state.auction_end() {
+            self.observe_reveal(game_state.game_phase(), game_state.get_auction_pool());
+        }
+    }
+    // Same hooks as PlayerController, game_state is the public state before the move
+    pub fn observe_bid(&mut self, game_state: &GameState, player: Player, action: Coins) {
+        self.observe_auction(game_state);
+        let index = player as usize;
+        if action > 0 {
+            let belief = &mut self.players[index];
+            // Raising shows the player had at least action coins
+            belief.min_coins = belief.min_coins.max(action) - action;
+            belief.max_coins = belief.max_coins.saturating_sub(action);
+            self.active_bids[index] += action;
+            return;
+        }
+        let refund = self.active_bids[index] / 2;
+        let belief = &mut self.players[index];
+        belief.min_coins += refund;
+        belief.max_coins += refund;
+        self.take_lowest(player);
+        self.active_players[index] = false;
+        self.active_bids[index] = 0;
+        if self.auction_pool.len() == 1 {
+            // The last player in pays its bid, which already left its coins, for the last card
+            if let Some(winner) = self.active_players.iter().position(|&active| active) {
+                self.take_lowest(winner as Player);
+            }
+            self.active_bids.iter_mut().for_each(|bid| *bid = 0);
+            self.active_players
+                .iter_mut()
+                .for_each(|active| *active = true);
+        }
+    }
+    // sales[player] is the property player sold, shown when the checks are handed out
+    pub fn observe_sales(&mut self, game_state: &GameState, sales: &[Property]) {
+        self.observe_auction(game_state);
+        let mut ordered_sales: Vec<(usize, Property)> = sales.iter().copied().enumerate().collect();
+        ordered_sales.sort_unstable_by_key(|(_, property)| *property);
+        for (player, property) in ordered_sales {
+            if let Some(check) = self.auction_pool.pop() {
+                let checks = &mut self.players[player].checks;
+                let position = checks.partition_point(|&held| held < check);
+                checks.insert(position, check);
+            }
+            let belief = &mut self.players[player];
+            if let Some(position) = belief
+                .known_properties
+                .iter()
+                .position(|&held| held == property)
+            {
+                belief.known_properties.remove(position);
+            } else {
+                debug_assert!(
+                    belief.unknown_properties > 0,
+                    "Player {player} sold {property} which it cannot hold"
+                );
+                belief.unknown_properties = belief.unknown_properties.saturating_sub(1);
+                self.unseen_properties.retain(|&unseen| unseen != property);
+            }
+        }
+    }
+
+    fn take_lowest(&mut self, player: Player) {
+        if let Some(property) = self.auction_pool.pop() {
+            let known_properties = &mut self.players[player as usize].known_properties;
+            let position = known_properties.partition_point(|&held| held < property);
+            known_properties.insert(position, property);
+        }
+    }
+
+    // GameState::get_holdings_encoding of the believed state, with ?? for each unknown property,
+    // so it is the CFR information set key whenever the beliefs are exact
+    pub fn holdings_encoding(&self) -> String {
+        fn push_u8(encoding: &mut String, num: u8) {
+            encoding.push((b'0' + num / 10) as char);
+            encoding.push((b'0' + num % 10) as char);
+        }
+        let mut encoding = String::with_capacity(160);
+        encoding.push_str(match self.game_phase {
+            GamePhase::Bid => "Bid|p",
+            GamePhase::Sell => "Sell|p",
+        });
+        for (player, belief) in self.players.iter().enumerate() {
+            if player > 0 {
+                encoding.push('-');
+            }
+            belief
+                .known_properties
+                .iter()
+                .for_each(|&property| push_u8(&mut encoding, property));
+            (0..belief.unknown_properties).for_each(|_| encoding.push_str("??"));
+        }
+        encoding.push_str("|ch");
+        for (player, belief) in self.players.iter().enumerate() {
+            if player > 0 {
+                encoding.push('-');
+            }
+            belief
+                .checks
+                .iter()
+                .for_each(|&check| push_u8(&mut encoding, check));
+        }
+        encoding.push_str("|a");
+        self.auction_pool
+            .iter()
+            .for_each(|&check| push_u8(&mut encoding, check));
+        encoding
+    }
+
+    // A world consistent with the beliefs: unknown holdings are dealt from the unseen properties,
+    // uncertain coins drawn from their range and the decks drawn from what is left, so the cards
+    // set aside stay unseen. game_state supplies everything public.
+    pub fn determinise<R: Rng>(&self, game_state: &GameState, rng: &mut R) -> GameState {
+        let mut unseen = self.unseen_properties.clone();
+        unseen.shuffle(rng);
+        let mut sampled_state = game_state.clone();
+        for (player, belief) in self.players.iter().enumerate() {
+            let mut properties = belief.known_properties.clone();
+            let dealt = belief.unknown_properties.min(unseen.len());
+            properties.extend(unseen.drain(..dealt));
+            sampled_state.set_player_properties(player as Player, properties);
+            let coins = if belief.min_coins == belief.max_coins {
+                belief.min_coins
+            } else {
+                rng.gen_range(belief.min_coins..=belief.max_coins)
+            };
+            sampled_state.set_player_coins(player as Player, coins);
+        }
+        if sampled_state.game_phase() == GamePhase::Bid {
+            unseen.truncate(game_state.get_remaining_properties().len());
+            sampled_state.set_remaining_properties(unseen);
+        }
+        sampled_state.determinise(rng);
+        sampled_state
+    }
+}
@@ -0,0 +1,61 @@
+use crate::engines::algorithms::counterfactual_regret::CFR;
+use crate::engines::q_values::regret::CFRPlus;
+use crate::engines::traits::PlayerController;
+use crate::models::enums::GamePhase;
+use crate::models::game_state::GameState;
+use std::sync::Arc;
+
+// Plays the abstracted bid phase strategy found by MCCFR. Positions missing from the table,
+// and the sell phase, are solved from the current state before sampling a move.
+pub struct CFRBidder {
+    id: u8,
+    nickname: String,
+    cfr: CFR,
+    bid_iterations: usize,
+    sell_iterations: usize,
+}
+
+impl CFRBidder {
+    pub fn new(id: u8, nickname: String) -> Self {
+        CFRBidder::with_cfr(id, nickname, CFR::new(Arc::new(CFRPlus {}), false))
+    }
+    // Uses a strategy already trained offline, e.g. by test_cfr_bidder
+    pub fn with_cfr(id: u8, nickname: String, cfr: CFR) -> Self {
+        CFRBidder {
+            id,
+            nickname,
+            cfr,
+            bid_iterations: 20000,
+            sell_iterations: 300,
+        }
+    }
+    pub fn with_iterations(mut self, bid_iterations: usize, sell_iterations: usize) -> Self {
+        self.bid_iterations = bid_iterations;
+        self.sell_iterations = sell_iterations;
+        self
+    }
+    pub fn cfr(&self) -> &CFR {
+        &self.cfr
+    }
+}
+
+impl PlayerController for CFRBidder {
+    fn nickname(&self) -> String {
+        self.nickname.clone()
+    }
+    fn decision(&mut self, game_state: &GameState) -> u8 {
+        if game_state.game_phase() == GamePhase::Bid
+            && (game_state.auction_end() || game_state.current_player() != self.id)
+        {
+            return 0;
+        }
+        let iterations = match game_state.game_phase() {
+            GamePhase::Bid => self.bid_iterations,
+            GamePhase::Sell => self.sell_iterations,
+        };
+        if game_state.game_phase() == GamePhase::Sell || !self.cfr.game_state_added(game_state) {
+            self.cfr.find_nash(game_state, iterations);
+        }
+        self.cfr.get_mixed_strategy(game_state, self.id)
+    }
+}
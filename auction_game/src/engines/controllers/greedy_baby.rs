@@ -1,28 +1,101 @@
+use crate::engines::abstractions::bid_abstraction::BidAbstraction;
+use crate::engines::algorithms::best_response::BestResponse;
 use crate::engines::algorithms::counterfactual_regret::CFR;
 use crate::engines::algorithms::maxn_player::MaxNPlayer;
+use crate::engines::q_values::regret::CFRPlus;
+use crate::engines::scorers::sell_phase_score::SellPhaseScore;
+use crate::engines::tablebases::sell_tablebase::SellTablebase;
 use crate::engines::traits::PlayerController;
 use crate::models::enums::GamePhase;
 use crate::models::game_state::GameState;
+use std::sync::Arc;
 
 pub struct GreedyBaby {
     id: u8,
     nickname: String,
     maxn_controller: MaxNPlayer,
     cfr_controller: CFR,
+    tablebase: SellTablebase,
+    exploitability_target: f32,
+    max_cfr_iterations: usize,
+    search_rounds: u8,
+    search_samples: u32,
+    // Lookahead per bid round, overrides search_rounds when set
+    round_schedule: Vec<u8>,
 }
 
 impl GreedyBaby {
     pub fn new(id: u8, nickname: String) -> Self {
         // TODO: consider making bool_print inputs?
-        let maxn_controller = MaxNPlayer::new(id, nickname.clone(), false, false);
-        let cfr_controller = CFR::new(false);
+        // Last bid round leaves land in the sell phase and are valued by playing it out
+        let maxn_controller = MaxNPlayer::new(id, nickname.clone(), false, false)
+            .with_sell_phase_score(SellPhaseScore::default());
+        let cfr_controller =
+            CFR::new(Arc::new(CFRPlus {}), false).with_exploitability(BestResponse::default(), 100);
         GreedyBaby {
             id,
             nickname,
             maxn_controller,
             cfr_controller,
+            tablebase: SellTablebase::default(),
+            exploitability_target: 2.0,
+            max_cfr_iterations: 300,
+            search_rounds: 1,
+            search_samples: 0,
+            round_schedule: Vec::new(),
         }
     }
+    // Starts from a table solved offline, see CFR::load
+    pub fn with_cfr(mut self, cfr_controller: CFR) -> Self {
+        self.cfr_controller = cfr_controller;
+        self
+    }
+    // Sell rounds are solved until the NashConv drops to target checks or max_iterations run
+    pub fn with_cfr_budget(mut self, exploitability_target: f32, max_iterations: usize) -> Self {
+        self.exploitability_target = exploitability_target;
+        self.max_cfr_iterations = max_iterations;
+        self
+    }
+    // Bid rounds MaxN looks ahead, sampling that many reveals of each later auction, 0 for all
+    pub fn with_search(mut self, rounds: u8, samples: u32) -> Self {
+        self.search_rounds = rounds.max(1);
+        self.search_samples = samples;
+        self
+    }
+    // Lookahead for each bid round in turn, the last entry covering the later rounds
+    pub fn with_round_schedule(mut self, round_schedule: Vec<u8>) -> Self {
+        self.round_schedule = round_schedule;
+        self
+    }
+    // Searches only the abstracted raises, needed for the deep bid trees of 3 and 4 players
+    pub fn with_bid_abstraction(mut self, bid_abstraction: BidAbstraction) -> Self {
+        self.maxn_controller = self.maxn_controller.with_bid_abstraction(bid_abstraction);
+        self
+    }
+    // Starts from endgames solved offline, see SellTablebase::load
+    pub fn with_tablebase(mut self, tablebase: SellTablebase) -> Self {
+        self.tablebase = tablebase;
+        self
+    }
+
+    // Own draw from the equilibrium of the sell round
+    fn sell(&mut self, game_state: &GameState) -> u8 {
+        // Exact play for the last few rounds, solving the endgame if it is not stored yet
+        if self.tablebase.covers(game_state) {
+            if self.tablebase.probe(game_state).is_none() {
+                self.tablebase.generate(game_state);
+            }
+            if let Some(action) = self.tablebase.sample_action(game_state, self.id) {
+                return action;
+            }
+        }
+        self.cfr_controller.find_nash_until(
+            game_state,
+            self.exploitability_target,
+            self.max_cfr_iterations,
+        );
+        self.cfr_controller.get_mixed_strategy(game_state, self.id)
+    }
 }
 
 impl PlayerController for GreedyBaby {
@@ -30,17 +103,23 @@ impl PlayerController for GreedyBaby {
         self.nickname.clone()
     }
     fn decision(&mut self, game_state: &GameState) -> u8 {
-        // TODO: Make this depend on game phase too so many can play against a bot
-        self.maxn_controller.maximax_round(&game_state, 1, false, 0)
-    }
-    fn batch_decision(&mut self, game_state: &GameState) -> Vec<u8> {
-        // TODO: Make iterations a param
-        self.cfr_controller.find_nash(game_state, 10000);
-        let mut aggregate_actions: Vec<u8> = Vec::with_capacity(game_state.no_players() as usize);
-        for player in 0..game_state.no_players() {
-            let action = self.cfr_controller.get_mixed_strategy(game_state, player);
-            aggregate_actions.push(action);
+        match game_state.game_phase() {
+            GamePhase::Bid => {
+                if game_state.auction_end() || game_state.current_player() != self.id {
+                    return 0;
+                }
+                let rounds = match self.round_schedule.get(game_state.round_no() as usize) {
+                    Some(&rounds) => rounds,
+                    None => *self.round_schedule.last().unwrap_or(&self.search_rounds),
+                };
+                self.maxn_controller.maximax_round(
+                    game_state,
+                    rounds.max(1),
+                    self.search_samples > 0,
+                    self.search_samples,
+                )
+            }
+            GamePhase::Sell => self.sell(game_state),
         }
-        aggregate_actions
     }
 }
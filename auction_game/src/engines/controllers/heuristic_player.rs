@@ -0,0 +1,232 @@
+use crate::engines::traits::PlayerController;
+use crate::models::enums::{Coins, GamePhase, Player, Property};
+use crate::models::game_state::GameState;
+
+const HIGHEST_CHECK: f32 = 15.0;
+
+// Which property to give up for the checks on offer
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SellPolicy {
+    // Property at the same position among the holdings as the checks on offer among all checks
+    Matched,
+    // Highest property when a check of at least this value is on offer, lowest otherwise
+    Threshold(u8),
+    // Always the lowest property
+    Cheapest,
+}
+
+// Knobs of HeuristicPlayer
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeuristicPersonality {
+    name: String,
+    // Most it will bid in total, per point of the best property in the auction
+    max_price_per_value: f32,
+    // Passes straight away when the best property beats the one a pass takes by less than this
+    min_gain: f32,
+    // Coins kept back for each auction still to come after this one
+    coin_reserve_per_round: f32,
+    // Coins bid above the minimum raise when it raises
+    raise_step: Coins,
+    sell_policy: SellPolicy,
+}
+
+impl HeuristicPersonality {
+    pub fn new(
+        name: String,
+        max_price_per_value: f32,
+        min_gain: f32,
+        coin_reserve_per_round: f32,
+        raise_step: Coins,
+        sell_policy: SellPolicy,
+    ) -> Self {
+        HeuristicPersonality {
+            name,
+            max_price_per_value: max_price_per_value.max(0.0),
+            min_gain: min_gain.max(0.0),
+            coin_reserve_per_round: coin_reserve_per_round.max(0.0),
+            raise_step,
+            sell_policy,
+        }
+    }
+    pub fn balanced() -> Self {
+        HeuristicPersonality::new(
+            "balanced".to_string(),
+            0.35,
+            2.0,
+            0.5,
+            0,
+            SellPolicy::Matched,
+        )
+    }
+    // Bids little, keeps coins for later auctions
+    pub fn cautious() -> Self {
+        HeuristicPersonality::new(
+            "cautious".to_string(),
+            0.25,
+            3.0,
+            1.0,
+            0,
+            SellPolicy::Matched,
+        )
+    }
+    // Bids high and jumps the price, sells its best property for the big checks
+    pub fn aggressive() -> Self {
+        HeuristicPersonality::new(
+            "aggressive".to_string(),
+            0.5,
+            0.0,
+            0.0,
+            2,
+            SellPolicy::Threshold(12),
+        )
+    }
+    // Passes nearly every auction to finish with as many coins as possible
+    pub fn hoarder() -> Self {
+        HeuristicPersonality::new(
+            "hoarder".to_string(),
+            0.15,
+            6.0,
+            2.0,
+            0,
+            SellPolicy::Matched,
+        )
+    }
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "balanced" => Some(Self::balanced()),
+            "cautious" => Some(Self::cautious()),
+            "aggressive" => Some(Self::aggressive()),
+            "hoarder" => Some(Self::hoarder()),
+            _ => None,
+        }
+    }
+    pub fn preset_names() -> [&'static str; 4] {
+        ["balanced", "cautious", "aggressive", "hoarder"]
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn max_price_per_value(&self) -> f32 {
+        self.max_price_per_value
+    }
+    pub fn min_gain(&self) -> f32 {
+        self.min_gain
+    }
+    pub fn coin_reserve_per_round(&self) -> f32 {
+        self.coin_reserve_per_round
+    }
+    pub fn raise_step(&self) -> Coins {
+        self.raise_step
+    }
+    pub fn sell_policy(&self) -> SellPolicy {
+        self.sell_policy
+    }
+}
+
+impl Default for HeuristicPersonality {
+    fn default() -> Self {
+        HeuristicPersonality::balanced()
+    }
+}
+
+// Rule based bot for both phases, cheap enough to fill tournaments with plausible opponents
+pub struct HeuristicPlayer {
+    id: u8,
+    nickname: String,
+    personality: HeuristicPersonality,
+}
+
+impl HeuristicPlayer {
+    pub fn new(id: u8, nickname: String) -> Self {
+        HeuristicPlayer {
+            id,
+            nickname,
+            personality: HeuristicPersonality::default(),
+        }
+    }
+    pub fn with_personality(mut self, personality: HeuristicPersonality) -> Self {
+        self.personality = personality;
+        self
+    }
+    pub fn personality(&self) -> &HeuristicPersonality {
+        &self.personality
+    }
+
+    // Coins player adds to its bid, 0 to pass
+    pub fn bid(&self, game_state: &GameState, player: Player) -> Coins {
+        let legal_moves = game_state.legal_moves_bid(player);
+        if legal_moves.len() == 1 {
+            return 0;
+        }
+        let pool = game_state.get_auction_pool();
+        // The pool is sorted highest first and a pass takes the lowest property
+        let highest = pool.first().copied().unwrap_or(0) as f32;
+        let lowest = pool.last().copied().unwrap_or(0) as f32;
+        if highest - lowest < self.personality.min_gain {
+            return 0;
+        }
+        let own_bid = game_state.get_active_bids()[player as usize];
+        let rounds_left =
+            game_state.get_remaining_properties().len() as f32 / game_state.no_players() as f32;
+        let budget = (game_state.get_player_coins(player) + own_bid) as f32
+            - self.personality.coin_reserve_per_round * rounds_left;
+        let max_price = (self.personality.max_price_per_value * highest)
+            .min(budget)
+            .floor();
+        let price = game_state
+            .get_active_bids()
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or(0)
+            + 1;
+        if (price as f32) > max_price {
+            return 0;
+        }
+        let target = (price as f32 + self.personality.raise_step as f32).min(max_price) as Coins;
+        let action = target - own_bid;
+        if legal_moves.contains(&action) {
+            action
+        } else {
+            price - own_bid
+        }
+    }
+
+    pub fn sell(&self, game_state: &GameState, player: Player) -> Property {
+        let holdings = game_state.legal_moves_sell(player);
+        let pool = game_state.get_auction_pool();
+        let index = match self.personality.sell_policy {
+            SellPolicy::Matched => {
+                let strength = pool.iter().map(|&check| check as f32).sum::<f32>()
+                    / (pool.len().max(1) as f32 * HIGHEST_CHECK);
+                (strength * (holdings.len() - 1) as f32).round() as usize
+            }
+            SellPolicy::Threshold(threshold) => {
+                if pool.first().copied().unwrap_or(0) >= threshold {
+                    holdings.len() - 1
+                } else {
+                    0
+                }
+            }
+            SellPolicy::Cheapest => 0,
+        };
+        holdings[index.min(holdings.len() - 1)]
+    }
+}
+
+impl PlayerController for HeuristicPlayer {
+    fn nickname(&self) -> String {
+        self.nickname.clone()
+    }
+    fn decision(&mut self, game_state: &GameState) -> u8 {
+        match game_state.game_phase() {
+            GamePhase::Bid => {
+                if game_state.auction_end() || game_state.current_player() != self.id {
+                    return 0;
+                }
+                self.bid(game_state, self.id)
+            }
+            GamePhase::Sell => self.sell(game_state, self.id),
+        }
+    }
+}
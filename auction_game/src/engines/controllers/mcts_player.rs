@@ -0,0 +1,105 @@
+use crate::engines::algorithms::ismcts::{SearchBudget, ISMCTS};
+use crate::engines::beliefs::belief_tracker::BeliefTracker;
+use crate::engines::opponent_models::observed_play::ObservedPlay;
+use crate::engines::opponent_models::threshold_model::ThresholdModel;
+use crate::engines::rollouts::random_rollout::RandomRollout;
+use crate::engines::traits::PlayerController;
+use crate::models::enums::{Coins, GamePhase, Player, Property};
+use crate::models::game_state::GameState;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub struct MCTSPlayer {
+    id: u8,
+    nickname: String,
+    search: ISMCTS,
+    observed_play: Option<ObservedPlay>,
+    belief_tracker: Option<BeliefTracker>,
+}
+
+impl MCTSPlayer {
+    pub fn new(id: u8, nickname: String) -> Self {
+        let search = ISMCTS::new(
+            Arc::new(RandomRollout {}),
+            SearchBudget::Iterations(5000),
+            0.7,
+            1,
+            None,
+            false,
+        );
+        MCTSPlayer {
+            id,
+            nickname,
+            search,
+            observed_play: None,
+            belief_tracker: None,
+        }
+    }
+    pub fn with_search(id: u8, nickname: String, search: ISMCTS) -> Self {
+        MCTSPlayer {
+            id,
+            nickname,
+            search,
+            observed_play: None,
+            belief_tracker: None,
+        }
+    }
+    // Tracks holdings and coins from the observed moves and searches over worlds consistent
+    // with them, for games where the controller is not shown the others' holdings
+    pub fn with_belief_tracking(mut self, no_players: u8) -> Self {
+        self.belief_tracker = Some(BeliefTracker::new(no_players));
+        self
+    }
+    pub fn belief_tracker(&self) -> Option<&BeliefTracker> {
+        self.belief_tracker.as_ref()
+    }
+    // Records what the other players do and searches against a ThresholdModel refitted before
+    // every decision
+    pub fn with_opponent_modelling(mut self, no_players: u8) -> Self {
+        self.observed_play = Some(ObservedPlay::new(no_players));
+        self
+    }
+    pub fn observed_play(&self) -> Option<&ObservedPlay> {
+        self.observed_play.as_ref()
+    }
+    fn refit_opponent_model(&mut self) {
+        if let Some(observed_play) = &self.observed_play {
+            self.search
+                .set_opponent_model(Some(Arc::new(ThresholdModel::fit(observed_play))));
+        }
+    }
+}
+
+impl PlayerController for MCTSPlayer {
+    fn nickname(&self) -> String {
+        self.nickname.clone()
+    }
+    fn decision(&mut self, game_state: &GameState) -> u8 {
+        if game_state.game_phase() == GamePhase::Bid && game_state.current_player() != self.id {
+            return 0;
+        }
+        self.refit_opponent_model();
+        self.search.set_belief(self.belief_tracker.clone());
+        self.search.best_action(game_state, self.id)
+    }
+    fn observe_bid(&mut self, game_state: &GameState, player: Player, action: Coins) {
+        if let Some(observed_play) = &mut self.observed_play {
+            observed_play.observe_bid(game_state, player, action);
+        }
+        if let Some(belief_tracker) = &mut self.belief_tracker {
+            belief_tracker.observe_bid(game_state, player, action);
+        }
+    }
+    fn observe_sales(&mut self, game_state: &GameState, sales: &[Property]) {
+        if let Some(observed_play) = &mut self.observed_play {
+            observed_play.observe_sales(game_state, sales);
+        }
+        if let Some(belief_tracker) = &mut self.belief_tracker {
+            belief_tracker.observe_sales(game_state, sales);
+        }
+    }
+    fn set_move_time_limit(&mut self, limit: Duration) {
+        // A tenth is left for the rest of the move
+        self.search.set_time_limit(Some(limit * 9 / 10));
+    }
+}
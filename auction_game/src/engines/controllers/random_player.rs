@@ -1,13 +1,13 @@
 use crate::engines::traits::PlayerController;
 use crate::models::game_state::GameState;
-use rand::rngs::ThreadRng;
+use rand::rngs::StdRng;
 use rand::seq::IndexedRandom;
-use rand::thread_rng;
+use rand::{thread_rng, SeedableRng};
 
 pub struct RandomPlayer {
     id: u8,
     nickname: String,
-    rng: ThreadRng,
+    rng: StdRng,
 }
 
 impl PlayerController for RandomPlayer {
@@ -23,13 +23,10 @@ impl PlayerController for RandomPlayer {
         );
         *legal_moves.choose(&mut self.rng).unwrap()
     }
-    fn batch_decision(&mut self, game_state: &GameState) -> Vec<u8> {
-        todo!()
-    }
 }
 impl RandomPlayer {
     pub fn new(id: u8, nickname: String) -> Self {
-        let rng = thread_rng();
+        let rng = StdRng::from_rng(thread_rng());
         RandomPlayer { id, nickname, rng }
     }
 }
@@ -21,15 +21,19 @@ impl PlayerController for HumanPlayer {
         );
 
         // Prompt the user to input their choice
+        println!("{game_state}");
         println!("{}, it's your turn!", self.nickname);
         println!("Available moves: {:?}", legal_moves);
         println!("Enter your choice:");
 
         loop {
             let mut input = String::new();
-            io::stdin()
+            let read = io::stdin()
                 .read_line(&mut input)
                 .expect("Failed to read line");
+            if read == 0 {
+                panic!("Input closed before {} chose a move", self.nickname);
+            }
 
             match input.trim().parse() {
                 Ok(choice) if legal_moves.contains(&choice) => return choice,
@@ -39,9 +43,6 @@ impl PlayerController for HumanPlayer {
             }
         }
     }
-    fn batch_decision(&mut self, game_state: &GameState) -> Vec<u8> {
-        todo!()
-    }
 }
 
 impl HumanPlayer {
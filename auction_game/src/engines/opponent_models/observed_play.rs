@@ -0,0 +1,122 @@
+use crate::models::enums::{Coins, GamePhase, Player, Property};
+use crate::models::game_state::GameState;
+
+pub const HIGHEST_CHECK: f32 = 15.0;
+
+// One decision of a player in an auction, described by the state it was made in
+#[derive(Clone, Debug)]
+pub struct BidObservation {
+    // How much better the average property in the pool is than the one a pass takes
+    pub gain: f32,
+    // Total bid the player has to reach to stay in the auction
+    pub price: Coins,
+    // Coins the player could commit, counting its current bid
+    pub available: Coins,
+    // Coins bid above the minimum raise, None for a pass
+    pub extra_raise: Option<Coins>,
+}
+
+// One sale of a player, described by the checks on offer and the property it gave up
+#[derive(Clone, Debug)]
+pub struct SaleObservation {
+    // Position of the sold property among the player's holdings, 0 lowest and 1 highest
+    pub property_quantile: f32,
+    // Average check on offer over the highest check in the game
+    pub check_strength: f32,
+}
+
+// Bids, passes and sales seen from every seat, kept across games so models keep improving
+#[derive(Clone, Debug, Default)]
+pub struct ObservedPlay {
+    bids: Vec<Vec<BidObservation>>,
+    sales: Vec<Vec<SaleObservation>>,
+}
+
+impl ObservedPlay {
+    pub fn new(no_players: u8) -> Self {
+        ObservedPlay {
+            bids: vec![Vec::new(); no_players as usize],
+            sales: vec![Vec::new(); no_players as usize],
+        }
+    }
+    pub fn no_players(&self) -> u8 {
+        self.bids.len() as u8
+    }
+    pub fn bids(&self, player: Player) -> &[BidObservation] {
+        &self.bids[player as usize]
+    }
+    pub fn sales(&self, player: Player) -> &[SaleObservation] {
+        &self.sales[player as usize]
+    }
+    // Records player adding action coins to its bid in game_state, 0 being a pass.
+    // Forced passes say nothing about the player and are skipped.
+    pub fn observe_bid(&mut self, game_state: &GameState, player: Player, action: Coins) {
+        if game_state.game_phase() != GamePhase::Bid || game_state.auction_end() {
+            return;
+        }
+        if game_state.legal_moves_bid(player).len() <= 1 {
+            return;
+        }
+        let (gain, price, available) = Self::bid_features(game_state, player);
+        let extra_raise = match action {
+            0 => None,
+            _ => {
+                Some((game_state.get_active_bids()[player as usize] + action).saturating_sub(price))
+            }
+        };
+        self.bids[player as usize].push(BidObservation {
+            gain,
+            price,
+            available,
+            extra_raise,
+        });
+    }
+    // Records every player's sale in game_state, sales[player] being the property sold
+    pub fn observe_sales(&mut self, game_state: &GameState, sales: &[Property]) {
+        if game_state.game_phase() != GamePhase::Sell || game_state.auction_end() {
+            return;
+        }
+        let check_strength = Self::check_strength(game_state);
+        for (player, &property) in sales.iter().enumerate() {
+            let holdings = game_state.get_player_properties(player as Player);
+            if let Some(property_quantile) = Self::property_quantile(holdings, property) {
+                self.sales[player].push(SaleObservation {
+                    property_quantile,
+                    check_strength,
+                });
+            }
+        }
+    }
+
+    // Gain of staying in, price of staying in and coins available to player
+    pub fn bid_features(game_state: &GameState, player: Player) -> (f32, Coins, Coins) {
+        let pool = game_state.get_auction_pool();
+        // The pool is sorted highest first and a pass takes the lowest property
+        let lowest = pool.last().copied().unwrap_or(0) as f32;
+        let average =
+            pool.iter().map(|&property| property as f32).sum::<f32>() / pool.len().max(1) as f32;
+        let price = game_state
+            .get_active_bids()
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or(0)
+            + 1;
+        let available =
+            game_state.get_player_coins(player) + game_state.get_active_bids()[player as usize];
+        (average - lowest, price, available)
+    }
+    pub fn check_strength(game_state: &GameState) -> f32 {
+        let pool = game_state.get_auction_pool();
+        pool.iter().map(|&check| check as f32).sum::<f32>()
+            / (pool.len().max(1) as f32 * HIGHEST_CHECK)
+    }
+    // None when the player holds a single property and so had no choice
+    pub fn property_quantile(holdings: &[Property], property: Property) -> Option<f32> {
+        if holdings.len() <= 1 {
+            return None;
+        }
+        let index = holdings.iter().position(|&held| held == property)?;
+        Some(index as f32 / (holdings.len() - 1) as f32)
+    }
+}
@@ -0,0 +1,239 @@
+use crate::engines::opponent_models::observed_play::ObservedPlay;
+use crate::engines::traits::OpponentModel;
+use crate::models::enums::{Coins, GamePhase, Player};
+use crate::models::game_state::GameState;
+
+const HIGHEST_PROPERTY: f32 = 30.0;
+const MOST_COINS: f32 = 18.0;
+// Observations a prior is worth, so a few early decisions do not decide the whole model
+const PRIOR_STRENGTH: f32 = 4.0;
+const FIT_STEPS: usize = 300;
+const LEARNING_RATE: f32 = 2.0;
+// Logistic weights of [1, gain, available, price] for a player nothing is known about yet
+const BID_PRIOR: [f32; 4] = [1.0, 12.0, 1.0, -10.0];
+const EXTRA_RAISE_PRIOR: f32 = 0.3;
+// Sold quantile = intercept + slope * check strength, with this spread
+const SALE_PRIOR: (f32, f32, f32) = (0.1, 0.8, 0.3);
+
+// Whether a player stays in an auction, as a logistic function of what staying can gain, the
+// coins it has and the price of staying, plus how far above the minimum it raises
+#[derive(Clone, Debug)]
+pub struct BidModel {
+    weights: [f32; 4],
+    mean_extra_raise: f32,
+    observations: usize,
+}
+
+// Which property a player sells, as a linear function of how strong the checks on offer are
+#[derive(Clone, Debug)]
+pub struct SaleModel {
+    intercept: f32,
+    slope: f32,
+    spread: f32,
+    observations: usize,
+}
+
+// Per player models fitted to observed play, pulled towards a generic player until there is
+// enough evidence
+#[derive(Clone, Debug)]
+pub struct ThresholdModel {
+    bid_models: Vec<BidModel>,
+    sale_models: Vec<SaleModel>,
+}
+
+impl BidModel {
+    fn features(gain: f32, price: Coins, available: Coins) -> [f32; 4] {
+        [
+            1.0,
+            gain / HIGHEST_PROPERTY,
+            available as f32 / MOST_COINS,
+            price as f32 / MOST_COINS,
+        ]
+    }
+    pub fn fit(observed: &ObservedPlay, player: Player) -> Self {
+        let bids = observed.bids(player);
+        let samples: Vec<([f32; 4], f32)> = bids
+            .iter()
+            .map(|bid| {
+                let stayed = if bid.extra_raise.is_some() { 1.0 } else { 0.0 };
+                (Self::features(bid.gain, bid.price, bid.available), stayed)
+            })
+            .collect();
+        // Gradient descent on the log loss with a Gaussian prior around BID_PRIOR
+        let mut weights = BID_PRIOR;
+        let normaliser = samples.len() as f32 + PRIOR_STRENGTH;
+        for _ in 0..FIT_STEPS {
+            let mut gradient: [f32; 4] = [0.0; 4];
+            for (features, stayed) in samples.iter() {
+                let error = Self::logistic(&weights, features) - stayed;
+                for (g, x) in gradient.iter_mut().zip(features.iter()) {
+                    *g += error * x;
+                }
+            }
+            for ((w, g), prior) in weights.iter_mut().zip(gradient.iter()).zip(BID_PRIOR) {
+                *w -= LEARNING_RATE * (g + PRIOR_STRENGTH * (*w - prior)) / normaliser;
+            }
+        }
+        let raises: Vec<f32> = bids
+            .iter()
+            .filter_map(|bid| bid.extra_raise.map(|extra| extra as f32))
+            .collect();
+        let mean_extra_raise = (raises.iter().sum::<f32>() + PRIOR_STRENGTH * EXTRA_RAISE_PRIOR)
+            / (raises.len() as f32 + PRIOR_STRENGTH);
+        BidModel {
+            weights,
+            mean_extra_raise,
+            observations: bids.len(),
+        }
+    }
+    fn logistic(weights: &[f32; 4], features: &[f32; 4]) -> f32 {
+        let z: f32 = weights
+            .iter()
+            .zip(features.iter())
+            .map(|(w, x)| w * x)
+            .sum();
+        1.0 / (1.0 + (-z).exp())
+    }
+    pub fn weights(&self) -> &[f32; 4] {
+        &self.weights
+    }
+    pub fn mean_extra_raise(&self) -> f32 {
+        self.mean_extra_raise
+    }
+    pub fn observations(&self) -> usize {
+        self.observations
+    }
+    pub fn stay_probability(&self, gain: f32, price: Coins, available: Coins) -> f32 {
+        Self::logistic(&self.weights, &Self::features(gain, price, available))
+    }
+    // Total bid at which the player is as likely to pass as to stay in
+    pub fn pass_threshold(&self, gain: f32, available: Coins) -> f32 {
+        let [bias, gain_weight, coins_weight, price_weight] = self.weights;
+        if price_weight >= 0.0 {
+            return available as f32;
+        }
+        let z = bias
+            + gain_weight * gain / HIGHEST_PROPERTY
+            + coins_weight * available as f32 / MOST_COINS;
+        (-z / price_weight * MOST_COINS).clamp(0.0, available as f32)
+    }
+}
+
+impl SaleModel {
+    pub fn fit(observed: &ObservedPlay, player: Player) -> Self {
+        let sales = observed.sales(player);
+        let (prior_intercept, prior_slope, prior_spread) = SALE_PRIOR;
+        // Ridge regression towards the prior, solving the 2x2 normal equations
+        let n = sales.len() as f32;
+        let sum_s: f32 = sales.iter().map(|sale| sale.check_strength).sum();
+        let sum_ss: f32 = sales.iter().map(|sale| sale.check_strength.powi(2)).sum();
+        let sum_q: f32 = sales.iter().map(|sale| sale.property_quantile).sum();
+        let sum_qs: f32 = sales
+            .iter()
+            .map(|sale| sale.property_quantile * sale.check_strength)
+            .sum();
+        let (a11, a12, a22) = (n + PRIOR_STRENGTH, sum_s, sum_ss + PRIOR_STRENGTH);
+        let (b1, b2) = (
+            sum_q + PRIOR_STRENGTH * prior_intercept,
+            sum_qs + PRIOR_STRENGTH * prior_slope,
+        );
+        let determinant = a11 * a22 - a12 * a12;
+        let intercept = (b1 * a22 - b2 * a12) / determinant;
+        let slope = (a11 * b2 - a12 * b1) / determinant;
+        let squared_residuals: f32 = sales
+            .iter()
+            .map(|sale| (sale.property_quantile - intercept - slope * sale.check_strength).powi(2))
+            .sum();
+        let spread = ((squared_residuals + PRIOR_STRENGTH * prior_spread.powi(2))
+            / (n + PRIOR_STRENGTH))
+            .sqrt()
+            .max(0.05);
+        SaleModel {
+            intercept,
+            slope,
+            spread,
+            observations: sales.len(),
+        }
+    }
+    pub fn intercept(&self) -> f32 {
+        self.intercept
+    }
+    pub fn slope(&self) -> f32 {
+        self.slope
+    }
+    pub fn spread(&self) -> f32 {
+        self.spread
+    }
+    pub fn observations(&self) -> usize {
+        self.observations
+    }
+    pub fn expected_quantile(&self, check_strength: f32) -> f32 {
+        (self.intercept + self.slope * check_strength).clamp(0.0, 1.0)
+    }
+}
+
+impl ThresholdModel {
+    pub fn fit(observed: &ObservedPlay) -> Self {
+        ThresholdModel {
+            bid_models: (0..observed.no_players())
+                .map(|player| BidModel::fit(observed, player))
+                .collect(),
+            sale_models: (0..observed.no_players())
+                .map(|player| SaleModel::fit(observed, player))
+                .collect(),
+        }
+    }
+    pub fn bid_model(&self, player: Player) -> &BidModel {
+        &self.bid_models[player as usize]
+    }
+    pub fn sale_model(&self, player: Player) -> &SaleModel {
+        &self.sale_models[player as usize]
+    }
+
+    // Passing with the chance of not staying in, raising with the rest spread geometrically
+    // over how far above the minimum raise each move is
+    fn bid_policy(&self, game_state: &GameState, player: Player, moves: &[u8]) -> Vec<f32> {
+        let (gain, price, available) = ObservedPlay::bid_features(game_state, player);
+        let model = self.bid_model(player);
+        let stay = model.stay_probability(gain, price, available);
+        let minimum_raise = price.saturating_sub(game_state.get_active_bids()[player as usize]);
+        let ratio = model.mean_extra_raise / (1.0 + model.mean_extra_raise);
+        moves
+            .iter()
+            .map(|&action| match action {
+                0 => 1.0 - stay,
+                _ => stay * (1.0 - ratio) * ratio.powi(action.saturating_sub(minimum_raise) as i32),
+            })
+            .collect()
+    }
+
+    fn sale_policy(&self, game_state: &GameState, player: Player, moves: &[u8]) -> Vec<f32> {
+        let model = self.sale_model(player);
+        let expected = model.expected_quantile(ObservedPlay::check_strength(game_state));
+        let holdings = game_state.get_player_properties(player);
+        moves
+            .iter()
+            .map(|&property| {
+                let quantile = ObservedPlay::property_quantile(holdings, property).unwrap_or(0.5);
+                (-(quantile - expected).powi(2) / (2.0 * model.spread.powi(2))).exp()
+            })
+            .collect()
+    }
+}
+
+impl OpponentModel for ThresholdModel {
+    fn name(&self) -> String {
+        "Threshold".to_string()
+    }
+    fn policy(&self, game_state: &GameState, player: Player, moves: &[u8]) -> Vec<f32> {
+        let weights = match game_state.game_phase() {
+            GamePhase::Bid => self.bid_policy(game_state, player, moves),
+            GamePhase::Sell => self.sale_policy(game_state, player, moves),
+        };
+        let total: f32 = weights.iter().sum();
+        if total <= 0.0 || !total.is_finite() {
+            return vec![1.0 / moves.len() as f32; moves.len()];
+        }
+        weights.iter().map(|weight| weight / total).collect()
+    }
+}
@@ -1,12 +1,153 @@
-pub struct Regret {}
+use crate::engines::strategies::average::AverageWeighting;
+use std::sync::Arc;
 
-impl Regret {
-    pub fn q_values(stored_q_values: &mut Vec<f32>, score: &Vec<f32>) {
-        let max_score = score.iter().fold(f32::NEG_INFINITY, |a, &b| a.max(b));
-        let regret: Vec<f32> = score
+// How cumulative regrets are accumulated and turned into the next strategy.
+// Sampled CFR does not visit every information set on every iteration, so discounts are applied
+// with the iteration number of the visit rather than once per iteration for every node.
+pub trait RegretUpdater: Send + Sync {
+    fn name(&self) -> String;
+    // Adds the regrets of the latest visit to the stored cumulative regrets
+    fn update(&self, stored_regret: &mut [f32], instant_regret: &[f32], iteration: usize);
+    // Strategy to play next, last_regret is the latest instant regret when predictive
+    fn strategy(&self, strategy: &mut [f32], stored_regret: &[f32], last_regret: &[f32]) {
+        let _ = last_regret;
+        regret_matching(strategy, stored_regret);
+    }
+    // Whether CFR has to keep the latest instant regret of every information set
+    fn predictive(&self) -> bool {
+        false
+    }
+}
+
+// Plays each action in proportion to its positive regret, uniformly if none is positive
+pub fn regret_matching(strategy: &mut [f32], regrets: &[f32]) {
+    let total: f32 = regrets.iter().map(|q| q.max(0.0)).sum();
+    if total <= 0.0 {
+        let uniform = 1.0 / strategy.len() as f32;
+        strategy.iter_mut().for_each(|s| *s = uniform);
+        return;
+    }
+    for (s, q) in strategy.iter_mut().zip(regrets.iter()) {
+        *s = q.max(0.0) / total;
+    }
+}
+
+pub struct VanillaCFR {}
+impl RegretUpdater for VanillaCFR {
+    fn name(&self) -> String {
+        "CFR".to_string()
+    }
+    fn update(&self, stored_regret: &mut [f32], instant_regret: &[f32], _iteration: usize) {
+        for (q, r) in stored_regret.iter_mut().zip(instant_regret.iter()) {
+            *q += r;
+        }
+    }
+}
+
+// Cumulative regrets are floored at zero so a good action recovers quickly
+pub struct CFRPlus {}
+impl RegretUpdater for CFRPlus {
+    fn name(&self) -> String {
+        "CFR+".to_string()
+    }
+    fn update(&self, stored_regret: &mut [f32], instant_regret: &[f32], _iteration: usize) {
+        for (q, r) in stored_regret.iter_mut().zip(instant_regret.iter()) {
+            *q = (*q + r).max(0.0);
+        }
+    }
+}
+
+// Regrets from iteration t are weighted by t so early, poorly informed iterations fade out
+pub struct LinearCFR {}
+impl RegretUpdater for LinearCFR {
+    fn name(&self) -> String {
+        "Linear CFR".to_string()
+    }
+    fn update(&self, stored_regret: &mut [f32], instant_regret: &[f32], iteration: usize) {
+        let weight = iteration.max(1) as f32;
+        for (q, r) in stored_regret.iter_mut().zip(instant_regret.iter()) {
+            *q += weight * r;
+        }
+    }
+}
+
+// Brown & Sandholm 2019: positive regrets are discounted by t^alpha / (t^alpha + 1), negative
+// ones by t^beta / (t^beta + 1). Gamma is the discount of the average strategy,
+// (t / (t + 1))^gamma.
+pub struct DiscountedCFR {
+    alpha: f32,
+    beta: f32,
+    gamma: f32,
+}
+impl DiscountedCFR {
+    pub fn new(alpha: f32, beta: f32, gamma: f32) -> Self {
+        DiscountedCFR { alpha, beta, gamma }
+    }
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+    pub fn beta(&self) -> f32 {
+        self.beta
+    }
+    pub fn gamma(&self) -> f32 {
+        self.gamma
+    }
+    // Weighting the average strategy by t^gamma matches discounting it by (t / (t + 1))^gamma
+    pub fn average_weighting(&self) -> AverageWeighting {
+        let gamma = self.gamma;
+        AverageWeighting::Custom(Arc::new(move |iteration| {
+            (iteration.max(1) as f32).powf(gamma)
+        }))
+    }
+}
+impl Default for DiscountedCFR {
+    fn default() -> Self {
+        DiscountedCFR::new(1.5, 0.0, 2.0)
+    }
+}
+impl RegretUpdater for DiscountedCFR {
+    fn name(&self) -> String {
+        format!(
+            "Discounted CFR ({}, {}, {})",
+            self.alpha, self.beta, self.gamma
+        )
+    }
+    fn update(&self, stored_regret: &mut [f32], instant_regret: &[f32], iteration: usize) {
+        let t = iteration.max(1) as f32;
+        let positive_discount = t.powf(self.alpha) / (t.powf(self.alpha) + 1.0);
+        let negative_discount = t.powf(self.beta) / (t.powf(self.beta) + 1.0);
+        for (q, r) in stored_regret.iter_mut().zip(instant_regret.iter()) {
+            let discount = if *q > 0.0 {
+                positive_discount
+            } else {
+                negative_discount
+            };
+            *q = *q * discount + r;
+        }
+    }
+}
+
+// Farina et al. 2021: CFR+ regrets, but the next strategy also counts the latest instant
+// regret as a prediction of the coming one
+pub struct PredictiveCFRPlus {}
+impl RegretUpdater for PredictiveCFRPlus {
+    fn name(&self) -> String {
+        "Predictive CFR+".to_string()
+    }
+    fn update(&self, stored_regret: &mut [f32], instant_regret: &[f32], _iteration: usize) {
+        for (q, r) in stored_regret.iter_mut().zip(instant_regret.iter()) {
+            *q = (*q + r).max(0.0);
+        }
+    }
+    fn strategy(&self, strategy: &mut [f32], stored_regret: &[f32], last_regret: &[f32]) {
+        let predicted: Vec<f32> = stored_regret
             .iter()
-            .map(|&score| (max_score - score).max(0.0))
+            .enumerate()
+            .map(|(index, q)| q + last_regret.get(index).unwrap_or(&0.0))
             .collect();
-        todo!()
+        regret_matching(strategy, &predicted);
+    }
+    fn predictive(&self) -> bool {
+        true
     }
 }
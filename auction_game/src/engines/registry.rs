@@ -0,0 +1,556 @@
+use crate::engines::abstractions::bid_abstraction::BidAbstraction;
+use crate::engines::algorithms::ismcts::{SearchBudget, ISMCTS};
+use crate::engines::controllers::cfr_bidder::CFRBidder;
+use crate::engines::controllers::greedy_baby::GreedyBaby;
+use crate::engines::controllers::heuristic_player::{
+    HeuristicPersonality, HeuristicPlayer, SellPolicy,
+};
+use crate::engines::controllers::mcts_player::MCTSPlayer;
+use crate::engines::controllers::random_player::RandomPlayer;
+use crate::engines::controllers::terminal_player::HumanPlayer;
+use crate::engines::rollouts::greedy_rollout::GreedyRollout;
+use crate::engines::rollouts::random_rollout::RandomRollout;
+use crate::engines::tablebases::sell_tablebase::SellTablebase;
+use crate::engines::traits::{PlayerController, RolloutPolicy};
+use crate::models::enums::Player;
+use crate::tournaments::tournament::Entrant;
+use std::fmt::Write;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ParamKind {
+    Integer,
+    Float,
+    Bool,
+    Choice(&'static [&'static str]),
+    // A file, checked by the bot's CheckSpec
+    Path,
+}
+
+#[derive(Clone, Debug)]
+pub struct ParamInfo {
+    pub name: &'static str,
+    pub kind: ParamKind,
+    pub default: &'static str,
+    pub help: &'static str,
+}
+
+// Builds seat id of a game of no_players from a validated spec
+pub type BuildController = fn(&ControllerSpec, Player, u8) -> Box<dyn PlayerController>;
+// Rejects parameter combinations a bot's builder would not honour
+pub type CheckSpec = fn(&ControllerSpec) -> Result<(), String>;
+
+#[derive(Clone)]
+pub struct BotInfo {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub params: Vec<ParamInfo>,
+    pub build: BuildController,
+    pub check: CheckSpec,
+}
+
+// A bot name with its parameters, e.g. maxn:rounds=2,samples=50, checked against the registry
+#[derive(Clone, Debug)]
+pub struct ControllerSpec {
+    spec: String,
+    bot: String,
+    // Every parameter of the bot, given or default
+    values: Vec<(&'static str, String)>,
+    given: Vec<&'static str>,
+    build: BuildController,
+}
+
+impl ControllerSpec {
+    pub fn spec(&self) -> &str {
+        &self.spec
+    }
+    pub fn bot(&self) -> &str {
+        &self.bot
+    }
+    pub fn is_given(&self, name: &str) -> bool {
+        self.given.contains(&name)
+    }
+    // Value of a parameter, which parsing already checked
+    pub fn value<T: FromStr>(&self, name: &str) -> T {
+        let (_, value) = self
+            .values
+            .iter()
+            .find(|(param, _)| *param == name)
+            .unwrap_or_else(|| panic!("{} has no parameter {name}", self.bot));
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("{} parameter {name} has bad value {value}", self.bot))
+    }
+    // Integer parameter held in a u8, saturating
+    pub fn small_value(&self, name: &str) -> u8 {
+        self.value::<u32>(name).min(u8::MAX as u32) as u8
+    }
+    pub fn text(&self, name: &str) -> &str {
+        self.values
+            .iter()
+            .find(|(param, _)| *param == name)
+            .map(|(_, value)| value.as_str())
+            .unwrap_or_else(|| panic!("{} has no parameter {name}", self.bot))
+    }
+    pub fn build(&self, id: Player, no_players: u8) -> Box<dyn PlayerController> {
+        (self.build)(self, id, no_players)
+    }
+    // Tournament entrant named after the spec
+    pub fn entrant(&self) -> Entrant {
+        let spec = self.clone();
+        Entrant::new(
+            self.spec.clone(),
+            Arc::new(move |id, no_players| spec.build(id, no_players)),
+        )
+    }
+}
+
+// Every bot that can be named in a spec string, with its parameters
+#[derive(Clone)]
+pub struct ControllerRegistry {
+    bots: Vec<BotInfo>,
+}
+
+impl ControllerRegistry {
+    pub fn empty() -> Self {
+        ControllerRegistry { bots: Vec::new() }
+    }
+    // Replaces any bot of the same name
+    pub fn register(&mut self, bot: BotInfo) {
+        self.bots.retain(|registered| registered.name != bot.name);
+        self.bots.push(bot);
+    }
+    pub fn bots(&self) -> &[BotInfo] {
+        &self.bots
+    }
+    pub fn bot(&self, name: &str) -> Option<&BotInfo> {
+        self.bots.iter().find(|bot| bot.name == name)
+    }
+
+    pub fn parse(&self, spec: &str) -> Result<ControllerSpec, String> {
+        let spec = spec.trim();
+        let (name, params) = match spec.split_once(':') {
+            Some((name, params)) => (name.trim(), params),
+            None => (spec, ""),
+        };
+        let bot = self.bot(name).ok_or_else(|| {
+            format!(
+                "Unknown bot '{name}', expected one of: {}",
+                self.bots
+                    .iter()
+                    .map(|bot| bot.name)
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            )
+        })?;
+        let mut values: Vec<(&'static str, String)> = bot
+            .params
+            .iter()
+            .map(|param| (param.name, param.default.to_string()))
+            .collect();
+        let mut given: Vec<&'static str> = Vec::new();
+        for pair in params.split(',').filter(|pair| !pair.trim().is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| format!("Expected key=value in '{pair}' of {spec}"))?;
+            let param = bot
+                .params
+                .iter()
+                .find(|param| param.name == key)
+                .ok_or_else(|| {
+                    format!(
+                        "{name} has no parameter '{key}', expected one of: {}",
+                        bot.params
+                            .iter()
+                            .map(|param| param.name)
+                            .collect::<Vec<&str>>()
+                            .join(", ")
+                    )
+                })?;
+            if given.contains(&param.name) {
+                return Err(format!("{name} parameter {key} is given twice"));
+            }
+            validate(param, value).map_err(|expected| {
+                format!("{name} parameter {key} expects {expected}, got '{value}'")
+            })?;
+            if let Some(entry) = values.iter_mut().find(|(name, _)| *name == param.name) {
+                entry.1 = value.to_string();
+            }
+            given.push(param.name);
+        }
+        let parsed = ControllerSpec {
+            spec: spec.to_string(),
+            bot: name.to_string(),
+            values,
+            given,
+            build: bot.build,
+        };
+        (bot.check)(&parsed)?;
+        Ok(parsed)
+    }
+    pub fn build(
+        &self,
+        spec: &str,
+        id: Player,
+        no_players: u8,
+    ) -> Result<Box<dyn PlayerController>, String> {
+        Ok(self.parse(spec)?.build(id, no_players))
+    }
+    pub fn entrant(&self, spec: &str) -> Result<Entrant, String> {
+        Ok(self.parse(spec)?.entrant())
+    }
+
+    pub fn help(&self) -> String {
+        let mut help = String::from("Bots are given as name or name:param=value,param=value\n");
+        for bot in self.bots.iter() {
+            let _ = writeln!(help, "\n  {:<10} {}", bot.name, bot.help);
+            for param in bot.params.iter() {
+                let kind = match param.kind {
+                    ParamKind::Integer => "int".to_string(),
+                    ParamKind::Float => "float".to_string(),
+                    ParamKind::Bool => "bool".to_string(),
+                    ParamKind::Choice(choices) => choices.join("|"),
+                    ParamKind::Path => "path".to_string(),
+                };
+                let _ = writeln!(
+                    help,
+                    "      {:<14} {:<36} {} (default {})",
+                    param.name, kind, param.help, param.default
+                );
+            }
+        }
+        help
+    }
+}
+
+impl Default for ControllerRegistry {
+    fn default() -> Self {
+        let mut registry = ControllerRegistry::empty();
+        registry.register(BotInfo {
+            name: "random",
+            help: "Uniformly random legal moves",
+            params: Vec::new(),
+            build: |_, id, _| Box::new(RandomPlayer::new(id, format!("random_{id}"))),
+            check: |_| Ok(()),
+        });
+        registry.register(BotInfo {
+            name: "human",
+            help: "Moves typed in the terminal",
+            params: Vec::new(),
+            build: |_, id, _| Box::new(HumanPlayer::new(id, format!("human_{id}"))),
+            check: |_| Ok(()),
+        });
+        registry.register(BotInfo {
+            name: "heuristic",
+            help: "Rule based bidding and selling, a preset with any knob overridden",
+            params: vec![
+                param(
+                    "preset",
+                    ParamKind::Choice(&["balanced", "cautious", "aggressive", "hoarder"]),
+                    "balanced",
+                    "Personality to start from",
+                ),
+                param(
+                    "max_price",
+                    ParamKind::Float,
+                    "0.35",
+                    "Most bid per point of the best property",
+                ),
+                param(
+                    "min_gain",
+                    ParamKind::Float,
+                    "2",
+                    "Passes when the pool spread is below this",
+                ),
+                param(
+                    "reserve",
+                    ParamKind::Float,
+                    "0.5",
+                    "Coins kept back per auction to come",
+                ),
+                param(
+                    "raise",
+                    ParamKind::Integer,
+                    "0",
+                    "Coins bid above the minimum raise",
+                ),
+                param(
+                    "sell",
+                    ParamKind::Choice(&["matched", "cheapest", "threshold"]),
+                    "matched",
+                    "Which property to sell",
+                ),
+                param(
+                    "threshold",
+                    ParamKind::Integer,
+                    "12",
+                    "Check that makes a threshold sell policy sell its best",
+                ),
+            ],
+            build: build_heuristic,
+            check: check_heuristic,
+        });
+        registry.register(BotInfo {
+            name: "maxn",
+            help: "MaxN bid round search, CFR and a tablebase to sell, slow under 6 players",
+            params: vec![
+                param("rounds", ParamKind::Integer, "1", "Bid rounds searched"),
+                param(
+                    "samples",
+                    ParamKind::Integer,
+                    "0",
+                    "Reveals sampled per auction, 0 for all",
+                ),
+                param(
+                    "abstraction",
+                    ParamKind::Bool,
+                    "false",
+                    "Searches abstracted raises only, for 4 or 5 players",
+                ),
+                param(
+                    "sell_iters",
+                    ParamKind::Integer,
+                    "300",
+                    "Most CFR iterations per sell round",
+                ),
+                param(
+                    "target",
+                    ParamKind::Float,
+                    "2",
+                    "NashConv that ends a sell round solve",
+                ),
+                param(
+                    "tablebase",
+                    ParamKind::Path,
+                    "",
+                    "Sell endgame tablebase to start from",
+                ),
+            ],
+            build: build_maxn,
+            check: check_maxn,
+        });
+        registry.register(BotInfo {
+            name: "mcts",
+            help: "Information set MCTS over determinised worlds",
+            params: vec![
+                param("iters", ParamKind::Integer, "5000", "Iterations per move"),
+                param(
+                    "time_ms",
+                    ParamKind::Integer,
+                    "0",
+                    "Milliseconds per move instead of iters",
+                ),
+                param(
+                    "exploration",
+                    ParamKind::Float,
+                    "0.7",
+                    "UCB exploration constant",
+                ),
+                param("threads", ParamKind::Integer, "1", "Search threads"),
+                param(
+                    "rollout",
+                    ParamKind::Choice(&["random", "greedy"]),
+                    "random",
+                    "Playout policy",
+                ),
+                param(
+                    "model",
+                    ParamKind::Bool,
+                    "false",
+                    "Searches against fitted opponent models",
+                ),
+                param(
+                    "beliefs",
+                    ParamKind::Bool,
+                    "false",
+                    "Determinises from tracked beliefs",
+                ),
+            ],
+            build: build_mcts,
+            check: check_mcts,
+        });
+        registry.register(BotInfo {
+            name: "cfr",
+            help: "MCCFR strategy for the abstracted bid phase and each sell round",
+            params: vec![
+                param(
+                    "iters",
+                    ParamKind::Integer,
+                    "20000",
+                    "Iterations for an unseen bid position",
+                ),
+                param(
+                    "sell_iters",
+                    ParamKind::Integer,
+                    "300",
+                    "Iterations per sell round",
+                ),
+            ],
+            build: |spec, id, _| {
+                Box::new(
+                    CFRBidder::new(id, format!("cfr_{id}"))
+                        .with_iterations(spec.value("iters"), spec.value("sell_iters")),
+                )
+            },
+            check: |_| Ok(()),
+        });
+        registry
+    }
+}
+
+fn param(
+    name: &'static str,
+    kind: ParamKind,
+    default: &'static str,
+    help: &'static str,
+) -> ParamInfo {
+    ParamInfo {
+        name,
+        kind,
+        default,
+        help,
+    }
+}
+
+// What the parameter expects when value does not fit it
+fn validate(param: &ParamInfo, value: &str) -> Result<(), String> {
+    let valid = match param.kind {
+        ParamKind::Integer => value.parse::<u32>().is_ok(),
+        ParamKind::Float => value.parse::<f32>().is_ok_and(f32::is_finite),
+        ParamKind::Bool => value.parse::<bool>().is_ok(),
+        ParamKind::Choice(choices) => choices.contains(&value),
+        ParamKind::Path => !value.is_empty(),
+    };
+    match (valid, param.kind) {
+        (true, _) => Ok(()),
+        (false, ParamKind::Integer) => Err("a whole number".to_string()),
+        (false, ParamKind::Float) => Err("a number".to_string()),
+        (false, ParamKind::Bool) => Err("true or false".to_string()),
+        (false, ParamKind::Choice(choices)) => Err(format!("one of {}", choices.join(", "))),
+        (false, ParamKind::Path) => Err("a file path".to_string()),
+    }
+}
+
+fn heuristic_sell_policy(spec: &ControllerSpec, preset: &HeuristicPersonality) -> SellPolicy {
+    match (spec.is_given("sell"), spec.text("sell")) {
+        (false, _) => preset.sell_policy(),
+        (true, "cheapest") => SellPolicy::Cheapest,
+        (true, "threshold") => SellPolicy::Threshold(spec.small_value("threshold")),
+        (true, _) => SellPolicy::Matched,
+    }
+}
+
+// threshold only means something to a threshold sell policy, given or from the preset
+fn check_heuristic(spec: &ControllerSpec) -> Result<(), String> {
+    let preset = HeuristicPersonality::preset(spec.text("preset")).unwrap_or_default();
+    match (
+        spec.is_given("threshold"),
+        heuristic_sell_policy(spec, &preset),
+    ) {
+        (true, SellPolicy::Matched | SellPolicy::Cheapest) => Err(
+            "heuristic parameter threshold needs sell=threshold or a preset that sells by threshold"
+                .to_string(),
+        ),
+        _ => Ok(()),
+    }
+}
+
+// Personality a heuristic spec describes, knobs not given keeping the preset's value
+pub fn heuristic_personality(spec: &ControllerSpec) -> HeuristicPersonality {
+    let preset = HeuristicPersonality::preset(spec.text("preset")).unwrap_or_default();
+    let pick = |name: &str, preset_value: f32| match spec.is_given(name) {
+        true => spec.value(name),
+        false => preset_value,
+    };
+    let sell_policy = match heuristic_sell_policy(spec, &preset) {
+        SellPolicy::Threshold(_) if spec.is_given("threshold") => {
+            SellPolicy::Threshold(spec.small_value("threshold"))
+        }
+        sell_policy => sell_policy,
+    };
+    HeuristicPersonality::new(
+        spec.spec().to_string(),
+        pick("max_price", preset.max_price_per_value()),
+        pick("min_gain", preset.min_gain()),
+        pick("reserve", preset.coin_reserve_per_round()),
+        match spec.is_given("raise") {
+            true => spec.small_value("raise"),
+            false => preset.raise_step(),
+        },
+        sell_policy,
+    )
+}
+
+fn build_heuristic(spec: &ControllerSpec, id: Player, _: u8) -> Box<dyn PlayerController> {
+    let preset = HeuristicPersonality::preset(spec.text("preset")).unwrap_or_default();
+    Box::new(
+        HeuristicPlayer::new(id, format!("{}_{id}", preset.name()))
+            .with_personality(heuristic_personality(spec)),
+    )
+}
+
+// A tablebase file has to load before any seat is built from it
+fn check_maxn(spec: &ControllerSpec) -> Result<(), String> {
+    if !spec.is_given("tablebase") {
+        return Ok(());
+    }
+    SellTablebase::load(Path::new(spec.text("tablebase")))
+        .map(|_| ())
+        .map_err(|error| format!("maxn tablebase {}: {error}", spec.text("tablebase")))
+}
+
+fn build_maxn(spec: &ControllerSpec, id: Player, _: u8) -> Box<dyn PlayerController> {
+    let mut player = GreedyBaby::new(id, format!("maxn_{id}"))
+        .with_search(spec.small_value("rounds"), spec.value("samples"))
+        .with_cfr_budget(spec.value("target"), spec.value("sell_iters"));
+    if spec.value("abstraction") {
+        player = player.with_bid_abstraction(BidAbstraction::default());
+    }
+    if spec.is_given("tablebase") {
+        let tablebase = SellTablebase::load(Path::new(spec.text("tablebase")))
+            .unwrap_or_else(|error| panic!("maxn tablebase {}: {error}", spec.text("tablebase")));
+        player = player.with_tablebase(tablebase);
+    }
+    Box::new(player)
+}
+
+// A time budget replaces the iteration count, so giving both would silently drop iters
+fn check_mcts(spec: &ControllerSpec) -> Result<(), String> {
+    match spec.is_given("iters") && spec.is_given("time_ms") {
+        true => {
+            Err("mcts parameters iters and time_ms are alternative budgets, give one".to_string())
+        }
+        false => Ok(()),
+    }
+}
+
+fn build_mcts(spec: &ControllerSpec, id: Player, no_players: u8) -> Box<dyn PlayerController> {
+    let rollout: Arc<dyn RolloutPolicy> = match spec.text("rollout") {
+        "greedy" => Arc::new(GreedyRollout {}),
+        _ => Arc::new(RandomRollout {}),
+    };
+    let budget = match spec.value::<u64>("time_ms") {
+        0 => SearchBudget::Iterations(spec.value("iters")),
+        time_ms => SearchBudget::Time(Duration::from_millis(time_ms)),
+    };
+    let search = ISMCTS::new(
+        rollout,
+        budget,
+        spec.value("exploration"),
+        spec.value("threads"),
+        None,
+        false,
+    );
+    let mut player = MCTSPlayer::with_search(id, format!("mcts_{id}"), search);
+    if spec.value("model") {
+        player = player.with_opponent_modelling(no_players);
+    }
+    if spec.value("beliefs") {
+        player = player.with_belief_tracking(no_players);
+    }
+    Box::new(player)
+}
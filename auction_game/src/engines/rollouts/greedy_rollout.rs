@@ -0,0 +1,54 @@
+use crate::engines::constants::VALUE_PER_PROPERTY;
+use crate::engines::traits::RolloutPolicy;
+use crate::models::enums::{GamePhase, Player};
+use crate::models::game_state::GameState;
+use rand::rngs::StdRng;
+
+pub struct GreedyRollout {}
+
+impl RolloutPolicy for GreedyRollout {
+    fn name(&self) -> String {
+        "greedy".to_string()
+    }
+    fn choose(&self, game_state: &GameState, player: Player, _rng: &mut StdRng) -> u8 {
+        match game_state.game_phase() {
+            GamePhase::Bid => Self::choose_bid(game_state, player),
+            GamePhase::Sell => Self::choose_sell(game_state, player),
+        }
+    }
+}
+
+impl GreedyRollout {
+    fn choose_bid(game_state: &GameState, player: Player) -> u8 {
+        // Winning the auction is worth the spread between the best card and the card left for
+        // the first player to pass, so keep raising by the minimum while the bid stays below it
+        let legal_moves = game_state.legal_moves_bid(player);
+        let auction_pool = game_state.get_auction_pool();
+        if legal_moves.len() < 2 || auction_pool.is_empty() {
+            return 0;
+        }
+        let spread = (auction_pool[0] - auction_pool[auction_pool.len() - 1]) as f32;
+        let minimum_raise = legal_moves[1];
+        let total_bid = game_state.get_active_bids()[player as usize] + minimum_raise;
+        if total_bid as f32 <= spread * VALUE_PER_PROPERTY {
+            minimum_raise
+        } else {
+            0
+        }
+    }
+    fn choose_sell(game_state: &GameState, player: Player) -> u8 {
+        // Sell the best property only when it is guaranteed to take the top check
+        let properties = game_state.get_player_properties(player);
+        let highest = *properties.last().unwrap();
+        let lowest = *properties.first().unwrap();
+        let top_property = (0..game_state.no_players())
+            .filter(|&opponent| opponent != player)
+            .filter_map(|opponent| game_state.get_player_properties(opponent).last())
+            .all(|&property| property < highest);
+        if top_property {
+            highest
+        } else {
+            lowest
+        }
+    }
+}
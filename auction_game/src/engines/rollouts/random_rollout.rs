@@ -0,0 +1,22 @@
+use crate::engines::traits::RolloutPolicy;
+use crate::models::enums::Player;
+use crate::models::game_state::GameState;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+
+pub struct RandomRollout {}
+
+impl RolloutPolicy for RandomRollout {
+    fn name(&self) -> String {
+        "random".to_string()
+    }
+    fn choose(&self, game_state: &GameState, player: Player, rng: &mut StdRng) -> u8 {
+        let legal_moves: Vec<u8> = game_state.legal_moves(player);
+        debug_assert!(
+            !legal_moves.is_empty(),
+            "Legal Moves Provided for player {} is empty",
+            player
+        );
+        *legal_moves.choose(rng).unwrap()
+    }
+}
@@ -0,0 +1,76 @@
+use crate::engines::algorithms::counterfactual_regret::CFR;
+use crate::engines::q_values::regret::CFRPlus;
+use crate::models::enums::GamePhase;
+use crate::models::game_state::GameState;
+use ahash::AHashMap;
+use std::sync::Arc;
+
+// Values a sell phase position by approximately solving the rest of the sell phase with CFR and
+// reading off the checks each player is expected to collect under the equilibrium.
+// Coins do not change how the sell phase is played, so the expected checks are cached on the
+// holdings alone and shared between leaves that only differ in coins.
+pub struct SellPhaseScore {
+    cfr_iterations: usize,
+    cache: AHashMap<String, Vec<f32>>,
+}
+
+impl SellPhaseScore {
+    pub fn new(cfr_iterations: usize) -> Self {
+        SellPhaseScore {
+            cfr_iterations: cfr_iterations.max(1),
+            cache: AHashMap::with_capacity(1000),
+        }
+    }
+    pub fn cache_len(&self) -> usize {
+        self.cache.len()
+    }
+    pub fn expected_checks(&mut self, game_state: &GameState) -> Vec<f32> {
+        debug_assert!(
+            game_state.game_phase() == GamePhase::Sell,
+            "SellPhaseScore can only value sell phase positions"
+        );
+        let key = game_state.get_holdings_encoding();
+        if let Some(expected) = self.cache.get(&key) {
+            return expected.clone();
+        }
+        // A fresh solver per position keeps memory bounded over a long MaxN search
+        let mut cfr = CFR::new(Arc::new(CFRPlus {}), false);
+        cfr.find_nash(game_state, self.cfr_iterations);
+        let expected: Vec<f32> = cfr
+            .root_value()
+            .iter()
+            .enumerate()
+            .map(|(player, future_checks)| {
+                future_checks
+                    + game_state
+                        .get_player_checks(player as u8)
+                        .iter()
+                        .map(|&check| check as f32)
+                        .sum::<f32>()
+            })
+            .collect();
+        self.cache.insert(key, expected.clone());
+        expected
+    }
+    // Same form as a finished game in round_score_function: each player's share of all points
+    pub fn round_score_function(&mut self, game_state: &GameState) -> Vec<f32> {
+        let expected_checks = self.expected_checks(game_state);
+        let mut scores: Vec<f32> = expected_checks
+            .iter()
+            .zip(game_state.get_coins().iter())
+            .map(|(&checks, &coins)| checks + coins as f32)
+            .collect();
+        let total_score: f32 = scores.iter().sum::<f32>().max(1.0);
+        for score in scores.iter_mut() {
+            *score /= total_score;
+        }
+        scores
+    }
+}
+
+impl Default for SellPhaseScore {
+    fn default() -> Self {
+        // Rough, but each solve covers the whole sell phase and is cached
+        SellPhaseScore::new(20)
+    }
+}
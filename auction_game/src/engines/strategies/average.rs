@@ -1,4 +1,79 @@
-pub struct Average {}
+use std::sync::Arc;
+
+// How much the strategy of iteration t counts towards the average strategy
+#[derive(Clone)]
+pub enum AverageWeighting {
+    Uniform,
+    Linear,
+    Custom(Arc<dyn Fn(usize) -> f32 + Send + Sync>),
+}
+
+// Which of the strategies stored by CFR to play
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StrategyKind {
+    // Time averaged strategy, the one that converges to an equilibrium
+    Average,
+    // Strategy regret matching would play on the next iteration
+    Current,
+    // Most likely action of the average strategy
+    Purified,
+}
+
+#[derive(Clone)]
+pub struct Average {
+    weighting: AverageWeighting,
+}
+
 impl Average {
-    pub fn update_strategy(trained_score: &mut Vec<f32>, latest_score: &Vec<f32>) {}
+    pub fn new(weighting: AverageWeighting) -> Self {
+        Average { weighting }
+    }
+    pub fn weighting(&self) -> &AverageWeighting {
+        &self.weighting
+    }
+    pub fn weight(&self, iteration: usize) -> f32 {
+        match &self.weighting {
+            AverageWeighting::Uniform => 1.0,
+            AverageWeighting::Linear => iteration.max(1) as f32,
+            AverageWeighting::Custom(weight) => weight(iteration),
+        }
+    }
+    // Adds the latest strategy, scaled by how likely the player was to reach it
+    pub fn update_strategy(
+        &self,
+        cumulative_strategy: &mut [f32],
+        latest_strategy: &[f32],
+        reach: f32,
+        iteration: usize,
+    ) {
+        let weight = self.weight(iteration) * reach;
+        for (cumulative, s) in cumulative_strategy.iter_mut().zip(latest_strategy.iter()) {
+            *cumulative += weight * s;
+        }
+    }
+    // Normalised average strategy, None before anything was accumulated
+    pub fn strategy(cumulative_strategy: &[f32]) -> Option<Vec<f32>> {
+        let total: f32 = cumulative_strategy.iter().sum();
+        if total <= 0.0 || !total.is_finite() {
+            return None;
+        }
+        Some(cumulative_strategy.iter().map(|s| s / total).collect())
+    }
+    pub fn purify(strategy: &[f32]) -> Vec<f32> {
+        let mut purified = vec![0.0; strategy.len()];
+        if let Some((best_index, _)) = strategy
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+        {
+            purified[best_index] = 1.0;
+        }
+        purified
+    }
+}
+
+impl Default for Average {
+    fn default() -> Self {
+        Average::new(AverageWeighting::Linear)
+    }
 }
@@ -0,0 +1,686 @@
+use crate::engines::utils::sample_strategy;
+use crate::models::enums::{GamePhase, Player};
+use crate::models::game_state::GameState;
+use ahash::AHashMap;
+use helper::hashing::{empty_checksum, update_checksum};
+use itertools::Itertools;
+use log::{info, warn};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+const TABLEBASE_VERSION: u32 = 3;
+// Checks any player may gain by deviating from a stored mixed equilibrium
+const EQUILIBRIUM_TOLERANCE: f32 = 1e-3;
+
+#[derive(Clone, Debug)]
+pub struct TablebaseEntry {
+    // Expected checks each player still collects from this position
+    pub values: Vec<f32>,
+    // Equilibrium over each player's properties in ascending order, empty before a reveal
+    pub strategies: Vec<Vec<f32>>,
+}
+
+// Endgame for the last few sell rounds, filled in from whichever positions generate is given.
+// Holdings are public, so the only uncertainty left is which checks come up: every reveal the
+// unseen checks allow is a chance node, the ones set aside being as unknown as the deck. Every
+// round is a simultaneous-move game on the payoffs below it, solved exactly: by a pure
+// equilibrium or, with two properties each, by mixed odds solved from the indifference
+// conditions and checked against every deviation. A round neither finds, which has not turned
+// up with two rounds left, falls back to regret matching to within EQUILIBRIUM_TOLERANCE checks,
+// at most iterations iterations, and is logged. Positions are keyed on what decides the rest of
+// the game, so ones that differ only in the checks already won share an entry.
+pub struct SellTablebase {
+    max_rounds: u8,
+    iterations: usize,
+    entries: AHashMap<String, TablebaseEntry>,
+}
+
+impl SellTablebase {
+    pub fn new(max_rounds: u8, iterations: usize) -> Self {
+        SellTablebase {
+            max_rounds,
+            iterations,
+            entries: AHashMap::with_capacity(10000),
+        }
+    }
+    pub fn max_rounds(&self) -> u8 {
+        self.max_rounds
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    pub fn rounds_remaining(game_state: &GameState) -> u8 {
+        game_state.get_player_properties(0).len() as u8
+    }
+    pub fn covers(&self, game_state: &GameState) -> bool {
+        game_state.game_phase() == GamePhase::Sell
+            && !game_state.game_end()
+            && Self::rounds_remaining(game_state) <= self.max_rounds
+    }
+    pub fn probe(&self, game_state: &GameState) -> Option<&TablebaseEntry> {
+        self.entries.get(&Self::key(game_state))
+    }
+    // Holdings, unseen checks and the checks on offer
+    pub fn key(game_state: &GameState) -> String {
+        fn push_u8(encoding: &mut String, num: u8) {
+            encoding.push((b'0' + num / 10) as char);
+            encoding.push((b'0' + num % 10) as char);
+        }
+        let mut encoding = String::with_capacity(96);
+        encoding.push('p');
+        for player in 0..game_state.no_players() {
+            if player > 0 {
+                encoding.push('-');
+            }
+            game_state
+                .get_player_properties(player)
+                .iter()
+                .for_each(|&property| push_u8(&mut encoding, property));
+        }
+        encoding.push_str("|u");
+        game_state
+            .unseen_checks()
+            .iter()
+            .for_each(|&check| push_u8(&mut encoding, check));
+        encoding.push_str("|a");
+        game_state
+            .get_auction_pool()
+            .iter()
+            .for_each(|&check| push_u8(&mut encoding, check));
+        encoding
+    }
+    // Solves every position reachable from game_state and stores them in the table
+    pub fn generate(&mut self, game_state: &GameState) -> usize {
+        debug_assert!(
+            self.covers(game_state),
+            "Tablebase only covers the last {} sell rounds",
+            self.max_rounds
+        );
+        let entries_before = self.entries.len();
+        if game_state.auction_end() {
+            self.solve_chance(game_state);
+        } else {
+            self.solve_decision(game_state);
+        }
+        self.entries.len() - entries_before
+    }
+    pub fn sample_action(&self, game_state: &GameState, player: Player) -> Option<u8> {
+        let entry = self.probe(game_state)?;
+        let strategy = entry.strategies.get(player as usize)?;
+        let legal_moves = game_state.legal_moves(player);
+        legal_moves.get(sample_strategy(strategy)).copied()
+    }
+    pub fn sample_joint_action(&self, game_state: &GameState) -> Option<Vec<u8>> {
+        (0..game_state.no_players())
+            .map(|player| self.sample_action(game_state, player))
+            .collect()
+    }
+
+    fn solve_chance(&mut self, game_state: &GameState) -> Vec<f32> {
+        let no_players = game_state.no_players() as usize;
+        if game_state.game_end() {
+            return vec![0.0; no_players];
+        }
+        let key = Self::key(game_state);
+        if let Some(entry) = self.entries.get(&key) {
+            return entry.values.clone();
+        }
+        // Distinct reveals weighted by how many ways the unseen checks can produce them
+        let mut reveals: AHashMap<Vec<u8>, usize> = AHashMap::new();
+        let mut total: usize = 0;
+        for combination in game_state
+            .unseen_checks()
+            .into_iter()
+            .combinations(no_players)
+        {
+            let mut reveal = combination;
+            reveal.sort_unstable();
+            *reveals.entry(reveal).or_insert(0) += 1;
+            total += 1;
+        }
+        let mut values: Vec<f32> = vec![0.0; no_players];
+        for (reveal, count) in reveals {
+            let mut next_state = game_state.clone();
+            next_state.reveal_auction_unseen(reveal);
+            let reveal_values = self.solve_decision(&next_state);
+            let probability = count as f32 / total as f32;
+            for (value, reveal_value) in values.iter_mut().zip(reveal_values.iter()) {
+                *value += probability * reveal_value;
+            }
+        }
+        self.entries.insert(
+            key,
+            TablebaseEntry {
+                values: values.clone(),
+                strategies: Vec::new(),
+            },
+        );
+        values
+    }
+
+    fn solve_decision(&mut self, game_state: &GameState) -> Vec<f32> {
+        let key = Self::key(game_state);
+        if let Some(entry) = self.entries.get(&key) {
+            return entry.values.clone();
+        }
+        let no_players = game_state.no_players() as usize;
+        let legal_moves: Vec<Vec<u8>> = (0..game_state.no_players())
+            .map(|player| game_state.legal_moves(player))
+            .collect();
+        let no_moves = legal_moves[0].len();
+        let no_joint = no_moves.pow(no_players as u32);
+        // Payoff of every joint sale: checks taken this round plus the value of what follows
+        let mut payoffs: Vec<Vec<f32>> = Vec::with_capacity(no_joint);
+        for joint in 0..no_joint {
+            let actions = Self::decode_joint(joint, no_moves, no_players);
+            let player_choices: Vec<u8> = actions
+                .iter()
+                .enumerate()
+                .map(|(player, &index)| legal_moves[player][index])
+                .collect();
+            let next_state = game_state.manual_next_state_sell(player_choices);
+            let future_values = self.solve_chance(&next_state);
+            let payoff: Vec<f32> = (0..game_state.no_players())
+                .map(|player| {
+                    let gained = next_state.get_player_checks(player).iter().sum::<u8>()
+                        - game_state.get_player_checks(player).iter().sum::<u8>();
+                    gained as f32 + future_values[player as usize]
+                })
+                .collect();
+            payoffs.push(payoff);
+        }
+        let strategies = self.solve_normal_form(&payoffs, no_moves, no_players);
+        let mut values: Vec<f32> = vec![0.0; no_players];
+        for (joint, payoff) in payoffs.iter().enumerate() {
+            let actions = Self::decode_joint(joint, no_moves, no_players);
+            let probability: f32 = actions
+                .iter()
+                .enumerate()
+                .map(|(player, &index)| strategies[player][index])
+                .product();
+            for (value, p) in values.iter_mut().zip(payoff.iter()) {
+                *value += probability * p;
+            }
+        }
+        self.entries.insert(
+            key,
+            TablebaseEntry {
+                values: values.clone(),
+                strategies,
+            },
+        );
+        values
+    }
+
+    // The first pure equilibrium in joint order when there is one, then one where some players
+    // mix, otherwise the average strategy of regret matching+ with linear weights once it is
+    // within EQUILIBRIUM_TOLERANCE
+    fn solve_normal_form(
+        &self,
+        payoffs: &[Vec<f32>],
+        no_moves: usize,
+        no_players: usize,
+    ) -> Vec<Vec<f32>> {
+        let uniform = 1.0 / no_moves as f32;
+        if no_moves == 1 {
+            return vec![vec![1.0]; no_players];
+        }
+        if let Some(joint) = Self::pure_equilibrium(payoffs, no_moves, no_players) {
+            return Self::decode_joint(joint, no_moves, no_players)
+                .into_iter()
+                .map(|index| {
+                    let mut strategy = vec![0.0; no_moves];
+                    strategy[index] = 1.0;
+                    strategy
+                })
+                .collect();
+        }
+        if let Some(strategies) = Self::mixed_equilibrium(payoffs, no_moves, no_players) {
+            return strategies;
+        }
+        let mut strategies: Vec<Vec<f32>> = vec![vec![uniform; no_moves]; no_players];
+        let mut regrets: Vec<Vec<f32>> = vec![vec![0.0; no_moves]; no_players];
+        let mut averages: Vec<Vec<f32>> = vec![vec![0.0; no_moves]; no_players];
+        let normalised = |averages: &[Vec<f32>]| -> Vec<Vec<f32>> {
+            averages
+                .iter()
+                .map(|average| {
+                    let total: f32 = average.iter().sum();
+                    average
+                        .iter()
+                        .map(|a| if total > 0.0 { a / total } else { uniform })
+                        .collect()
+                })
+                .collect()
+        };
+        for iteration in 1..=self.iterations {
+            let action_values = Self::action_values(payoffs, &strategies, no_moves, no_players);
+            for player in 0..no_players {
+                let expected: f32 = strategies[player]
+                    .iter()
+                    .zip(action_values[player].iter())
+                    .map(|(s, v)| s * v)
+                    .sum();
+                for (regret, value) in regrets[player].iter_mut().zip(action_values[player].iter())
+                {
+                    *regret = (*regret + value - expected).max(0.0);
+                }
+                for (average, s) in averages[player].iter_mut().zip(strategies[player].iter()) {
+                    *average += iteration as f32 * s;
+                }
+                let total_regret: f32 = regrets[player].iter().sum();
+                for (s, regret) in strategies[player].iter_mut().zip(regrets[player].iter()) {
+                    *s = if total_regret > 0.0 {
+                        regret / total_regret
+                    } else {
+                        uniform
+                    };
+                }
+            }
+            if iteration % 100 == 0
+                && Self::nash_conv(payoffs, &normalised(&averages), no_moves, no_players)
+                    <= EQUILIBRIUM_TOLERANCE
+            {
+                return normalised(&averages);
+            }
+        }
+        let averages = normalised(&averages);
+        warn!(
+            "Sell round is {:.4} checks from an equilibrium after {} iterations",
+            Self::nash_conv(payoffs, &averages, no_moves, no_players),
+            self.iterations
+        );
+        averages
+    }
+
+    // A joint sale no player gains from leaving on its own
+    fn pure_equilibrium(payoffs: &[Vec<f32>], no_moves: usize, no_players: usize) -> Option<usize> {
+        (0..payoffs.len()).find(|&joint| {
+            let actions = Self::decode_joint(joint, no_moves, no_players);
+            (0..no_players).all(|player| {
+                let stride = no_moves.pow(player as u32);
+                let base = joint - actions[player] * stride;
+                (0..no_moves)
+                    .all(|index| payoffs[base + index * stride][player] <= payoffs[joint][player])
+            })
+        })
+    }
+
+    // With two moves each, an equilibrium where some players mix and the rest play pure moves.
+    // Every mixing player's odds have to leave the others indifferent, solved by Newton's method
+    // for each choice of who mixes, and whatever it finds is checked against every deviation.
+    fn mixed_equilibrium(
+        payoffs: &[Vec<f32>],
+        no_moves: usize,
+        no_players: usize,
+    ) -> Option<Vec<Vec<f32>>> {
+        if no_moves != 2 {
+            return None;
+        }
+        let mut supports: Vec<usize> = (0..1 << no_players)
+            .filter(|mask: &usize| mask.count_ones() >= 2)
+            .collect();
+        supports.sort_by_key(|mask| mask.count_ones());
+        for mixers in supports {
+            let mixing: Vec<usize> = (0..no_players).filter(|p| mixers & (1 << p) != 0).collect();
+            for pure in (0..payoffs.len()).filter(|joint| joint & mixers == 0) {
+                if !Self::plausible_support(payoffs, no_players, mixers, pure) {
+                    continue;
+                }
+                for start in [0.5, 0.25, 0.75] {
+                    let Some(odds) = Self::indifferent_odds(payoffs, &mixing, pure, start) else {
+                        continue;
+                    };
+                    let strategies: Vec<Vec<f32>> = (0..no_players)
+                        .map(|player| match mixing.iter().position(|&m| m == player) {
+                            Some(index) => vec![odds[index] as f32, 1.0 - odds[index] as f32],
+                            None if pure & (1 << player) == 0 => vec![1.0, 0.0],
+                            None => vec![0.0, 1.0],
+                        })
+                        .collect();
+                    if Self::nash_conv(payoffs, &strategies, no_moves, no_players) <= 1e-4 {
+                        return Some(strategies);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Whether every player's part could hold for some odds of the mixing players: a mixing
+    // player needs its first move to be neither always better nor always worse and a pure one
+    // its move to be a best response somewhere
+    fn plausible_support(
+        payoffs: &[Vec<f32>],
+        no_players: usize,
+        mixers: usize,
+        pure: usize,
+    ) -> bool {
+        (0..no_players).all(|player| {
+            let bit = 1 << player;
+            let (mut lowest, mut highest) = (f32::MAX, f32::MIN);
+            // Every joint with player on its first move and the pure players on theirs
+            for joint in (0..payoffs.len())
+                .filter(|&joint| joint & bit == 0 && joint & !mixers == pure & !bit)
+            {
+                let gap = payoffs[joint][player] - payoffs[joint | bit][player];
+                lowest = lowest.min(gap);
+                highest = highest.max(gap);
+            }
+            match (mixers & bit != 0, pure & bit != 0) {
+                (true, _) => lowest <= 0.0 && highest >= 0.0,
+                (false, false) => highest >= 0.0,
+                (false, true) => lowest <= 0.0,
+            }
+        })
+    }
+
+    // Odds of the first move for each mixing player that leave all of them indifferent, the
+    // others playing the moves set in pure
+    fn indifferent_odds(
+        payoffs: &[Vec<f32>],
+        mixing: &[usize],
+        pure: usize,
+        start: f64,
+    ) -> Option<Vec<f64>> {
+        let k = mixing.len();
+        let mut odds: Vec<f64> = vec![start; k];
+        for _ in 0..30 {
+            // gaps[m] is what mixing player m gains from its first move over its second and
+            // jacobian[m][l] how that changes with player l's odds
+            let mut gaps: Vec<f64> = vec![0.0; k];
+            let mut jacobian: Vec<Vec<f64>> = vec![vec![0.0; k]; k];
+            for moves in 0..1usize << k {
+                let joint = mixing
+                    .iter()
+                    .enumerate()
+                    .fold(pure, |joint, (index, &player)| {
+                        joint | (((moves >> index) & 1) << player)
+                    });
+                let probability = |skip: usize| -> f64 {
+                    (0..k)
+                        .filter(|&index| index != skip)
+                        .map(|index| match (moves >> index) & 1 {
+                            0 => odds[index],
+                            _ => 1.0 - odds[index],
+                        })
+                        .product()
+                };
+                for (m, &player) in mixing.iter().enumerate() {
+                    // Only the joints where m plays its first move, paired with the second
+                    if (moves >> m) & 1 == 1 {
+                        continue;
+                    }
+                    let gap =
+                        (payoffs[joint][player] - payoffs[joint | (1 << player)][player]) as f64;
+                    gaps[m] += probability(m) * gap;
+                    for l in (0..k).filter(|&l| l != m) {
+                        let sign = match (moves >> l) & 1 {
+                            0 => 1.0,
+                            _ => -1.0,
+                        };
+                        let others: f64 = (0..k)
+                            .filter(|&index| index != m && index != l)
+                            .map(|index| match (moves >> index) & 1 {
+                                0 => odds[index],
+                                _ => 1.0 - odds[index],
+                            })
+                            .product();
+                        jacobian[m][l] += sign * others * gap;
+                    }
+                }
+            }
+            if gaps.iter().all(|gap| gap.abs() < 1e-9) {
+                return odds
+                    .iter()
+                    .all(|odd| (0.0..=1.0).contains(odd))
+                    .then_some(odds);
+            }
+            // Newton's step, or damped least squares when ties between payoffs leave the
+            // jacobian singular
+            if let Some(step) = Self::solve_linear(jacobian.clone(), gaps.clone()) {
+                for (odd, delta) in odds.iter_mut().zip(step.iter()) {
+                    *odd -= delta;
+                }
+                if odds.iter().any(|odd| !(-1.0..=2.0).contains(odd)) {
+                    return None;
+                }
+                continue;
+            }
+            let normal: Vec<Vec<f64>> = (0..k)
+                .map(|row| {
+                    (0..k)
+                        .map(|column| {
+                            let product: f64 =
+                                (0..k).map(|m| jacobian[m][row] * jacobian[m][column]).sum();
+                            product + if row == column { 1e-9 } else { 0.0 }
+                        })
+                        .collect()
+                })
+                .collect();
+            let projected: Vec<f64> = (0..k)
+                .map(|row| (0..k).map(|m| jacobian[m][row] * gaps[m]).sum())
+                .collect();
+            let step = Self::solve_linear(normal, projected)?;
+            if step.iter().all(|delta| delta.abs() < 1e-12) {
+                return None;
+            }
+            for (odd, delta) in odds.iter_mut().zip(step.iter()) {
+                *odd -= delta;
+            }
+        }
+        None
+    }
+
+    // Gaussian elimination with partial pivoting, None when the system is singular
+    fn solve_linear(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+        let n = rhs.len();
+        for column in 0..n {
+            let pivot = (column..n)
+                .max_by(|&a, &b| matrix[a][column].abs().total_cmp(&matrix[b][column].abs()))?;
+            if matrix[pivot][column].abs() < 1e-12 {
+                return None;
+            }
+            matrix.swap(column, pivot);
+            rhs.swap(column, pivot);
+            let (above, below) = matrix.split_at_mut(column + 1);
+            let pivot_row = &above[column];
+            for (offset, row) in below.iter_mut().enumerate() {
+                let factor = row[column] / pivot_row[column];
+                for (value, pivot_value) in row[column..].iter_mut().zip(pivot_row[column..].iter())
+                {
+                    *value -= factor * pivot_value;
+                }
+                rhs[column + 1 + offset] -= factor * rhs[column];
+            }
+        }
+        let mut solution: Vec<f64> = vec![0.0; n];
+        for row in (0..n).rev() {
+            let known: f64 = (row + 1..n).map(|c| matrix[row][c] * solution[c]).sum();
+            solution[row] = (rhs[row] - known) / matrix[row][row];
+        }
+        Some(solution)
+    }
+
+    // What each of a player's moves is worth against the others' strategies
+    fn action_values(
+        payoffs: &[Vec<f32>],
+        strategies: &[Vec<f32>],
+        no_moves: usize,
+        no_players: usize,
+    ) -> Vec<Vec<f32>> {
+        let mut action_values: Vec<Vec<f32>> = vec![vec![0.0; no_moves]; no_players];
+        for (joint, payoff) in payoffs.iter().enumerate() {
+            let actions = Self::decode_joint(joint, no_moves, no_players);
+            for player in 0..no_players {
+                let opponents_probability: f32 = actions
+                    .iter()
+                    .enumerate()
+                    .filter(|&(opponent, _)| opponent != player)
+                    .map(|(opponent, &index)| strategies[opponent][index])
+                    .product();
+                action_values[player][actions[player]] += opponents_probability * payoff[player];
+            }
+        }
+        action_values
+    }
+
+    // Sum of what every player gains by deviating from strategies
+    fn nash_conv(
+        payoffs: &[Vec<f32>],
+        strategies: &[Vec<f32>],
+        no_moves: usize,
+        no_players: usize,
+    ) -> f32 {
+        Self::action_values(payoffs, strategies, no_moves, no_players)
+            .iter()
+            .zip(strategies.iter())
+            .map(|(values, strategy)| {
+                let expected: f32 = strategy.iter().zip(values.iter()).map(|(s, v)| s * v).sum();
+                values.iter().copied().fold(f32::MIN, f32::max) - expected
+            })
+            .sum()
+    }
+
+    fn decode_joint(mut joint: usize, no_moves: usize, no_players: usize) -> Vec<usize> {
+        let mut actions: Vec<usize> = Vec::with_capacity(no_players);
+        for _ in 0..no_players {
+            actions.push(joint % no_moves);
+            joint /= no_moves;
+        }
+        actions
+    }
+
+    // Text format with a header, one position per line and a checksum of every line before it
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let mut hash = empty_checksum();
+        let mut write_line = |writer: &mut BufWriter<File>, line: String| -> std::io::Result<()> {
+            hash = update_checksum(hash, line.as_bytes());
+            hash = update_checksum(hash, b"\n");
+            writeln!(writer, "{}", line)
+        };
+        write_line(&mut writer, "# For Sale sell endgame tablebase".to_string())?;
+        write_line(&mut writer, format!("version {}", TABLEBASE_VERSION))?;
+        write_line(&mut writer, format!("max_rounds {}", self.max_rounds))?;
+        write_line(&mut writer, format!("iterations {}", self.iterations))?;
+        write_line(&mut writer, format!("entries {}", self.entries.len()))?;
+        for (key, entry) in self.entries.iter() {
+            let values = entry.values.iter().map(|v| v.to_string()).join(",");
+            let strategies = entry
+                .strategies
+                .iter()
+                .map(|strategy| strategy.iter().map(|s| s.to_string()).join(","))
+                .join(";");
+            write_line(&mut writer, format!("{}\t{}\t{}", key, values, strategies))?;
+        }
+        writeln!(writer, "checksum {:016x}", hash)?;
+        writer.flush()?;
+        info!(
+            "Saved {} tablebase entries to {:?}",
+            self.entries.len(),
+            path
+        );
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let invalid =
+            |message: String| std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+        let reader = BufReader::new(File::open(path)?);
+        let mut hash = empty_checksum();
+        let mut version: Option<u32> = None;
+        let mut stored_checksum: Option<u64> = None;
+        let mut expected_entries: Option<usize> = None;
+        let mut tablebase = SellTablebase::new(0, 0);
+        for line in reader.lines() {
+            let line = line?;
+            if let Some(value) = line.strip_prefix("checksum ") {
+                stored_checksum =
+                    Some(u64::from_str_radix(value, 16).map_err(|_| invalid(line.clone()))?);
+                break;
+            }
+            hash = update_checksum(hash, line.as_bytes());
+            hash = update_checksum(hash, b"\n");
+            if line.starts_with('#') || line.is_empty() {
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("version ") {
+                version = value.parse().ok();
+                if version != Some(TABLEBASE_VERSION) {
+                    return Err(invalid(format!("Unsupported tablebase version: {value}")));
+                }
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("max_rounds ") {
+                tablebase.max_rounds = value.parse().map_err(|_| invalid(line.clone()))?;
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("iterations ") {
+                tablebase.iterations = value.parse().map_err(|_| invalid(line.clone()))?;
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("entries ") {
+                expected_entries = Some(value.parse().map_err(|_| invalid(line.clone()))?);
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() != 3 {
+                return Err(invalid(format!("Malformed tablebase entry: {line}")));
+            }
+            let parse_floats = |field: &str| -> std::io::Result<Vec<f32>> {
+                field
+                    .split(',')
+                    .map(|v| v.parse::<f32>().map_err(|_| invalid(line.clone())))
+                    .collect()
+            };
+            let values = parse_floats(fields[1])?;
+            let strategies = if fields[2].is_empty() {
+                Vec::new()
+            } else {
+                fields[2]
+                    .split(';')
+                    .map(parse_floats)
+                    .collect::<std::io::Result<Vec<Vec<f32>>>>()?
+            };
+            tablebase
+                .entries
+                .insert(fields[0].to_string(), TablebaseEntry { values, strategies });
+        }
+        if version.is_none() {
+            return Err(invalid("Tablebase file is missing its version".to_string()));
+        }
+        match stored_checksum {
+            Some(stored) if stored == hash => {}
+            Some(_) => return Err(invalid("Tablebase checksum does not match".to_string())),
+            None => {
+                return Err(invalid(
+                    "Tablebase file is missing its checksum".to_string(),
+                ))
+            }
+        }
+        if expected_entries.is_some_and(|entries| entries != tablebase.entries.len()) {
+            return Err(invalid(format!(
+                "Tablebase should hold {:?} entries but holds {}",
+                expected_entries,
+                tablebase.entries.len()
+            )));
+        }
+        info!(
+            "Loaded {} tablebase entries from {:?}",
+            tablebase.entries.len(),
+            path
+        );
+        Ok(tablebase)
+    }
+}
+
+impl Default for SellTablebase {
+    fn default() -> Self {
+        SellTablebase::new(2, 1000)
+    }
+}
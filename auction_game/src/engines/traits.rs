@@ -1,7 +1,28 @@
+use crate::models::enums::{Coins, Player, Property};
 use crate::models::game_state::GameState;
+use rand::rngs::StdRng;
+use std::time::Duration;
 
-pub trait PlayerController {
+pub trait PlayerController: Send {
     fn nickname(&self) -> String;
+    // In the sell phase the property to sell, chosen without seeing anyone else's choice
     fn decision(&mut self, game_state: &GameState) -> u8;
-    fn batch_decision(&mut self, game_state: &GameState) -> Vec<u8>;
+    // Called with the state before every bid of any player, so controllers can learn from play
+    fn observe_bid(&mut self, _game_state: &GameState, _player: Player, _action: Coins) {}
+    // Called with the state before every sell round, sales[player] being the property sold
+    fn observe_sales(&mut self, _game_state: &GameState, _sales: &[Property]) {}
+    // Called before play when moves are timed, searching controllers should answer within limit
+    fn set_move_time_limit(&mut self, _limit: Duration) {}
+}
+
+pub trait RolloutPolicy: Send + Sync {
+    fn name(&self) -> String;
+    // Picks a legal move for player, used to play out simulations to the end of the game
+    fn choose(&self, game_state: &GameState, player: Player, rng: &mut StdRng) -> u8;
+}
+
+pub trait OpponentModel: Send + Sync {
+    fn name(&self) -> String;
+    // Probability of player choosing each of moves in game_state, in the order of moves
+    fn policy(&self, game_state: &GameState, player: Player, moves: &[u8]) -> Vec<f32>;
 }
@@ -15,6 +15,19 @@ pub fn sample_strategy(vec: &Vec<f32>) -> usize {
     random_index as usize
 }
 
+// Same as sample_strategy with a caller supplied generator, for reproducible sampling
+pub fn sample_strategy_with<R: Rng>(vec: &[f32], rng: &mut R) -> usize {
+    let sample: f32 = rng.random::<f32>();
+    let mut total: f32 = 0.0;
+    for (i, probability) in vec.iter().enumerate() {
+        total += probability;
+        if sample < total {
+            return i;
+        }
+    }
+    (sample * vec.len() as f32).floor() as usize
+}
+
 pub fn mixed_strategy_score(strategy: &Vec<f32>, score: &Vec<f32>) -> f32 {
     debug_assert!(
         strategy.len() == score.len(),
@@ -31,6 +44,12 @@ pub fn mixed_strategy_score(strategy: &Vec<f32>, score: &Vec<f32>) -> f32 {
 
 pub fn normalize(strategy: &mut Vec<f32>, regret_vec: &Vec<f32>) {
     let total: f32 = regret_vec.iter().sum();
+    if total <= 0.0 {
+        // No positive regret yet, play uniformly
+        let uniform = 1.0 / strategy.len() as f32;
+        strategy.iter_mut().for_each(|s| *s = uniform);
+        return;
+    }
     for (s, q) in strategy.iter_mut().zip(regret_vec.iter()) {
         *s = *q / total;
     }
@@ -0,0 +1,183 @@
+use crate::game_modes::referee::Incident;
+use crate::game_modes::runner::GameSetup;
+use crate::game_modes::traits::GameObserver;
+use crate::models::enums::{Coins, GamePhase, Player, Property};
+use crate::models::game_record::{GameRecord, RecordedMove};
+use crate::models::game_state::GameState;
+use crate::tournaments::tournament::final_scores;
+use log::{info, warn};
+use std::path::PathBuf;
+
+// Logs every move and state at info level
+pub struct LogObserver {
+    game_id: String,
+    turns: usize,
+    game_phase: GamePhase,
+}
+
+impl LogObserver {
+    pub fn new(game_id: String) -> Self {
+        LogObserver {
+            game_id,
+            turns: 0,
+            game_phase: GamePhase::Bid,
+        }
+    }
+}
+
+impl GameObserver for LogObserver {
+    fn game_start(&mut self, _setup: &GameSetup, game_state: &GameState) {
+        self.turns = 0;
+        self.game_phase = game_state.game_phase();
+        info!(
+            "Starting game: {}|First player is player {}",
+            self.game_id,
+            game_state.current_player() + 1
+        );
+        info!("{game_state}");
+    }
+    fn reveal(&mut self, game_state: &GameState) {
+        if game_state.game_phase() != self.game_phase {
+            self.game_phase = game_state.game_phase();
+            info!("");
+            info!("===== Starting Sell Phase =====");
+            info!("");
+        }
+        info!("{game_state}");
+    }
+    fn bid(&mut self, _game_state: &GameState, player: Player, action: Coins) {
+        self.turns += 1;
+        info!("player {} chose to add {} to their bid", player + 1, action);
+    }
+    fn sales(&mut self, _game_state: &GameState, sales: &[Property]) {
+        self.turns += 1;
+        for (player, property) in sales.iter().enumerate() {
+            info!("player {} chose to sell property: {}", player + 1, property);
+        }
+    }
+    fn incident(&mut self, _game_state: &GameState, incident: &Incident) {
+        warn!("{incident}");
+    }
+    fn game_end(&mut self, game_state: &GameState) {
+        info!("{game_state}");
+        info!(
+            "\n ===== Auctions have closed after {} turns =====",
+            self.turns
+        );
+        if game_state.game_end() {
+            info!("\n{}", game_state.tally_game_score());
+        }
+    }
+}
+
+// Prints the table and every move to the terminal, for games with a human at the table
+pub struct PrintObserver {}
+
+impl GameObserver for PrintObserver {
+    fn game_start(&mut self, _setup: &GameSetup, game_state: &GameState) {
+        println!("GameState: {game_state}");
+    }
+    fn reveal(&mut self, game_state: &GameState) {
+        println!("{game_state}");
+    }
+    fn bid(&mut self, _game_state: &GameState, player: Player, action: Coins) {
+        println!("Player: {} chose to do: {}", player + 1, action);
+    }
+    fn sales(&mut self, _game_state: &GameState, sales: &[Property]) {
+        println!("Properties Chosen by Players were: {:?}", sales);
+    }
+    fn incident(&mut self, _game_state: &GameState, incident: &Incident) {
+        println!("Referee: {incident}");
+    }
+    fn game_end(&mut self, game_state: &GameState) {
+        println!("{game_state}");
+        if game_state.game_end() {
+            println!("Game has concluded!");
+            println!("\n{}", game_state.tally_game_score());
+        }
+    }
+}
+
+// Keeps a GameRecord of the game, moves before the starting position included, and writes it to
+// path when play stops
+pub struct RecordObserver {
+    path: PathBuf,
+    event: String,
+    players: Option<Vec<String>>,
+    record: Option<GameRecord>,
+}
+
+impl RecordObserver {
+    pub fn new(path: PathBuf) -> Self {
+        RecordObserver {
+            path,
+            event: "?".to_string(),
+            players: None,
+            record: None,
+        }
+    }
+    pub fn with_event(mut self, event: String) -> Self {
+        self.event = event;
+        self
+    }
+    // Names the seats in the headers, such as by bot spec, instead of by nickname
+    pub fn with_players(mut self, players: Vec<String>) -> Self {
+        self.players = Some(players);
+        self
+    }
+    // Record of the game being played or last played
+    pub fn record(&self) -> Option<&GameRecord> {
+        self.record.as_ref()
+    }
+    fn push(&mut self, recorded_move: RecordedMove) {
+        if let Some(record) = &mut self.record {
+            record.moves.push(recorded_move);
+        }
+    }
+}
+
+impl GameObserver for RecordObserver {
+    fn game_start(&mut self, setup: &GameSetup, game_state: &GameState) {
+        let moves = GameRecord::moves_to(game_state);
+        let starting_player = moves
+            .iter()
+            .find_map(|recorded_move| match recorded_move {
+                RecordedMove::Bid { player, .. } => Some(*player),
+                _ => None,
+            })
+            .unwrap_or(game_state.current_player());
+        let mut record = GameRecord::new(setup.rule_set, starting_player);
+        record.event = self.event.clone();
+        record.date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        record.seed = Some(setup.seed);
+        record.players = self
+            .players
+            .clone()
+            .unwrap_or_else(|| setup.players.clone());
+        record.moves = moves;
+        self.record = Some(record);
+    }
+    fn reveal(&mut self, game_state: &GameState) {
+        self.push(RecordedMove::Reveal(game_state.get_auction_pool().clone()));
+    }
+    fn bid(&mut self, _game_state: &GameState, player: Player, action: Coins) {
+        self.push(RecordedMove::Bid { player, action });
+    }
+    fn sales(&mut self, _game_state: &GameState, sales: &[Property]) {
+        self.push(RecordedMove::Sales(sales.to_vec()));
+    }
+    fn incident(&mut self, _game_state: &GameState, incident: &Incident) {
+        self.push(RecordedMove::Comment(incident.to_string()));
+    }
+    fn game_end(&mut self, game_state: &GameState) {
+        let Some(record) = &mut self.record else {
+            return;
+        };
+        if game_state.game_end() {
+            record.result = Some(final_scores(game_state));
+        }
+        if let Err(error) = record.write(&self.path) {
+            warn!("Could not write {}: {error}", self.path.display());
+        }
+    }
+}
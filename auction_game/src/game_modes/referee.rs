@@ -0,0 +1,243 @@
+use crate::engines::traits::PlayerController;
+use crate::models::enums::{GamePhase, Player};
+use crate::models::game_state::GameState;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Move played for a controller that broke the rules
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Fallback {
+    // A bid of 0, or the lowest property in the sell phase
+    Pass,
+    RandomMove,
+    // The seat passes for the rest of the game and finishes last
+    Forfeit,
+}
+
+impl fmt::Display for Fallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Fallback::Pass => "pass",
+            Fallback::RandomMove => "random",
+            Fallback::Forfeit => "forfeit",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Fallback {
+    type Err = String;
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "pass" => Ok(Fallback::Pass),
+            "random" => Ok(Fallback::RandomMove),
+            "forfeit" => Ok(Fallback::Forfeit),
+            _ => Err(format!(
+                "Unknown fallback {text}, expected pass, random or forfeit"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    MoveTimeout { elapsed: Duration, limit: Duration },
+    // The seat's thinking time over the game ran out
+    GameTimeout { used: Duration, budget: Duration },
+    Panic(String),
+    IllegalMove(u8),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::MoveTimeout { elapsed, limit } => {
+                write!(f, "took {:.2?}, over the {:.2?} move limit", elapsed, limit)
+            }
+            Violation::GameTimeout { used, budget } => {
+                write!(f, "used {:.2?}, over the {:.2?} game budget", used, budget)
+            }
+            Violation::Panic(message) => write!(f, "panicked: {}", message),
+            Violation::IllegalMove(action) => write!(f, "played illegal move {}", action),
+        }
+    }
+}
+
+// A rule broken during a game and what the referee did about it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Incident {
+    pub turn: u32,
+    pub player: Player,
+    pub violation: Violation,
+    pub fallback: Fallback,
+    // Move played instead, None when the controller was only being told about a move
+    pub replacement: Option<u8>,
+}
+
+impl fmt::Display for Incident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "turn {} player {} {}, {}",
+            self.turn,
+            self.player + 1,
+            self.violation,
+            self.fallback
+        )?;
+        if let Some(action) = self.replacement {
+            write!(f, " {}", action)?;
+        }
+        Ok(())
+    }
+}
+
+// Time budgets and the fallback the game runners enforce. Without time limits controllers move
+// on the runner's thread, with them every move is asked for on a thread of its own and a
+// controller that runs out of time is left behind on it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Referee {
+    move_time_limit: Option<Duration>,
+    game_time_budget: Option<Duration>,
+    fallback: Fallback,
+}
+
+impl Default for Referee {
+    fn default() -> Self {
+        Referee {
+            move_time_limit: None,
+            game_time_budget: None,
+            fallback: Fallback::Pass,
+        }
+    }
+}
+
+impl Referee {
+    pub fn with_move_time_limit(mut self, move_time_limit: Duration) -> Self {
+        self.move_time_limit = Some(move_time_limit);
+        self
+    }
+    // Total thinking time of each seat over a game
+    pub fn with_game_time_budget(mut self, game_time_budget: Duration) -> Self {
+        self.game_time_budget = Some(game_time_budget);
+        self
+    }
+    pub fn with_fallback(mut self, fallback: Fallback) -> Self {
+        self.fallback = fallback;
+        self
+    }
+    pub fn move_time_limit(&self) -> Option<Duration> {
+        self.move_time_limit
+    }
+    pub fn game_time_budget(&self) -> Option<Duration> {
+        self.game_time_budget
+    }
+    pub fn fallback(&self) -> Fallback {
+        self.fallback
+    }
+
+    // Time a seat that has already thought for used may take over its next move
+    pub fn time_limit(&self, used: Duration) -> Option<Duration> {
+        let remaining = self
+            .game_time_budget
+            .map(|budget| budget.saturating_sub(used));
+        match (self.move_time_limit, remaining) {
+            (Some(limit), Some(remaining)) => Some(limit.min(remaining)),
+            (limit, remaining) => limit.or(remaining),
+        }
+    }
+
+    // Asks controller for its move, returning the controller, the move and the time taken. The
+    // controller is lost if it runs out of time.
+    pub fn ask(
+        &self,
+        mut controller: Box<dyn PlayerController>,
+        game_state: &GameState,
+        used: Duration,
+    ) -> (
+        Option<Box<dyn PlayerController>>,
+        Result<u8, Violation>,
+        Duration,
+    ) {
+        let start = Instant::now();
+        let Some(limit) = self.time_limit(used) else {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| controller.decision(game_state)));
+            return (
+                Some(controller),
+                result.map_err(panic_violation),
+                start.elapsed(),
+            );
+        };
+        let (sender, receiver) = mpsc::channel();
+        let view = game_state.clone();
+        thread::spawn(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| controller.decision(&view)));
+            let _ = sender.send((controller, result));
+        });
+        match receiver.recv_timeout(limit) {
+            Ok((controller, result)) => {
+                let elapsed = start.elapsed();
+                (Some(controller), result.map_err(panic_violation), elapsed)
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                let elapsed = start.elapsed();
+                let violation = match self.move_time_limit {
+                    Some(move_time_limit) if limit >= move_time_limit => Violation::MoveTimeout {
+                        elapsed,
+                        limit: move_time_limit,
+                    },
+                    _ => Violation::GameTimeout {
+                        used: used + elapsed,
+                        budget: self.game_time_budget.unwrap_or(limit),
+                    },
+                };
+                (None, Err(violation), elapsed)
+            }
+            Err(RecvTimeoutError::Disconnected) => (
+                None,
+                Err(Violation::Panic("controller thread died".to_string())),
+                start.elapsed(),
+            ),
+        }
+    }
+
+    // Move played in place of one the controller failed to make
+    pub fn fallback_move(&self, game_state: &GameState, player: Player, rng: &mut StdRng) -> u8 {
+        match self.fallback {
+            Fallback::RandomMove => *game_state.legal_moves(player).choose(rng).unwrap(),
+            Fallback::Pass | Fallback::Forfeit => pass_move(game_state, player),
+        }
+    }
+}
+
+// A bid of 0, or the lowest property held in the sell phase
+pub fn pass_move(game_state: &GameState, player: Player) -> u8 {
+    match game_state.game_phase() {
+        GamePhase::Bid => 0,
+        GamePhase::Sell => *game_state.legal_moves_sell(player).iter().min().unwrap(),
+    }
+}
+
+pub fn is_legal(game_state: &GameState, player: Player, action: u8) -> bool {
+    match game_state.game_phase() {
+        GamePhase::Bid => game_state.legal_moves_bid(player).contains(&action),
+        GamePhase::Sell => game_state.legal_moves_sell(player).contains(&action),
+    }
+}
+
+pub fn panic_violation(payload: Box<dyn Any + Send>) -> Violation {
+    let message = match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    };
+    Violation::Panic(message)
+}
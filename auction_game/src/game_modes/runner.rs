@@ -0,0 +1,409 @@
+use crate::engines::controllers::random_player::RandomPlayer;
+use crate::engines::traits::PlayerController;
+use crate::game_modes::referee::{
+    is_legal, panic_violation, pass_move, Fallback, Incident, Referee, Violation,
+};
+use crate::game_modes::sealed_sales::SealedSales;
+use crate::game_modes::traits::GameObserver;
+use crate::models::enums::{Coins, GamePhase, Player, Property};
+use crate::models::game_state::GameState;
+use crate::models::rule_set::RuleSet;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+// Which part of the game the runner plays
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PhaseLimit {
+    Full,
+    // Stops once the last property is taken
+    BidOnly,
+    // Starts from the sell phase, reaching it by random bids when the start is in the bid phase
+    SellOnly,
+}
+
+// What observers are told when play starts
+#[derive(Clone, Debug)]
+pub struct GameSetup {
+    pub seed: u64,
+    pub rule_set: RuleSet,
+    // Nickname of every seat's controller
+    pub players: Vec<String>,
+    pub referee: Referee,
+}
+
+// How a seat is being played
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SeatStatus {
+    Playing,
+    // Its controller is lost or out of time, the referee's fallback moves for it
+    Retired,
+    // Passes for the rest of the game and finishes last
+    Forfeited,
+}
+
+// Plays one game between controllers, seat i being controllers[i], and tells the observers
+// about every move. The game modes are presets over it. Controllers are shown the table with
+// the unrevealed decks shuffled, and sell rounds are committed face down then revealed together.
+// The referee keeps controllers to time and stands in for them when they panic or move illegally.
+pub struct GameRunner {
+    // None once a controller is left behind thinking past its time
+    controllers: Vec<Option<Box<dyn PlayerController>>>,
+    nicknames: Vec<String>,
+    rule_set: RuleSet,
+    seed: Option<u64>,
+    bool_random_starting_player: bool,
+    phase_limit: PhaseLimit,
+    start_position: Option<GameState>,
+    observers: Vec<Box<dyn GameObserver>>,
+    referee: Referee,
+    // Seed, states and refereeing of the last run
+    played_seed: Option<u64>,
+    history: Vec<GameState>,
+    seat_status: Vec<SeatStatus>,
+    time_used: Vec<Duration>,
+    incidents: Vec<Incident>,
+}
+
+impl GameRunner {
+    pub fn new(controllers: Vec<Box<dyn PlayerController>>) -> Self {
+        let rule_set = RuleSet::standard(controllers.len() as u8);
+        let nicknames = controllers
+            .iter()
+            .map(|controller| controller.nickname())
+            .collect();
+        GameRunner {
+            controllers: controllers.into_iter().map(Some).collect(),
+            nicknames,
+            rule_set,
+            seed: None,
+            bool_random_starting_player: false,
+            phase_limit: PhaseLimit::Full,
+            start_position: None,
+            observers: Vec::new(),
+            referee: Referee::default(),
+            played_seed: None,
+            history: Vec::new(),
+            seat_status: Vec::new(),
+            time_used: Vec::new(),
+            incidents: Vec::new(),
+        }
+    }
+    pub fn with_rule_set(mut self, rule_set: RuleSet) -> Self {
+        debug_assert!(
+            rule_set.no_players() as usize == self.controllers.len(),
+            "Rule set is for {} players but there are {} controllers",
+            rule_set.no_players(),
+            self.controllers.len()
+        );
+        self.rule_set = rule_set;
+        self
+    }
+    // Fixes the deal, the starting player and random bids, which are otherwise from a random seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+    pub fn with_random_starting_player(mut self, bool_random_starting_player: bool) -> Self {
+        self.bool_random_starting_player = bool_random_starting_player;
+        self
+    }
+    pub fn with_phase_limit(mut self, phase_limit: PhaseLimit) -> Self {
+        self.phase_limit = phase_limit;
+        self
+    }
+    // Plays on from game_state instead of dealing
+    pub fn with_start_position(mut self, game_state: GameState) -> Self {
+        debug_assert!(
+            game_state.no_players() as usize == self.controllers.len(),
+            "Position is for {} players but there are {} controllers",
+            game_state.no_players(),
+            self.controllers.len()
+        );
+        self.start_position = Some(game_state);
+        self
+    }
+    pub fn with_observer(mut self, observer: Box<dyn GameObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+    pub fn with_referee(mut self, referee: Referee) -> Self {
+        self.referee = referee;
+        self
+    }
+    // Also passed on to the controllers so searches can stay within it
+    pub fn with_move_time_limit(mut self, move_time_limit: Duration) -> Self {
+        self.referee = self.referee.with_move_time_limit(move_time_limit);
+        self
+    }
+    pub fn rule_set(&self) -> RuleSet {
+        self.rule_set
+    }
+    pub fn referee(&self) -> Referee {
+        self.referee
+    }
+    pub fn nicknames(&self) -> &[String] {
+        &self.nicknames
+    }
+    // Hands the observers back, for the next runner
+    pub fn take_observers(&mut self) -> Vec<Box<dyn GameObserver>> {
+        std::mem::take(&mut self.observers)
+    }
+    // Controllers lost to a timeout come back as random players
+    pub fn into_controllers(self) -> Vec<Box<dyn PlayerController>> {
+        self.controllers
+            .into_iter()
+            .zip(self.nicknames)
+            .enumerate()
+            .map(|(seat, (controller, nickname))| {
+                controller.unwrap_or_else(|| Box::new(RandomPlayer::new(seat as Player, nickname)))
+            })
+            .collect()
+    }
+    // Seed the last run was played from
+    pub fn played_seed(&self) -> Option<u64> {
+        self.played_seed
+    }
+    // Every state of the last run, from the start to where play stopped
+    pub fn history(&self) -> &[GameState] {
+        &self.history
+    }
+    pub fn seat_status(&self) -> &[SeatStatus] {
+        &self.seat_status
+    }
+    // Thinking time of every seat over the last run
+    pub fn time_used(&self) -> &[Duration] {
+        &self.time_used
+    }
+    pub fn incidents(&self) -> &[Incident] {
+        &self.incidents
+    }
+    pub fn forfeits(&self) -> Vec<bool> {
+        self.seat_status
+            .iter()
+            .map(|&status| status == SeatStatus::Forfeited)
+            .collect()
+    }
+
+    // Plays until the game or the phase limit ends and returns the last state
+    pub fn run(&mut self) -> GameState {
+        let seed = self.seed.unwrap_or_else(|| thread_rng().random());
+        self.played_seed = Some(seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut game_state = match &self.start_position {
+            Some(game_state) => game_state.clone(),
+            None => self
+                .rule_set
+                .deal_with_rng(self.bool_random_starting_player, &mut rng),
+        };
+        self.seat_status = self
+            .controllers
+            .iter()
+            .map(|controller| match controller {
+                Some(_) => SeatStatus::Playing,
+                None => SeatStatus::Retired,
+            })
+            .collect();
+        self.time_used = vec![Duration::ZERO; self.controllers.len()];
+        self.incidents.clear();
+        if let Some(limit) = self.referee.move_time_limit() {
+            for controller in self.controllers.iter_mut().flatten() {
+                controller.set_move_time_limit(limit);
+            }
+        }
+        if self.phase_limit == PhaseLimit::SellOnly {
+            game_state = self.skip_bid_phase(game_state, &mut rng);
+        }
+        let setup = GameSetup {
+            seed,
+            rule_set: self.rule_set,
+            players: self.nicknames.clone(),
+            referee: self.referee,
+        };
+        for observer in self.observers.iter_mut() {
+            observer.game_start(&setup, &game_state);
+        }
+        self.history.clear();
+        while !self.stopped(&game_state) {
+            self.history.push(game_state.clone());
+            if game_state.auction_end() {
+                game_state = game_state.generate_next_state_reveal();
+                for observer in self.observers.iter_mut() {
+                    observer.reveal(&game_state);
+                }
+                continue;
+            }
+            game_state = match game_state.game_phase() {
+                GamePhase::Bid => self.play_bid(&game_state, &mut rng),
+                GamePhase::Sell => self.play_sales(&game_state, &mut rng),
+            };
+        }
+        self.history.push(game_state.clone());
+        for observer in self.observers.iter_mut() {
+            observer.game_end(&game_state);
+        }
+        game_state
+    }
+
+    fn stopped(&self, game_state: &GameState) -> bool {
+        game_state.game_end()
+            || (self.phase_limit == PhaseLimit::BidOnly
+                && game_state.game_phase() == GamePhase::Sell)
+    }
+
+    fn play_bid(&mut self, game_state: &GameState, rng: &mut StdRng) -> GameState {
+        let view = public_view(game_state, rng);
+        let player = game_state.current_player();
+        let action = self.refereed_decision(game_state, &view, player, rng);
+        self.tell(game_state, rng, |controller| {
+            controller.observe_bid(&view, player, action)
+        });
+        for observer in self.observers.iter_mut() {
+            observer.bid(game_state, player, action);
+        }
+        game_state.manual_next_state_bid(player, action)
+    }
+
+    // Every seat commits its own property from the same table, none seeing another's choice
+    fn play_sales(&mut self, game_state: &GameState, rng: &mut StdRng) -> GameState {
+        let view = public_view(game_state, rng);
+        let mut sealed_sales = SealedSales::new(game_state.no_players());
+        for player in 0..game_state.no_players() {
+            let property = self.refereed_decision(game_state, &view, player, rng);
+            sealed_sales.commit(player, property);
+        }
+        let sales: Vec<Property> = sealed_sales.reveal();
+        self.tell(game_state, rng, |controller| {
+            controller.observe_sales(&view, &sales)
+        });
+        for observer in self.observers.iter_mut() {
+            observer.sales(game_state, &sales);
+        }
+        game_state.manual_next_state_sell(sales)
+    }
+
+    // Move of player, from its controller if it keeps to the rules and otherwise the fallback
+    fn refereed_decision(
+        &mut self,
+        game_state: &GameState,
+        view: &GameState,
+        player: Player,
+        rng: &mut StdRng,
+    ) -> u8 {
+        let seat = player as usize;
+        match self.seat_status[seat] {
+            SeatStatus::Playing => {}
+            SeatStatus::Retired => return self.referee.fallback_move(game_state, player, rng),
+            SeatStatus::Forfeited => return pass_move(game_state, player),
+        }
+        let used = self.time_used[seat];
+        let violation = match (self.referee.time_limit(used), self.controllers[seat].take()) {
+            (Some(Duration::ZERO), controller) => {
+                self.controllers[seat] = controller;
+                Violation::GameTimeout {
+                    used,
+                    budget: self.referee.game_time_budget().unwrap_or_default(),
+                }
+            }
+            (_, Some(controller)) => {
+                let (controller, result, elapsed) = self.referee.ask(controller, view, used);
+                self.controllers[seat] = controller;
+                self.time_used[seat] += elapsed;
+                match result {
+                    Ok(action) if is_legal(game_state, player, action) => return action,
+                    Ok(action) => Violation::IllegalMove(action),
+                    Err(violation) => violation,
+                }
+            }
+            (_, None) => return self.referee.fallback_move(game_state, player, rng),
+        };
+        self.penalise(game_state, player, violation, true, rng)
+            .unwrap()
+    }
+
+    // Tells every seat still playing about a move, penalising controllers that panic
+    fn tell<F: Fn(&mut Box<dyn PlayerController>)>(
+        &mut self,
+        game_state: &GameState,
+        rng: &mut StdRng,
+        tell: F,
+    ) {
+        for player in 0..game_state.no_players() {
+            let seat = player as usize;
+            if self.seat_status[seat] != SeatStatus::Playing {
+                continue;
+            }
+            let Some(controller) = self.controllers[seat].as_mut() else {
+                continue;
+            };
+            if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| tell(controller))) {
+                self.penalise(game_state, player, panic_violation(payload), false, rng);
+            }
+        }
+    }
+
+    // Records the incident and applies the fallback, returning the move played instead when
+    // the seat was asked for one
+    fn penalise(
+        &mut self,
+        game_state: &GameState,
+        player: Player,
+        violation: Violation,
+        bool_replace_move: bool,
+        rng: &mut StdRng,
+    ) -> Option<Coins> {
+        let seat = player as usize;
+        let fallback = self.referee.fallback();
+        if fallback == Fallback::Forfeit {
+            self.seat_status[seat] = SeatStatus::Forfeited;
+        } else if self.controllers[seat].is_none()
+            || matches!(violation, Violation::GameTimeout { .. })
+        {
+            self.seat_status[seat] = SeatStatus::Retired;
+        }
+        let replacement = match bool_replace_move {
+            true => Some(self.referee.fallback_move(game_state, player, rng)),
+            false => None,
+        };
+        let incident = Incident {
+            turn: game_state.turn_no(),
+            player,
+            violation,
+            fallback,
+            replacement,
+        };
+        for observer in self.observers.iter_mut() {
+            observer.incident(game_state, &incident);
+        }
+        self.incidents.push(incident);
+        replacement
+    }
+
+    // Random legal bids until the last property is taken, told to the controllers so trackers
+    // stay in step
+    fn skip_bid_phase(&mut self, mut game_state: GameState, rng: &mut StdRng) -> GameState {
+        while game_state.game_phase() == GamePhase::Bid && !game_state.game_end() {
+            if game_state.auction_end() {
+                game_state = game_state.generate_next_state_reveal();
+                continue;
+            }
+            let player = game_state.current_player();
+            let action = *game_state.legal_moves_bid(player).choose(rng).unwrap();
+            let view = public_view(&game_state, rng);
+            self.tell(&game_state, rng, |controller| {
+                controller.observe_bid(&view, player, action)
+            });
+            game_state = game_state.manual_next_state_bid(player, action);
+        }
+        game_state
+    }
+}
+
+// The table as the players see it, the unrevealed decks shuffled so their order stays hidden
+fn public_view(game_state: &GameState, rng: &mut StdRng) -> GameState {
+    let mut view = game_state.clone();
+    view.determinise(rng);
+    view
+}
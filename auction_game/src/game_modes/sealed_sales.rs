@@ -0,0 +1,43 @@
+use crate::models::enums::{Player, Property};
+
+// Properties committed face down for one sell round, turned over together once every seat has
+// committed so no choice can depend on another
+pub struct SealedSales {
+    commitments: Vec<Option<Property>>,
+}
+
+impl SealedSales {
+    pub fn new(no_players: u8) -> Self {
+        SealedSales {
+            commitments: vec![None; no_players as usize],
+        }
+    }
+    pub fn commit(&mut self, player: Player, property: Property) {
+        debug_assert!(
+            self.commitments[player as usize].is_none(),
+            "Player {} has already committed a property",
+            player + 1
+        );
+        self.commitments[player as usize] = Some(property);
+    }
+    pub fn is_committed(&self, player: Player) -> bool {
+        self.commitments[player as usize].is_some()
+    }
+    pub fn is_complete(&self) -> bool {
+        self.commitments
+            .iter()
+            .all(|commitment| commitment.is_some())
+    }
+
+    // Every seat's property, sales[player] being the one player sold
+    pub fn reveal(self) -> Vec<Property> {
+        self.commitments
+            .into_iter()
+            .enumerate()
+            .map(|(player, commitment)| {
+                commitment
+                    .unwrap_or_else(|| panic!("Player {} has not committed a property", player + 1))
+            })
+            .collect()
+    }
+}
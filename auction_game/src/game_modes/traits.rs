@@ -1,3 +1,24 @@
+use crate::game_modes::referee::Incident;
+use crate::game_modes::runner::GameSetup;
+use crate::models::enums::{Coins, Player, Property};
+use crate::models::game_state::GameState;
+
 pub trait Game {
     fn game_run(&mut self);
 }
+
+// Watches a GameRunner play, for logging, printing and recording games
+pub trait GameObserver {
+    // Called once with the position play starts from
+    fn game_start(&mut self, _setup: &GameSetup, _game_state: &GameState) {}
+    // Called with the state after every auction is revealed
+    fn reveal(&mut self, _game_state: &GameState) {}
+    // Called with the state before player adds action to their bid, 0 being a pass
+    fn bid(&mut self, _game_state: &GameState, _player: Player, _action: Coins) {}
+    // Called with the state before every sell round, sales[player] being the property sold
+    fn sales(&mut self, _game_state: &GameState, _sales: &[Property]) {}
+    // Called with the state a controller broke the rules in
+    fn incident(&mut self, _game_state: &GameState, _incident: &Incident) {}
+    // Called once with the state play stopped at
+    fn game_end(&mut self, _game_state: &GameState) {}
+}
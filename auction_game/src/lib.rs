@@ -1,29 +1,59 @@
 pub mod engines {
+    pub mod abstractions {
+        pub mod bid_abstraction;
+        pub mod card_abstraction;
+        pub mod progressive_widening;
+    }
     pub mod algorithms {
+        pub mod best_response;
         pub mod counterfactual_regret;
+        pub mod ismcts;
         pub mod maxn_player;
         pub mod maxn_player_multi;
     }
+    pub mod beliefs {
+        pub mod belief_tracker;
+    }
     pub mod controllers {
+        pub mod cfr_bidder;
         pub mod greedy_baby;
+        pub mod heuristic_player;
+        pub mod mcts_player;
         pub mod random_player;
         pub mod terminal_player;
     }
+    pub mod opponent_models {
+        pub mod observed_play;
+        pub mod threshold_model;
+    }
     pub mod q_values {
         pub mod regret;
     }
+    pub mod rollouts {
+        pub mod greedy_rollout;
+        pub mod random_rollout;
+    }
     pub mod scorers {
         pub mod naive_round_score;
+        pub mod sell_phase_score;
     }
     pub mod strategies {
         pub mod average;
     }
+    pub mod tablebases {
+        pub mod sell_tablebase;
+    }
     pub mod constants;
+    pub mod registry;
     pub mod traits;
     pub mod utils;
 }
 pub mod game_modes {
+    pub mod observers;
     pub mod play_with_bots;
+    pub mod referee;
+    pub mod runner;
+    pub mod sealed_sales;
     pub mod self_play;
     pub mod standard;
     pub mod traits;
@@ -32,5 +62,18 @@ pub mod helper {}
 pub mod models {
     pub mod constants;
     pub mod enums;
+    pub mod game_record;
     pub mod game_state;
+    pub mod rule_set;
+}
+pub mod tournaments {
+    pub mod duplicate;
+    pub mod ratings;
+    pub mod sprt;
+    pub mod standings;
+    pub mod tournament;
+}
+pub mod tui {
+    pub mod cfr_dashboard;
+    pub mod replay;
 }
@@ -0,0 +1,403 @@
+use crate::models::enums::{Coins, GamePhase, Player, Property};
+use crate::models::game_state::GameState;
+use crate::models::rule_set::RuleSet;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordedMove {
+    // Cards turned up for the next auction, highest first
+    Reveal(Vec<u8>),
+    // Coins player added to their bid, 0 for a pass
+    Bid { player: Player, action: Coins },
+    // Property every seat sold, in seat order
+    Sales(Vec<Property>),
+    // Notes between the moves, such as referee incidents
+    Comment(String),
+}
+
+// A played game as text, headers in square brackets followed by one move per line, in the
+// spirit of PGN:
+//
+// [Event "selfplay_42"]
+// [Players "4"]
+// [Coins "21"]
+// [P1 "heuristic:preset=balanced"]
+// [Start "P1"]
+// [Result "45 37 58 66"]
+//
+// 1. reveal 30 22 14 5
+// 1. P1 bid 3
+// 1. P2 pass
+// 8. sell 3 17 22 9
+// {turn 40 player 2 panicked: index out of bounds, pass 0}
+//
+// The number in front of each move is the auction it belongs to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRecord {
+    pub event: String,
+    pub date: String,
+    pub seed: Option<u64>,
+    pub rule_set: RuleSet,
+    // Name or bot spec of every seat
+    pub players: Vec<String>,
+    pub starting_player: Player,
+    // Checks plus coins of every seat, None while the game is unfinished
+    pub result: Option<Vec<u8>>,
+    pub moves: Vec<RecordedMove>,
+}
+
+impl GameRecord {
+    pub fn new(rule_set: RuleSet, starting_player: Player) -> Self {
+        GameRecord {
+            event: "?".to_string(),
+            date: "?".to_string(),
+            seed: None,
+            rule_set,
+            players: (0..rule_set.no_players())
+                .map(|player| format!("P{}", player + 1))
+                .collect(),
+            starting_player,
+            result: None,
+            moves: Vec::new(),
+        }
+    }
+
+    // Moves that led from the deal to game_state, read from its path encoding
+    pub fn moves_to(game_state: &GameState) -> Vec<RecordedMove> {
+        let cards = |text: &str| -> Vec<u8> {
+            text.split(':')
+                .filter_map(|card| card.parse().ok())
+                .collect()
+        };
+        game_state
+            .get_path_encoding()
+            .split('|')
+            .filter_map(|step| {
+                if let Some(revealed) = step.strip_prefix('R') {
+                    Some(RecordedMove::Reveal(cards(revealed)))
+                } else if let Some(sales) = step.strip_prefix('S') {
+                    let mut sales = cards(sales);
+                    sales.truncate(game_state.no_players() as usize);
+                    Some(RecordedMove::Sales(sales))
+                } else {
+                    let (action, player) = step.split_once('P')?;
+                    Some(RecordedMove::Bid {
+                        player: player.parse().ok()?,
+                        action: action.parse().ok()?,
+                    })
+                }
+            })
+            .collect()
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = fs::read_to_string(&path)
+            .map_err(|error| format!("Could not read {}: {error}", path.as_ref().display()))?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut event = "?".to_string();
+        let mut date = "?".to_string();
+        let mut seed = None;
+        let mut no_players: Option<u8> = None;
+        let mut coins: Option<Coins> = None;
+        // Line, seat and name of every seat header
+        let mut players: Vec<(usize, Player, String)> = Vec::new();
+        let mut starting_player: Option<(usize, Player)> = None;
+        let mut result = None;
+        let mut moves = Vec::new();
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let error = |message: &str| format!("Line {}: {message}: {line}", line_no + 1);
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                let (tag, value) = header
+                    .strip_suffix(']')
+                    .and_then(|header| header.split_once(' '))
+                    .ok_or_else(|| error("Malformed header"))?;
+                let value = value.trim().trim_matches('"').to_string();
+                match tag {
+                    "Event" => event = value,
+                    "Date" => date = value,
+                    "Seed" => seed = value.parse().ok(),
+                    "Players" => {
+                        no_players = Some(value.parse().map_err(|_| error("Bad player count"))?)
+                    }
+                    "Coins" => coins = Some(value.parse().map_err(|_| error("Bad coins"))?),
+                    "Start" => {
+                        let seat = parse_player(&value).ok_or_else(|| error("Bad seat"))?;
+                        starting_player = Some((line_no, seat));
+                    }
+                    "Result" => {
+                        result = match value.as_str() {
+                            "*" => None,
+                            _ => Some(
+                                parse_numbers(value.split_whitespace())
+                                    .ok_or_else(|| error("Bad result"))?,
+                            ),
+                        }
+                    }
+                    _ => {
+                        if let Some(seat) = parse_player(tag) {
+                            players.push((line_no, seat, value));
+                        }
+                    }
+                }
+                continue;
+            }
+            if let Some(comment) = line.strip_prefix('{') {
+                let comment = comment
+                    .strip_suffix('}')
+                    .ok_or_else(|| error("Unclosed comment"))?;
+                moves.push(RecordedMove::Comment(comment.to_string()));
+                continue;
+            }
+            let mut tokens = line.split_whitespace().peekable();
+            if tokens.peek().is_some_and(|token| token.ends_with('.')) {
+                tokens.next();
+            }
+            let recorded_move = match tokens.next() {
+                Some("reveal") => {
+                    RecordedMove::Reveal(parse_numbers(tokens).ok_or_else(|| error("Bad cards"))?)
+                }
+                Some("sell") => RecordedMove::Sales(
+                    parse_numbers(tokens).ok_or_else(|| error("Bad properties"))?,
+                ),
+                Some(seat) => {
+                    let player = parse_player(seat).ok_or_else(|| error("Unknown move"))?;
+                    let action = match (tokens.next(), tokens.next()) {
+                        (Some("pass"), None) => 0,
+                        (Some("bid"), Some(coins)) => match coins.parse() {
+                            Ok(coins) if coins > 0 => coins,
+                            _ => return Err(error("Bad bid")),
+                        },
+                        _ => return Err(error("Unknown move")),
+                    };
+                    RecordedMove::Bid { player, action }
+                }
+                None => continue,
+            };
+            moves.push(recorded_move);
+        }
+        let no_players = no_players
+            .or_else(|| players.iter().map(|(_, seat, _)| seat + 1).max())
+            .ok_or("Record does not say how many players there are")?;
+        if !(3..=6).contains(&no_players) {
+            return Err(format!("{no_players} players is not a For Sale game"));
+        }
+        let mut rule_set = RuleSet::standard(no_players);
+        if let Some(coins) = coins {
+            rule_set = rule_set.with_starting_coins(coins);
+        }
+        // Seats are checked once the player count is known, wherever its header is
+        let seat_error = |line_no: usize, seat: Player| {
+            format!(
+                "Line {}: seat P{} in a {no_players} player game",
+                line_no + 1,
+                seat + 1
+            )
+        };
+        let starting_player = match starting_player {
+            Some((line_no, seat)) if seat >= no_players => return Err(seat_error(line_no, seat)),
+            Some((_, seat)) => seat,
+            None => 0,
+        };
+        let mut record = GameRecord::new(rule_set, starting_player);
+        for (line_no, seat, name) in players {
+            if seat >= no_players {
+                return Err(seat_error(line_no, seat));
+            }
+            record.players[seat as usize] = name;
+        }
+        record.event = event;
+        record.date = date;
+        record.seed = seed;
+        record.result = result;
+        record.moves = moves;
+        Ok(record)
+    }
+
+    // The dealt state followed by the state after every reveal, bid and sale, so states[i + 1]
+    // follows plays()[i]. Cards never revealed fill the rest of the decks in order.
+    pub fn states(&self) -> Result<Vec<GameState>, String> {
+        let no_players = self.rule_set.no_players() as usize;
+        // Records built by hand skip parse's checks
+        if !(3..=6).contains(&no_players) {
+            return Err(format!("{no_players} players is not a For Sale game"));
+        }
+        if self.starting_player as usize >= no_players {
+            return Err(format!(
+                "Start is seat P{} in a {no_players} player game",
+                self.starting_player + 1
+            ));
+        }
+        let dealt = GameState::dealt_cards(self.rule_set.no_players());
+        let reveals: Vec<&Vec<u8>> = self
+            .moves
+            .iter()
+            .filter_map(|recorded_move| match recorded_move {
+                RecordedMove::Reveal(cards) => Some(cards),
+                _ => None,
+            })
+            .collect();
+        let property_reveals = reveals.len().min(dealt / no_players);
+        let properties = deck(
+            GameState::all_properties(),
+            reveals[..property_reveals]
+                .iter()
+                .flat_map(|cards| cards.iter()),
+            dealt,
+        )
+        .ok_or("A revealed property is not in the deck")?;
+        let checks = deck(
+            GameState::all_checks(),
+            reveals[property_reveals..]
+                .iter()
+                .flat_map(|cards| cards.iter()),
+            dealt,
+        )
+        .ok_or("A revealed check is not in the deck")?;
+        let mut game_state = self
+            .rule_set
+            .deal(self.starting_player, &mut StdRng::seed_from_u64(0));
+        game_state.set_remaining_properties(properties);
+        game_state.set_remaining_checks(checks);
+
+        let mut states = vec![game_state.clone()];
+        for (move_no, recorded_move) in self.plays().enumerate() {
+            let error = |message: &str| format!("Move {}: {message}", move_no + 1);
+            game_state = match recorded_move {
+                RecordedMove::Reveal(cards) => {
+                    if !game_state.auction_end() || game_state.game_end() {
+                        return Err(error("Reveal before the auction ended"));
+                    }
+                    if cards.len() != no_players {
+                        return Err(error("Reveal of the wrong number of cards"));
+                    }
+                    game_state.generate_next_state_reveal_manual(cards.clone())
+                }
+                RecordedMove::Bid { player, action } => {
+                    if game_state.game_phase() != GamePhase::Bid
+                        || game_state.auction_end()
+                        || game_state.current_player() != *player
+                    {
+                        return Err(error("Bid out of turn"));
+                    }
+                    if !game_state.legal_moves_bid(*player).contains(action) {
+                        return Err(error("Illegal bid"));
+                    }
+                    game_state.manual_next_state_bid(*player, *action)
+                }
+                RecordedMove::Sales(sales) => {
+                    if game_state.game_phase() != GamePhase::Sell || game_state.auction_end() {
+                        return Err(error("Sale outside a sell round"));
+                    }
+                    if sales.len() != no_players
+                        || sales.iter().enumerate().any(|(player, property)| {
+                            !game_state
+                                .legal_moves_sell(player as Player)
+                                .contains(property)
+                        })
+                    {
+                        return Err(error("Illegal sale"));
+                    }
+                    game_state.manual_next_state_sell(sales.clone())
+                }
+                RecordedMove::Comment(_) => unreachable!(),
+            };
+            states.push(game_state.clone());
+        }
+        Ok(states)
+    }
+
+    // Every move but the comments
+    pub fn plays(&self) -> impl Iterator<Item = &RecordedMove> {
+        self.moves
+            .iter()
+            .filter(|recorded_move| !matches!(recorded_move, RecordedMove::Comment(_)))
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[Event \"{}\"]", self.event)?;
+        writeln!(f, "[Date \"{}\"]", self.date)?;
+        if let Some(seed) = self.seed {
+            writeln!(f, "[Seed \"{}\"]", seed)?;
+        }
+        writeln!(f, "[Players \"{}\"]", self.rule_set.no_players())?;
+        writeln!(f, "[Coins \"{}\"]", self.rule_set.starting_coins())?;
+        for (seat, name) in self.players.iter().enumerate() {
+            writeln!(f, "[P{} \"{}\"]", seat + 1, name.replace('"', "'"))?;
+        }
+        writeln!(f, "[Start \"P{}\"]", self.starting_player + 1)?;
+        match &self.result {
+            Some(scores) => writeln!(f, "[Result \"{}\"]", join(scores))?,
+            None => writeln!(f, "[Result \"*\"]")?,
+        }
+        writeln!(f)?;
+        let mut auction = 0;
+        for recorded_move in self.moves.iter() {
+            match recorded_move {
+                RecordedMove::Reveal(cards) => {
+                    auction += 1;
+                    writeln!(f, "{auction}. reveal {}", join(cards))?
+                }
+                RecordedMove::Bid { player, action: 0 } => {
+                    writeln!(f, "{auction}. P{} pass", player + 1)?
+                }
+                RecordedMove::Bid { player, action } => {
+                    writeln!(f, "{auction}. P{} bid {}", player + 1, action)?
+                }
+                RecordedMove::Sales(sales) => writeln!(f, "{auction}. sell {}", join(sales))?,
+                RecordedMove::Comment(comment) => writeln!(f, "{{{}}}", comment.replace('}', ")"))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// Seat of a P1 style name
+fn parse_player(text: &str) -> Option<Player> {
+    let seat: Player = text.strip_prefix('P')?.parse().ok()?;
+    seat.checked_sub(1)
+}
+
+fn parse_numbers<'a, I: Iterator<Item = &'a str>>(tokens: I) -> Option<Vec<u8>> {
+    tokens.map(|token| token.parse().ok()).collect()
+}
+
+fn join(values: &[u8]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// The dealt cards: every revealed card, then cards never revealed up to dealt, None when a
+// revealed card is not in the deck
+fn deck<'a, I: Iterator<Item = &'a u8>>(
+    mut cards: Vec<u8>,
+    revealed: I,
+    dealt: usize,
+) -> Option<Vec<u8>> {
+    let mut deck = Vec::with_capacity(dealt);
+    for card in revealed {
+        let position = cards.iter().position(|other| other == card)?;
+        deck.push(cards.remove(position));
+    }
+    let unrevealed = dealt.checked_sub(deck.len())?;
+    deck.extend(cards.into_iter().take(unrevealed));
+    Some(deck)
+}
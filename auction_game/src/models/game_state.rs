@@ -3,7 +3,7 @@ use ahash::AHashMap;
 use itertools::Itertools;
 use log::info;
 use rand::seq::{IndexedRandom, SliceRandom};
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
 use std::fmt;
 use std::fmt::Write;
 use std::hash::{Hash, Hasher};
@@ -30,6 +30,10 @@ pub struct GameState {
 
 impl GameState {
     pub fn starting(no_players: u8, starting_player: u8) -> Self {
+        Self::starting_with_rng(no_players, starting_player, &mut thread_rng())
+    }
+    // Same deal for the same generator state, for seeded and duplicate games
+    pub fn starting_with_rng<R: Rng>(no_players: u8, starting_player: u8, rng: &mut R) -> Self {
         debug_assert!(
             no_players < 7,
             "Please ensure no_players is < 7. It is currently {no_players}"
@@ -39,15 +43,7 @@ impl GameState {
             "Please ensure no_players is > 2. It is currently {no_players}"
         );
 
-        let starting_coins = match no_players {
-            6 => 14,
-            5 => 16,
-            4 => 21,
-            3 => 28,
-            _ => {
-                panic!("Please ensure 3 <= no_players <= 6. Received no_players = {no_players}")
-            }
-        };
+        let starting_coins = Self::starting_coins(no_players);
         let mut coins: Vec<Coins> = Vec::with_capacity(no_players as usize);
         let mut active_players: Vec<bool> = Vec::with_capacity(no_players as usize);
         let active_bids: Vec<u8> = vec![0; 6];
@@ -55,14 +51,14 @@ impl GameState {
             coins.push(starting_coins);
             active_players.push(true);
         }
-        let mut remaining_properties: Vec<Property> = (1..=30).collect();
-        let mut rng = thread_rng();
-        remaining_properties.shuffle(&mut rng);
-        let mut remaining_checks: Vec<Check> = vec![
-            0, 0, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
-            14, 14, 15, 15,
-        ];
-        remaining_checks.shuffle(&mut rng);
+        let mut remaining_properties: Vec<Property> = Self::all_properties();
+        remaining_properties.shuffle(rng);
+        let mut remaining_checks: Vec<Check> = Self::all_checks();
+        remaining_checks.shuffle(rng);
+        // The rest of each deck is set aside unseen
+        let dealt = Self::dealt_cards(no_players);
+        remaining_properties.truncate(dealt);
+        remaining_checks.truncate(dealt);
 
         let mut properties: AHashMap<Player, Vec<Property>> =
             AHashMap::with_capacity(no_players as usize);
@@ -92,6 +88,37 @@ impl GameState {
             path_encoding: "|O".to_string(), //O for Origin
         }
     }
+    pub fn starting_coins(no_players: u8) -> Coins {
+        match no_players {
+            6 => 14,
+            5 => 16,
+            4 => 21,
+            3 => 28,
+            _ => {
+                panic!("Please ensure 3 <= no_players <= 6. Received no_players = {no_players}")
+            }
+        }
+    }
+    // Properties, and checks, played with: 6 of each are set aside with 3 players and 2 with 4
+    pub fn dealt_cards(no_players: u8) -> usize {
+        match no_players {
+            3 => 24,
+            4 => 28,
+            5 | 6 => 30,
+            _ => {
+                panic!("Please ensure 3 <= no_players <= 6. Received no_players = {no_players}")
+            }
+        }
+    }
+    pub fn all_properties() -> Vec<Property> {
+        (1..=30).collect()
+    }
+    pub fn all_checks() -> Vec<Check> {
+        vec![
+            0, 0, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+            14, 14, 15, 15,
+        ]
+    }
     pub fn previous_player(&self) -> Player {
         self.previous_decision_player.unwrap()
     }
@@ -157,6 +184,20 @@ impl GameState {
         );
         self.coins[player as usize]
     }
+    // Setters for rebuilding a state from beliefs, they do not touch the path encoding
+    pub fn set_player_properties(&mut self, player: Player, mut properties: Vec<Property>) {
+        properties.sort_unstable();
+        self.properties.insert(player, properties);
+    }
+    pub fn set_player_coins(&mut self, player: Player, coins: Coins) {
+        self.coins[player as usize] = coins;
+    }
+    pub fn set_remaining_properties(&mut self, remaining_properties: Vec<Property>) {
+        self.remaining_properties = remaining_properties;
+    }
+    pub fn set_remaining_checks(&mut self, remaining_checks: Vec<Check>) {
+        self.remaining_checks = remaining_checks;
+    }
     pub fn get_player_checks(&self, player: Player) -> &Vec<Check> {
         debug_assert!(
             player < self.no_players,
@@ -174,6 +215,12 @@ impl GameState {
     pub fn get_coins(&self) -> &Vec<Coins> {
         &self.coins
     }
+    pub fn get_active_bids(&self) -> &Vec<u8> {
+        &self.active_bids
+    }
+    pub fn get_active_players(&self) -> &Vec<bool> {
+        &self.active_players
+    }
     pub fn get_auction_pool(&self) -> &Vec<u8> {
         &self.auction_pool
     }
@@ -329,6 +376,64 @@ impl GameState {
             auction_pool_str,
         )
     }
+    pub fn get_holdings_encoding(&self) -> String {
+        // Everything public that decides the sell phase apart from coins: holdings, checks won and
+        // the checks on offer. The checks still to come are whatever has not been seen, so the
+        // real deck, which also leaves out the cards set aside, stays out of the key.
+        // Built by hand as search engines key on it at every node.
+        fn push_u8(encoding: &mut String, num: u8) {
+            encoding.push((b'0' + num / 10) as char);
+            encoding.push((b'0' + num % 10) as char);
+        }
+        let mut encoding = String::with_capacity(160);
+        encoding.push_str(match self.game_phase {
+            GamePhase::Bid => "Bid|p",
+            GamePhase::Sell => "Sell|p",
+        });
+        for player in 0..self.no_players {
+            if player > 0 {
+                encoding.push('-');
+            }
+            self.properties[&player]
+                .iter()
+                .for_each(|&property| push_u8(&mut encoding, property));
+        }
+        encoding.push_str("|ch");
+        for player in 0..self.no_players {
+            if player > 0 {
+                encoding.push('-');
+            }
+            self.checks[&player]
+                .iter()
+                .for_each(|&check| push_u8(&mut encoding, check));
+        }
+        encoding.push_str("|a");
+        self.auction_pool
+            .iter()
+            .for_each(|&check| push_u8(&mut encoding, check));
+        encoding
+    }
+    pub fn get_information_set_encoding(&self) -> String {
+        // Only the deck order is hidden in For Sale and it is hidden from everyone, so every
+        // player's information set is the public state rather than the path that led to it. The
+        // properties still to come are whatever has not been seen, so the deck stays out of it.
+        match self.game_phase {
+            GamePhase::Sell => self.get_holdings_encoding(),
+            GamePhase::Bid => {
+                let active_str: String = self
+                    .active_players
+                    .iter()
+                    .map(|&active| if active { '1' } else { '0' })
+                    .collect();
+                format!(
+                    "{}|t{}|x{}",
+                    self.get_state_encoding(),
+                    self.current_player(),
+                    active_str
+                )
+            }
+        }
+    }
     pub fn get_parent_encoding(&self) -> String {
         // TODO: Change to getting parent from current path encoding
         if let Some(pos) = self.path_encoding.rfind("|") {
@@ -522,20 +627,43 @@ impl GameState {
             self.auction_pool
         );
 
-        match self.game_phase {
-            GamePhase::Bid => {
-                // Ensure all provided values exist in the remaining_properties
-                self.remaining_properties.retain(|x| !values.contains(x));
-            }
-            GamePhase::Sell => {
-                // Ensure all provided values exist in the remaining_checks
-                self.remaining_checks.retain(|x| !values.contains(x));
+        // Removes one card per revealed value, checks come in pairs so retain would drop both
+        let deck = match self.game_phase {
+            GamePhase::Bid => &mut self.remaining_properties,
+            GamePhase::Sell => &mut self.remaining_checks,
+        };
+        for value in values.iter() {
+            if let Some(pos) = deck.iter().position(|x| x == value) {
+                deck.remove(pos);
+            } else {
+                debug_assert!(false, "Revealed value {value} is not in the remaining deck");
             }
         }
         self.auction_pool = values;
         self.auction_pool.sort_unstable_by(|a, b| b.cmp(a));
         self.update_path_encoding_vec(true);
     }
+    // Reveals checks drawn from the unseen ones rather than the deck, which is redrawn around
+    // them, for search enumerating every deal the public state allows
+    pub fn reveal_auction_unseen(&mut self, values: Vec<u8>) {
+        debug_assert!(
+            self.game_phase == GamePhase::Sell,
+            "Only checks are drawn from the unseen cards"
+        );
+        let mut deck = values.clone();
+        let mut rest = self.unseen_checks();
+        for value in values.iter() {
+            if let Some(position) = rest.iter().position(|unseen| unseen == value) {
+                rest.remove(position);
+            } else {
+                debug_assert!(false, "Revealed value {value} has already been seen");
+            }
+        }
+        deck.extend(rest);
+        deck.truncate(self.remaining_checks.len());
+        self.remaining_checks = deck;
+        self.reveal_auction_manual(values);
+    }
     pub fn reveal_auction_perms(&self, random_sample: bool, n_sample: u32) -> Vec<Self> {
         // TODO: Validate this function
         debug_assert!(self.auction_pool.len() == 0, "Cannot reveal new auction while another auction has yet to end. Current auctio is: {:?}", self.auction_pool);
@@ -633,6 +761,75 @@ impl GameState {
         }
         results
     }
+    // Checks not turned up yet, the deck together with any set aside, lowest first
+    pub fn unseen_checks(&self) -> Vec<Check> {
+        let mut unseen_checks = Self::all_checks();
+        let seen_checks = self.checks.values().flatten().chain(match self.game_phase {
+            GamePhase::Bid => [].iter(),
+            GamePhase::Sell => self.auction_pool.iter(),
+        });
+        for check in seen_checks {
+            if let Some(position) = unseen_checks.iter().position(|unseen| unseen == check) {
+                unseen_checks.remove(position);
+            }
+        }
+        unseen_checks
+    }
+    pub fn determinise<R: Rng>(&mut self, rng: &mut R) {
+        // Redraws the hidden decks from every card not seen yet so search can sample one
+        // consistent world, as the cards set aside are as unknown as the deck
+        let mut unseen_properties = Self::all_properties();
+        let seen_properties = self
+            .properties
+            .values()
+            .flatten()
+            .chain(match self.game_phase {
+                GamePhase::Bid => self.auction_pool.iter(),
+                GamePhase::Sell => [].iter(),
+            });
+        for property in seen_properties {
+            unseen_properties.retain(|unseen| unseen != property);
+        }
+        let mut unseen_checks = self.unseen_checks();
+        debug_assert!(
+            unseen_properties.len() >= self.remaining_properties.len()
+                && unseen_checks.len() >= self.remaining_checks.len(),
+            "Fewer cards unseen than left in the decks"
+        );
+        unseen_properties.shuffle(rng);
+        unseen_properties.truncate(self.remaining_properties.len());
+        self.remaining_properties = unseen_properties;
+        unseen_checks.shuffle(rng);
+        unseen_checks.truncate(self.remaining_checks.len());
+        self.remaining_checks = unseen_checks;
+    }
+    pub fn generate_next_state_reveal(&self) -> Self {
+        debug_assert!(
+            self.auction_end(),
+            "Cannot reveal new auction while another auction has yet to end. Current auction is: {:?}",
+            self.auction_pool
+        );
+        let mut new_state = self.clone();
+        new_state.reveal_auction();
+        if new_state.game_phase == GamePhase::Sell {
+            new_state.active_bids = vec![0; 6];
+        }
+        new_state
+    }
+    // Reveals the given cards instead of the top of the deck, for replaying recorded games
+    pub fn generate_next_state_reveal_manual(&self, values: Vec<u8>) -> Self {
+        debug_assert!(
+            self.auction_end(),
+            "Cannot reveal new auction while another auction has yet to end. Current auction is: {:?}",
+            self.auction_pool
+        );
+        let mut new_state = self.clone();
+        new_state.reveal_auction_manual(values);
+        if new_state.game_phase == GamePhase::Sell {
+            new_state.active_bids = vec![0; 6];
+        }
+        new_state
+    }
     pub fn generate_next_state_bid(&self, player: Player, action: Coins) -> Self {
         if self.auction_end() {
             let mut new_state: GameState = self.clone();
@@ -719,7 +916,7 @@ impl GameState {
     }
     pub fn bid_phase_end(&self) -> bool {
         if self.remaining_properties.len() == 0
-            && (self.remaining_checks.len() < 30 || self.auction_pool.len() == 0)
+            && (self.game_phase == GamePhase::Sell || self.auction_pool.is_empty())
         {
             true
         } else {
@@ -0,0 +1,62 @@
+use crate::models::enums::{Coins, Player};
+use crate::models::game_state::GameState;
+use rand::Rng;
+use std::fmt;
+
+// Player count and starting coins a game is dealt under, the decks are always the standard ones
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RuleSet {
+    no_players: u8,
+    starting_coins: Coins,
+}
+
+impl RuleSet {
+    pub fn standard(no_players: u8) -> Self {
+        RuleSet {
+            no_players,
+            starting_coins: GameState::starting_coins(no_players),
+        }
+    }
+    pub fn with_starting_coins(mut self, starting_coins: Coins) -> Self {
+        self.starting_coins = starting_coins;
+        self
+    }
+    pub fn no_players(&self) -> u8 {
+        self.no_players
+    }
+    pub fn starting_coins(&self) -> Coins {
+        self.starting_coins
+    }
+    pub fn is_standard(&self) -> bool {
+        self.starting_coins == GameState::starting_coins(self.no_players)
+    }
+
+    // Same deal as GameState::starting_with_rng for standard rules
+    pub fn deal<R: Rng>(&self, starting_player: Player, rng: &mut R) -> GameState {
+        let mut game_state = GameState::starting_with_rng(self.no_players, starting_player, rng);
+        if !self.is_standard() {
+            for player in 0..self.no_players {
+                game_state.set_player_coins(player, self.starting_coins);
+            }
+        }
+        game_state
+    }
+    // The deal every seeded game plays, with the starting player drawn first when it is random
+    pub fn deal_with_rng<R: Rng>(&self, random_starting_player: bool, rng: &mut R) -> GameState {
+        let starting_player: Player = match random_starting_player {
+            false => 0,
+            true => rng.gen_range(0..self.no_players),
+        };
+        self.deal(starting_player, rng)
+    }
+}
+
+impl fmt::Display for RuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} players, {} coins",
+            self.no_players, self.starting_coins
+        )
+    }
+}
@@ -0,0 +1,233 @@
+use crate::game_modes::referee::Referee;
+use crate::tournaments::standings::Standings;
+use crate::tournaments::tournament::{play_seeded, Entrant, GameResult};
+use log::info;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+const Z_95: f64 = 1.96;
+
+// Every deal is replayed with every distinct seat order of the lineup, so each entrant gets
+// the same cards from every seat and the luck of the deck cancels out
+pub struct DuplicateMatch {
+    entrants: Vec<Entrant>,
+    // Entrant index of every seat before permuting, entrants may fill several seats
+    lineup: Vec<usize>,
+    deals: usize,
+    seed: u64,
+    referee: Referee,
+}
+
+// How an entrant did against the field on average over the deals
+#[derive(Clone, Debug)]
+pub struct DuplicateRow {
+    pub name: String,
+    pub deals: usize,
+    // Average score per game minus the average score of all seats on the same deal
+    pub relative_score: f64,
+    // Half width of its 95% confidence interval
+    pub interval: f64,
+}
+
+pub struct DuplicateReport {
+    rows: Vec<DuplicateRow>,
+    // relative_scores[deal][entrant], NaN where the entrant did not play
+    relative_scores: Vec<Vec<f64>>,
+    standings: Standings,
+}
+
+impl DuplicateMatch {
+    // Entrants take turns filling no_players seats
+    pub fn new(entrants: Vec<Entrant>, no_players: u8, deals: usize) -> Self {
+        debug_assert!(!entrants.is_empty(), "A match needs entrants");
+        let lineup = (0..no_players as usize)
+            .map(|seat| seat % entrants.len())
+            .collect();
+        DuplicateMatch {
+            entrants,
+            lineup,
+            deals,
+            seed: 0,
+            referee: Referee::default(),
+        }
+    }
+    pub fn with_lineup(mut self, lineup: Vec<usize>) -> Self {
+        debug_assert!(
+            (3..=6).contains(&lineup.len()),
+            "Lineup must fill between 3 and 6 seats"
+        );
+        debug_assert!(
+            lineup.iter().all(|&entrant| entrant < self.entrants.len()),
+            "Lineup refers to a missing entrant"
+        );
+        self.lineup = lineup;
+        self
+    }
+    // Deal i is dealt from seed + i
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+    pub fn with_referee(mut self, referee: Referee) -> Self {
+        self.referee = referee;
+        self
+    }
+    pub fn games_per_deal(&self) -> usize {
+        self.seatings().len()
+    }
+
+    pub fn seatings(&self) -> Vec<Vec<usize>> {
+        distinct_seatings(&self.lineup)
+    }
+
+    pub fn run(&self) -> DuplicateReport {
+        let seatings = self.seatings();
+        let n = self.entrants.len();
+        let mut results: Vec<GameResult> = Vec::with_capacity(self.deals * seatings.len());
+        let mut relative_scores: Vec<Vec<f64>> = Vec::with_capacity(self.deals);
+        for deal in 0..self.deals {
+            let seed = self.seed.wrapping_add(deal as u64);
+            let deal_results: Vec<GameResult> = seatings
+                .iter()
+                .map(|seating| play_seeded(&self.entrants, seed, seating.clone(), self.referee))
+                .collect();
+            let mut totals = vec![0.0; n];
+            let mut seats = vec![0; n];
+            for result in deal_results.iter() {
+                for (&entrant, &score) in result.entrants.iter().zip(result.scores.iter()) {
+                    totals[entrant] += score as f64;
+                    seats[entrant] += 1;
+                }
+            }
+            let field = totals.iter().sum::<f64>() / seats.iter().sum::<usize>().max(1) as f64;
+            let deal_scores: Vec<f64> = (0..n)
+                .map(|entrant| match seats[entrant] {
+                    0 => f64::NAN,
+                    played => totals[entrant] / played as f64 - field,
+                })
+                .collect();
+            info!("Deal {} seed {}: {:?}", deal + 1, seed, deal_scores);
+            relative_scores.push(deal_scores);
+            results.extend(deal_results);
+        }
+        let names: Vec<String> = self
+            .entrants
+            .iter()
+            .map(|entrant| entrant.name().to_string())
+            .collect();
+        let rows = (0..n)
+            .map(|entrant| {
+                let scores: Vec<f64> = relative_scores
+                    .iter()
+                    .map(|deal| deal[entrant])
+                    .filter(|score| !score.is_nan())
+                    .collect();
+                let deals = scores.len();
+                let mean = scores.iter().sum::<f64>() / deals.max(1) as f64;
+                let variance = scores
+                    .iter()
+                    .map(|score| (score - mean).powi(2))
+                    .sum::<f64>()
+                    / deals.saturating_sub(1).max(1) as f64;
+                DuplicateRow {
+                    name: names[entrant].clone(),
+                    deals,
+                    relative_score: mean,
+                    interval: Z_95 * (variance / deals.max(1) as f64).sqrt(),
+                }
+            })
+            .collect();
+        DuplicateReport {
+            rows,
+            relative_scores,
+            standings: Standings::new(names, results),
+        }
+    }
+}
+
+impl DuplicateReport {
+    pub fn rows(&self) -> &[DuplicateRow] {
+        &self.rows
+    }
+    pub fn relative_scores(&self) -> &[Vec<f64>] {
+        &self.relative_scores
+    }
+    // Standings over every game played
+    pub fn standings(&self) -> &Standings {
+        &self.standings
+    }
+
+    pub fn leaderboard(&self) -> String {
+        let mut rows: Vec<&DuplicateRow> = self.rows.iter().collect();
+        rows.sort_by(|a, b| b.relative_score.total_cmp(&a.relative_score));
+        let width = rows
+            .iter()
+            .map(|row| row.name.len())
+            .max()
+            .unwrap_or(0)
+            .max(6);
+        let mut board = format!(
+            "{:>4}  {:<width$}  {:>5}  {:>16}\n",
+            "Rank", "Player", "Deals", "Score vs field"
+        );
+        for (rank, row) in rows.iter().enumerate() {
+            board.push_str(&format!(
+                "{:>4}  {:<width$}  {:>5}  {:>+8.2} ± {:<5.2}\n",
+                rank + 1,
+                row.name,
+                row.deals,
+                row.relative_score,
+                row.interval
+            ));
+        }
+        board
+    }
+
+    // Tab separated duplicate scores, one line per deal, then the usual standings
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        writeln!(file, "player\tdeals\trelative_score\trelative_score_95")?;
+        for row in self.rows.iter() {
+            writeln!(
+                file,
+                "{}\t{}\t{:.3}\t{:.3}",
+                row.name, row.deals, row.relative_score, row.interval
+            )?;
+        }
+        writeln!(file)?;
+        let names: Vec<&str> = self.rows.iter().map(|row| row.name.as_str()).collect();
+        writeln!(file, "deal\t{}", names.join("\t"))?;
+        for (deal, scores) in self.relative_scores.iter().enumerate() {
+            let scores: Vec<String> = scores.iter().map(|score| format!("{score:.3}")).collect();
+            writeln!(file, "{}\t{}", deal + 1, scores.join("\t"))?;
+        }
+        writeln!(file)?;
+        write!(file, "{}", self.standings.leaderboard())
+    }
+}
+
+// Distinct orders of lineup, in lexicographic order
+pub fn distinct_seatings(lineup: &[usize]) -> Vec<Vec<usize>> {
+    let mut seating = lineup.to_vec();
+    seating.sort_unstable();
+    let mut seatings = vec![seating.clone()];
+    while next_permutation(&mut seating) {
+        seatings.push(seating.clone());
+    }
+    seatings
+}
+
+// Rearranges values into the next greater order, false once they are in descending order
+fn next_permutation(values: &mut [usize]) -> bool {
+    let Some(pivot) = (1..values.len()).rev().find(|&i| values[i - 1] < values[i]) else {
+        return false;
+    };
+    let successor = (pivot..values.len())
+        .rev()
+        .find(|&i| values[i] > values[pivot - 1])
+        .unwrap();
+    values.swap(pivot - 1, successor);
+    values[pivot..].reverse();
+    true
+}
@@ -0,0 +1,146 @@
+use crate::tournaments::tournament::GameResult;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const MM_ITERATIONS: usize = 1000;
+const MM_TOLERANCE: f64 = 1e-9;
+const AVERAGE_RATING: f64 = 1500.0;
+const Z_95: f64 = 1.96;
+const BOOTSTRAP_RESAMPLES: usize = 200;
+
+// Elo style rating with the half width of its 95% confidence interval
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rating {
+    pub rating: f64,
+    pub interval: f64,
+}
+
+// Pairwise results between entrants: every game counts as a match between each pair of seats,
+// worth 1/(players - 1) so a game weighs the same whatever the player count
+pub struct PairwiseResults {
+    // wins[i][j] is what entrant i scored against entrant j, ties counting half
+    wins: Vec<Vec<f64>>,
+}
+
+impl PairwiseResults {
+    pub fn new(no_entrants: usize, results: &[GameResult]) -> Self {
+        let mut wins = vec![vec![0.0; no_entrants]; no_entrants];
+        for result in results.iter() {
+            let seats = result.entrants.len();
+            if seats < 2 {
+                continue;
+            }
+            let weight = 1.0 / (seats - 1) as f64;
+            for a in 0..seats {
+                for b in 0..seats {
+                    // Entrants playing themselves tell us nothing
+                    if result.entrants[a] == result.entrants[b] {
+                        continue;
+                    }
+                    let score = match result.scores[a].cmp(&result.scores[b]) {
+                        std::cmp::Ordering::Greater => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Less => 0.0,
+                    };
+                    wins[result.entrants[a]][result.entrants[b]] += weight * score;
+                }
+            }
+        }
+        PairwiseResults { wins }
+    }
+    pub fn no_entrants(&self) -> usize {
+        self.wins.len()
+    }
+    pub fn score(&self, a: usize, b: usize) -> f64 {
+        self.wins[a][b]
+    }
+    pub fn games(&self, a: usize, b: usize) -> f64 {
+        self.wins[a][b] + self.wins[b][a]
+    }
+
+    // Bradley-Terry strengths by minorization-maximization. Every entrant also draws one virtual
+    // game against a strength 1 player, which keeps unbeaten or winless entrants finite.
+    pub fn strengths(&self) -> Vec<f64> {
+        let n = self.no_entrants();
+        let mut strengths = vec![1.0; n];
+        for _ in 0..MM_ITERATIONS {
+            let mut change: f64 = 0.0;
+            for i in 0..n {
+                let total_score: f64 = 0.5 + (0..n).map(|j| self.wins[i][j]).sum::<f64>();
+                let denominator: f64 = 1.0 / (strengths[i] + 1.0)
+                    + (0..n)
+                        .filter(|&j| j != i)
+                        .map(|j| self.games(i, j) / (strengths[i] + strengths[j]))
+                        .sum::<f64>();
+                let updated = total_score / denominator;
+                change = change.max((updated.ln() - strengths[i].ln()).abs());
+                strengths[i] = updated;
+            }
+            if change < MM_TOLERANCE {
+                break;
+            }
+        }
+        strengths
+    }
+
+    // Elo scale ratings averaging 1500, with intervals from the Fisher information of each
+    // entrant's log strength. The intervals treat every pair of seats as a game of its own, which
+    // the pairs from one multiplayer game are not, so they come out too narrow; bootstrap_ratings
+    // keeps games whole.
+    pub fn ratings(&self) -> Vec<Rating> {
+        let n = self.no_entrants();
+        if n == 0 {
+            return Vec::new();
+        }
+        let strengths = self.strengths();
+        let scale = 400.0 / std::f64::consts::LN_10;
+        let log_strengths: Vec<f64> = strengths.iter().map(|strength| strength.ln()).collect();
+        let mean = log_strengths.iter().sum::<f64>() / n as f64;
+        (0..n)
+            .map(|i| {
+                let information: f64 = strengths[i] / (strengths[i] + 1.0).powi(2)
+                    + (0..n)
+                        .filter(|&j| j != i)
+                        .map(|j| {
+                            self.games(i, j) * strengths[i] * strengths[j]
+                                / (strengths[i] + strengths[j]).powi(2)
+                        })
+                        .sum::<f64>();
+                Rating {
+                    rating: AVERAGE_RATING + scale * (log_strengths[i] - mean),
+                    interval: Z_95 * scale / information.sqrt(),
+                }
+            })
+            .collect()
+    }
+}
+
+// Ratings of PairwiseResults::ratings with intervals from resampling whole games instead, so the
+// correlated pairs within a game stay together
+pub fn bootstrap_ratings(no_entrants: usize, results: &[GameResult], seed: u64) -> Vec<Rating> {
+    let mut ratings = PairwiseResults::new(no_entrants, results).ratings();
+    if results.is_empty() {
+        return ratings;
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut samples: Vec<Vec<f64>> = vec![Vec::with_capacity(BOOTSTRAP_RESAMPLES); no_entrants];
+    for _ in 0..BOOTSTRAP_RESAMPLES {
+        let resample: Vec<GameResult> = (0..results.len())
+            .map(|_| results[rng.gen_range(0..results.len())].clone())
+            .collect();
+        let resampled = PairwiseResults::new(no_entrants, &resample).ratings();
+        for (entrant, rating) in resampled.iter().enumerate() {
+            samples[entrant].push(rating.rating);
+        }
+    }
+    for (rating, samples) in ratings.iter_mut().zip(samples.iter()) {
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance = samples
+            .iter()
+            .map(|sample| (sample - mean).powi(2))
+            .sum::<f64>()
+            / (samples.len() - 1) as f64;
+        rating.interval = Z_95 * variance.sqrt();
+    }
+    ratings
+}
@@ -0,0 +1,225 @@
+use crate::game_modes::referee::Referee;
+use crate::tournaments::duplicate::distinct_seatings;
+use crate::tournaments::tournament::{play_seeded, Entrant, GameResult};
+
+const CANDIDATE: usize = 0;
+const BASELINE: usize = 1;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SprtDecision {
+    // Candidate gains at least elo1
+    AcceptH1,
+    // Candidate gains at most elo0
+    AcceptH0,
+    Continue,
+}
+
+// Where the test stands after some deals
+#[derive(Clone, Debug)]
+pub struct SprtStatus {
+    pub deals: usize,
+    pub games: usize,
+    // Candidate's average head to head score against the baseline, ties counting half
+    pub score: f64,
+    pub llr: f64,
+    pub lower_bound: f64,
+    pub upper_bound: f64,
+    pub decision: SprtDecision,
+}
+
+impl SprtStatus {
+    // Elo difference the score implies
+    pub fn elo(&self) -> f64 {
+        score_to_elo(self.score)
+    }
+}
+
+// Sequential probability ratio test of H1: the candidate is elo1 stronger than the baseline,
+// against H0: it is only elo0 stronger. Deals are played over every seating of the two
+// controllers like a duplicate match, each deal being one trial. With an odd player count one
+// controller would hold the extra seat, so every deal is also played with the lineup mirrored.
+// The log likelihood ratio uses the normal approximation of the trial scores so it works with
+// fractional scores.
+pub struct Sprt {
+    entrants: Vec<Entrant>,
+    no_players: u8,
+    elo0: f64,
+    elo1: f64,
+    alpha: f64,
+    beta: f64,
+    min_deals: usize,
+    max_deals: usize,
+    seed: u64,
+    referee: Referee,
+}
+
+impl Sprt {
+    pub fn new(candidate: Entrant, baseline: Entrant) -> Self {
+        Sprt {
+            entrants: vec![candidate, baseline],
+            no_players: 4,
+            elo0: 0.0,
+            elo1: 10.0,
+            alpha: 0.05,
+            beta: 0.05,
+            min_deals: 16,
+            max_deals: 10000,
+            seed: 0,
+            referee: Referee::default(),
+        }
+    }
+    // Seats alternate between candidate and baseline, odd counts also playing the mirrored lineup
+    pub fn with_no_players(mut self, no_players: u8) -> Self {
+        debug_assert!(
+            (3..=6).contains(&no_players),
+            "Player count must be between 3 and 6"
+        );
+        self.no_players = no_players;
+        self
+    }
+    pub fn with_hypotheses(mut self, elo0: f64, elo1: f64) -> Self {
+        debug_assert!(elo0 < elo1, "elo0 must be below elo1");
+        self.elo0 = elo0;
+        self.elo1 = elo1;
+        self
+    }
+    // alpha is the chance of accepting H1 when H0 holds, beta the reverse
+    pub fn with_error_bounds(mut self, alpha: f64, beta: f64) -> Self {
+        debug_assert!(alpha > 0.0 && alpha < 1.0 && beta > 0.0 && beta < 1.0);
+        self.alpha = alpha;
+        self.beta = beta;
+        self
+    }
+    // The variance estimate of a handful of deals is too rough to stop on
+    pub fn with_min_deals(mut self, min_deals: usize) -> Self {
+        self.min_deals = min_deals;
+        self
+    }
+    // Stops undecided after this many deals
+    pub fn with_max_deals(mut self, max_deals: usize) -> Self {
+        self.max_deals = max_deals;
+        self
+    }
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+    pub fn with_referee(mut self, referee: Referee) -> Self {
+        self.referee = referee;
+        self
+    }
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    // Plays deals until a hypothesis is accepted or max_deals run out, calling report after each
+    pub fn run<F: FnMut(&SprtStatus)>(&self, mut report: F) -> SprtStatus {
+        let lineup: Vec<usize> = (0..self.no_players as usize).map(|seat| seat % 2).collect();
+        let mut seatings = distinct_seatings(&lineup);
+        if self.no_players % 2 == 1 {
+            let mirrored: Vec<usize> = lineup.iter().map(|&entrant| 1 - entrant).collect();
+            seatings.extend(distinct_seatings(&mirrored));
+        }
+        let (lower_bound, upper_bound) = self.bounds();
+        let mut trials: Vec<f64> = Vec::with_capacity(self.max_deals.min(1024));
+        let mut status = SprtStatus {
+            deals: 0,
+            games: 0,
+            score: 0.5,
+            llr: 0.0,
+            lower_bound,
+            upper_bound,
+            decision: SprtDecision::Continue,
+        };
+        while status.decision == SprtDecision::Continue && trials.len() < self.max_deals {
+            let seed = self.seed.wrapping_add(trials.len() as u64);
+            let trial = seatings
+                .iter()
+                .map(|seating| {
+                    head_to_head_score(&play_seeded(
+                        &self.entrants,
+                        seed,
+                        seating.clone(),
+                        self.referee,
+                    ))
+                })
+                .sum::<f64>()
+                / seatings.len() as f64;
+            trials.push(trial);
+            status.deals = trials.len();
+            status.games += seatings.len();
+            status.score = trials.iter().sum::<f64>() / trials.len() as f64;
+            status.llr = self.llr(&trials);
+            status.decision = if status.deals < self.min_deals {
+                SprtDecision::Continue
+            } else if status.llr >= upper_bound {
+                SprtDecision::AcceptH1
+            } else if status.llr <= lower_bound {
+                SprtDecision::AcceptH0
+            } else {
+                SprtDecision::Continue
+            };
+            report(&status);
+        }
+        status
+    }
+
+    // Normal approximation of the log likelihood ratio of the trial scores
+    pub fn llr(&self, trials: &[f64]) -> f64 {
+        let n = trials.len() as f64;
+        if trials.len() < 2 {
+            return 0.0;
+        }
+        let mean = trials.iter().sum::<f64>() / n;
+        let variance = trials
+            .iter()
+            .map(|trial| (trial - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        // Every trial so far scored the same, nothing to say yet
+        if variance <= f64::EPSILON {
+            return 0.0;
+        }
+        let score0 = elo_to_score(self.elo0);
+        let score1 = elo_to_score(self.elo1);
+        n * (score1 - score0) * (2.0 * mean - score0 - score1) / (2.0 * variance)
+    }
+}
+
+// Candidate's average score over every candidate and baseline pair of seats
+pub fn head_to_head_score(result: &GameResult) -> f64 {
+    let mut total = 0.0;
+    let mut pairs = 0;
+    for (a, &entrant_a) in result.entrants.iter().enumerate() {
+        if entrant_a != CANDIDATE {
+            continue;
+        }
+        for (b, &entrant_b) in result.entrants.iter().enumerate() {
+            if entrant_b != BASELINE {
+                continue;
+            }
+            total += match result.scores[a].cmp(&result.scores[b]) {
+                std::cmp::Ordering::Greater => 1.0,
+                std::cmp::Ordering::Equal => 0.5,
+                std::cmp::Ordering::Less => 0.0,
+            };
+            pairs += 1;
+        }
+    }
+    match pairs {
+        0 => 0.5,
+        _ => total / pairs as f64,
+    }
+}
+
+pub fn elo_to_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+pub fn score_to_elo(score: f64) -> f64 {
+    let score = score.clamp(1e-6, 1.0 - 1e-6);
+    -400.0 * (1.0 / score - 1.0).log10()
+}
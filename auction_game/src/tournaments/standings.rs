@@ -0,0 +1,138 @@
+use crate::tournaments::ratings::{bootstrap_ratings, Rating};
+use crate::tournaments::tournament::GameResult;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+#[derive(Clone, Debug)]
+pub struct StandingsRow {
+    pub name: String,
+    pub games: usize,
+    // Games won outright count 1, shared wins split between the winners
+    pub wins: f32,
+    pub average_score: f32,
+    pub average_place: f32,
+    pub rating: Rating,
+}
+
+// Results of every game with the per entrant summary, best rated first
+pub struct Standings {
+    rows: Vec<StandingsRow>,
+    results: Vec<GameResult>,
+    names: Vec<String>,
+}
+
+impl Standings {
+    pub fn new(names: Vec<String>, results: Vec<GameResult>) -> Self {
+        let n = names.len();
+        let mut games = vec![0; n];
+        let mut wins = vec![0.0; n];
+        let mut total_scores = vec![0.0; n];
+        let mut total_places = vec![0.0; n];
+        for result in results.iter() {
+            let places = result.places();
+            let best = result.scores.iter().max().copied().unwrap_or(0);
+            let shared = result.scores.iter().filter(|&&score| score == best).count();
+            for (seat, &entrant) in result.entrants.iter().enumerate() {
+                games[entrant] += 1;
+                total_scores[entrant] += result.scores[seat] as f32;
+                total_places[entrant] += places[seat];
+                if result.scores[seat] == best {
+                    wins[entrant] += 1.0 / shared as f32;
+                }
+            }
+        }
+        let ratings = bootstrap_ratings(n, &results, 0);
+        let mut rows: Vec<StandingsRow> = names
+            .iter()
+            .enumerate()
+            .map(|(entrant, name)| {
+                let played = games[entrant].max(1) as f32;
+                StandingsRow {
+                    name: name.clone(),
+                    games: games[entrant],
+                    wins: wins[entrant],
+                    average_score: total_scores[entrant] / played,
+                    average_place: total_places[entrant] / played,
+                    rating: ratings[entrant],
+                }
+            })
+            .collect();
+        rows.sort_by(|a, b| b.rating.rating.total_cmp(&a.rating.rating));
+        Standings {
+            rows,
+            results,
+            names,
+        }
+    }
+    pub fn rows(&self) -> &[StandingsRow] {
+        &self.rows
+    }
+    pub fn results(&self) -> &[GameResult] {
+        &self.results
+    }
+
+    pub fn leaderboard(&self) -> String {
+        let width = self
+            .rows
+            .iter()
+            .map(|row| row.name.len())
+            .max()
+            .unwrap_or(0)
+            .max(6);
+        let mut board = format!(
+            "{:>4}  {:<width$}  {:>5}  {:>6}  {:>6}  {:>5}  {:>6}  {:>5}\n",
+            "Rank", "Player", "Games", "Wins", "Win%", "Place", "Score", "Elo"
+        );
+        for (rank, row) in self.rows.iter().enumerate() {
+            board.push_str(&format!(
+                "{:>4}  {:<width$}  {:>5}  {:>6.1}  {:>5.1}%  {:>5.2}  {:>6.1}  {:>5.0} ± {:.0}\n",
+                rank + 1,
+                row.name,
+                row.games,
+                row.wins,
+                100.0 * row.wins / row.games.max(1) as f32,
+                row.average_place,
+                row.average_score,
+                row.rating.rating,
+                row.rating.interval,
+            ));
+        }
+        board
+    }
+
+    // Tab separated standings followed by every game, one seat per column
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        writeln!(
+            file,
+            "rank\tplayer\tgames\twins\taverage_place\taverage_score\telo\telo_95"
+        )?;
+        for (rank, row) in self.rows.iter().enumerate() {
+            writeln!(
+                file,
+                "{}\t{}\t{}\t{:.2}\t{:.3}\t{:.3}\t{:.1}\t{:.1}",
+                rank + 1,
+                row.name,
+                row.games,
+                row.wins,
+                row.average_place,
+                row.average_score,
+                row.rating.rating,
+                row.rating.interval
+            )?;
+        }
+        writeln!(file)?;
+        writeln!(file, "game\tseed\tseats")?;
+        for (game, result) in self.results.iter().enumerate() {
+            let seats: Vec<String> = result
+                .entrants
+                .iter()
+                .zip(result.scores.iter())
+                .map(|(&entrant, score)| format!("{}={}", self.names[entrant], score))
+                .collect();
+            writeln!(file, "{}\t{}\t{}", game + 1, result.seed, seats.join("\t"))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use crate::engines::traits::PlayerController;
+use crate::game_modes::referee::Referee;
+use crate::game_modes::runner::GameRunner;
+use crate::models::enums::Player;
+use crate::models::game_state::GameState;
+use crate::tournaments::standings::Standings;
+use log::info;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::sync::Arc;
+
+const SEATING_SEED: u64 = 0x5EA7_1265_0F5A_1E00;
+
+// Builds a controller for seat id in a game of no_players
+pub type ControllerFactory = Arc<dyn Fn(Player, u8) -> Box<dyn PlayerController> + Send + Sync>;
+
+#[derive(Clone)]
+pub struct Entrant {
+    name: String,
+    factory: ControllerFactory,
+}
+
+impl Entrant {
+    pub fn new(name: String, factory: ControllerFactory) -> Self {
+        Entrant { name, factory }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn create(&self, id: Player, no_players: u8) -> Box<dyn PlayerController> {
+        (self.factory)(id, no_players)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GameResult {
+    pub seed: u64,
+    // Entrant index of every seat
+    pub entrants: Vec<usize>,
+    // Checks plus coins of every seat
+    pub scores: Vec<u8>,
+}
+
+impl GameResult {
+    // Finishing place of every seat, 1 for the winner, tied seats sharing the average place
+    pub fn places(&self) -> Vec<f32> {
+        self.scores
+            .iter()
+            .map(|score| {
+                let better = self.scores.iter().filter(|other| *other > score).count();
+                let tied = self.scores.iter().filter(|other| *other == score).count();
+                better as f32 + (tied as f32 + 1.0) / 2.0
+            })
+            .collect()
+    }
+}
+
+pub fn final_scores(game_state: &GameState) -> Vec<u8> {
+    (0..game_state.no_players())
+        .map(|player| {
+            game_state.get_player_checks(player).iter().sum::<u8>()
+                + game_state.get_player_coins(player)
+        })
+        .collect()
+}
+
+// Plays games among the entrants, cycling through the player counts and rotating which entrants
+// play. Seats are shuffled from each game's seed, so no entrant always bids right after another.
+pub struct Tournament {
+    entrants: Vec<Entrant>,
+    games: usize,
+    player_counts: Vec<u8>,
+    seed: u64,
+    referee: Referee,
+}
+
+impl Tournament {
+    pub fn new(entrants: Vec<Entrant>, games: usize) -> Self {
+        debug_assert!(!entrants.is_empty(), "A tournament needs entrants");
+        let default_count = (entrants.len() as u8).clamp(3, 6);
+        Tournament {
+            entrants,
+            games,
+            player_counts: vec![default_count],
+            seed: 0,
+            referee: Referee::default(),
+        }
+    }
+    pub fn with_player_counts(mut self, player_counts: Vec<u8>) -> Self {
+        debug_assert!(
+            player_counts.iter().all(|count| (3..=6).contains(count)),
+            "Player counts must be between 3 and 6"
+        );
+        if !player_counts.is_empty() {
+            self.player_counts = player_counts;
+        }
+        self
+    }
+    // Game i is dealt from seed + i
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+    pub fn with_referee(mut self, referee: Referee) -> Self {
+        self.referee = referee;
+        self
+    }
+    pub fn entrants(&self) -> &[Entrant] {
+        &self.entrants
+    }
+
+    // Entrant index of every seat in game
+    pub fn seating(&self, game: usize) -> Vec<usize> {
+        let no_players = self.player_counts[game % self.player_counts.len()] as usize;
+        let rotation = game / self.player_counts.len();
+        let mut seating: Vec<usize> = (0..no_players)
+            .map(|seat| (rotation + seat) % self.entrants.len())
+            .collect();
+        // Kept apart from the deal, which is drawn from the same seed
+        let seed = self.seed.wrapping_add(game as u64) ^ SEATING_SEED;
+        seating.shuffle(&mut StdRng::seed_from_u64(seed));
+        seating
+    }
+
+    pub fn run(&self) -> Standings {
+        let mut results: Vec<GameResult> = Vec::with_capacity(self.games);
+        for game in 0..self.games {
+            let seed = self.seed.wrapping_add(game as u64);
+            let seating = self.seating(game);
+            let result = self.play(seed, seating);
+            info!(
+                "Game {} seed {}: {}",
+                game + 1,
+                seed,
+                result
+                    .entrants
+                    .iter()
+                    .zip(result.scores.iter())
+                    .map(|(&entrant, score)| format!("{} {}", self.entrants[entrant].name, score))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            );
+            results.push(result);
+        }
+        Standings::new(
+            self.entrants
+                .iter()
+                .map(|entrant| entrant.name.clone())
+                .collect(),
+            results,
+        )
+    }
+
+    pub fn play(&self, seed: u64, seating: Vec<usize>) -> GameResult {
+        play_seeded(&self.entrants, seed, seating, self.referee)
+    }
+}
+
+// Plays one game dealt from seed with seating[seat] the entrant in each seat, seats that
+// forfeit scoring 0
+pub fn play_seeded(
+    entrants: &[Entrant],
+    seed: u64,
+    seating: Vec<usize>,
+    referee: Referee,
+) -> GameResult {
+    let no_players = seating.len() as u8;
+    let controllers: Vec<Box<dyn PlayerController>> = seating
+        .iter()
+        .enumerate()
+        .map(|(seat, &entrant)| entrants[entrant].create(seat as Player, no_players))
+        .collect();
+    let mut runner = GameRunner::new(controllers)
+        .with_seed(seed)
+        .with_referee(referee);
+    let final_state = runner.run();
+    let scores = final_scores(&final_state)
+        .into_iter()
+        .zip(runner.forfeits())
+        .map(|(score, forfeit)| match forfeit {
+            true => 0,
+            false => score,
+        })
+        .collect();
+    GameResult {
+        seed,
+        entrants: seating,
+        scores,
+    }
+}
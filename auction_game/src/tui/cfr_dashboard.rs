@@ -0,0 +1,295 @@
+use crate::engines::algorithms::counterfactual_regret::CFR;
+use crate::engines::strategies::average::StrategyKind;
+use crate::models::enums::GamePhase;
+use crate::models::game_state::GameState;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::Line;
+use ratatui::widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::time::{Duration, Instant};
+
+const PLAYER_COLOURS: [Color; 6] = [
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+];
+
+// Live view of CFR::find_nash. The solve runs in chunks of chunk_iterations and the dashboard
+// redraws between chunks with the NashConv (sell phase only), the root value of every player
+// averaged over every chunk so far and the current strategy of every player at the root.
+// q or Esc stops the solve and keeps what has been learnt so far.
+pub struct CFRDashboard {
+    chunk_iterations: usize,
+    max_iterations: usize,
+    // NashConv is measured every this many chunks, as a best response costs far more than a chunk
+    exploitability_every: usize,
+}
+
+// What the dashboard plotted, so callers can report on it after the terminal is restored
+#[derive(Clone, Debug, Default)]
+pub struct DashboardHistory {
+    // (iteration, NashConv)
+    pub exploitability: Vec<(f64, f64)>,
+    // (iteration, root value averaged over every chunk so far) per player
+    pub root_values: Vec<Vec<(f64, f64)>>,
+    pub iterations: usize,
+    pub stopped: bool,
+}
+
+impl CFRDashboard {
+    pub fn new(chunk_iterations: usize, max_iterations: usize) -> Self {
+        CFRDashboard {
+            chunk_iterations: chunk_iterations.max(1),
+            max_iterations,
+            exploitability_every: 1,
+        }
+    }
+    pub fn with_exploitability_every(mut self, chunks: usize) -> Self {
+        self.exploitability_every = chunks.max(1);
+        self
+    }
+
+    pub fn run(&self, cfr: &mut CFR, game_state: &GameState) -> io::Result<DashboardHistory> {
+        let mut terminal = ratatui::try_init()?;
+        let result = self.solve(&mut terminal, cfr, game_state);
+        ratatui::restore();
+        result
+    }
+
+    fn solve(
+        &self,
+        terminal: &mut DefaultTerminal,
+        cfr: &mut CFR,
+        game_state: &GameState,
+    ) -> io::Result<DashboardHistory> {
+        let no_players = game_state.no_players() as usize;
+        let mut history = DashboardHistory {
+            root_values: vec![Vec::new(); no_players],
+            ..DashboardHistory::default()
+        };
+        let start = Instant::now();
+        let mut chunk: usize = 0;
+        let mut root_value: Vec<f64> = vec![0.0; no_players];
+        let mut root_samples: Vec<f64> = vec![0.0; no_players];
+        loop {
+            terminal.draw(|frame| self.draw(frame, cfr, game_state, &history, start))?;
+            if history.iterations >= self.max_iterations {
+                break;
+            }
+            if Self::stop_requested()? {
+                history.stopped = true;
+                break;
+            }
+            let iterations = self
+                .chunk_iterations
+                .min(self.max_iterations - history.iterations);
+            history.iterations += cfr.find_nash(game_state, iterations);
+            chunk += 1;
+            let iteration = cfr.iteration() as f64;
+            // find_nash only averages the root value over its own chunk, so pool the chunks
+            for (player, (value, samples)) in
+                cfr.root_value().iter().zip(cfr.root_samples()).enumerate()
+            {
+                let samples = *samples as f64;
+                root_samples[player] += samples;
+                if root_samples[player] > 0.0 {
+                    root_value[player] +=
+                        (*value as f64 - root_value[player]) * samples / root_samples[player];
+                }
+                history.root_values[player].push((iteration, root_value[player]));
+            }
+            if game_state.game_phase() == GamePhase::Sell
+                && chunk.is_multiple_of(self.exploitability_every)
+            {
+                let exploitability = cfr.exploitability(game_state);
+                history
+                    .exploitability
+                    .push((iteration, exploitability.nash_conv as f64));
+            }
+        }
+        // Leaves the last frame up until a key is pressed so the final state can be read
+        if !history.stopped {
+            loop {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(history)
+    }
+
+    fn stop_requested() -> io::Result<bool> {
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press
+                    && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    fn draw(
+        &self,
+        frame: &mut Frame,
+        cfr: &CFR,
+        game_state: &GameState,
+        history: &DashboardHistory,
+        start: Instant,
+    ) {
+        let [charts_area, strategy_area, status_area] = Layout::vertical([
+            Constraint::Percentage(60),
+            Constraint::Min(4),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [exploitability_area, value_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(charts_area);
+        Self::draw_exploitability(frame, exploitability_area, game_state, history);
+        Self::draw_root_values(frame, value_area, history);
+        Self::draw_strategies(frame, strategy_area, cfr, game_state);
+        let nash_conv = match history.exploitability.last() {
+            Some((_, nash_conv)) => format!("{nash_conv:.3}"),
+            None => "-".to_string(),
+        };
+        let status = format!(
+            " Iteration {} | NashConv {} | {:.1}s | {} | q/Esc: stop",
+            cfr.iteration(),
+            nash_conv,
+            start.elapsed().as_secs_f64(),
+            if history.iterations >= self.max_iterations {
+                "done, press any key"
+            } else {
+                "solving"
+            }
+        );
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    fn draw_exploitability(
+        frame: &mut Frame,
+        area: Rect,
+        game_state: &GameState,
+        history: &DashboardHistory,
+    ) {
+        let block = Block::default().title("NashConv").borders(Borders::ALL);
+        if game_state.game_phase() == GamePhase::Bid {
+            frame.render_widget(
+                Paragraph::new("Best responses are only computed for the sell phase").block(block),
+                area,
+            );
+            return;
+        }
+        let dataset = Dataset::default()
+            .name("NashConv")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::White))
+            .data(&history.exploitability);
+        let (x_bounds, y_bounds) = Self::bounds(std::slice::from_ref(&history.exploitability));
+        let chart = Chart::new(vec![dataset])
+            .block(block)
+            .x_axis(Self::axis(x_bounds, "iteration"))
+            .y_axis(Self::axis(y_bounds, "checks"));
+        frame.render_widget(chart, area);
+    }
+
+    fn draw_root_values(frame: &mut Frame, area: Rect, history: &DashboardHistory) {
+        let datasets: Vec<Dataset> = history
+            .root_values
+            .iter()
+            .enumerate()
+            .map(|(player, values)| {
+                Dataset::default()
+                    .name(format!("P{player}"))
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(PLAYER_COLOURS[player % PLAYER_COLOURS.len()]))
+                    .data(values)
+            })
+            .collect();
+        let (x_bounds, y_bounds) = Self::bounds(&history.root_values);
+        let chart = Chart::new(datasets)
+            .block(
+                Block::default()
+                    .title("Average root value")
+                    .borders(Borders::ALL),
+            )
+            .x_axis(Self::axis(x_bounds, "iteration"))
+            .y_axis(Self::axis(y_bounds, "value"));
+        frame.render_widget(chart, area);
+    }
+
+    // Current strategy of every player at the root, as the percentage played per move
+    fn draw_strategies(frame: &mut Frame, area: Rect, cfr: &CFR, game_state: &GameState) {
+        let players = match game_state.game_phase() {
+            GamePhase::Sell => (0..game_state.no_players()).collect(),
+            GamePhase::Bid => vec![game_state.current_player()],
+        };
+        let rows: Vec<Row> = players
+            .into_iter()
+            .map(|player| {
+                let (moves, strategy) = cfr.policy(game_state, player, StrategyKind::Current);
+                let distribution: Vec<String> = moves
+                    .iter()
+                    .zip(strategy.iter())
+                    .map(|(action, probability)| format!("{action}:{:.0}%", probability * 100.0))
+                    .collect();
+                Row::new(vec![format!("P{player}"), distribution.join(" ")]).style(
+                    Style::default().fg(PLAYER_COLOURS[player as usize % PLAYER_COLOURS.len()]),
+                )
+            })
+            .collect();
+        let table = Table::new(rows, [Constraint::Length(4), Constraint::Fill(1)]).block(
+            Block::default()
+                .title(Line::from("Current strategy at the root"))
+                .borders(Borders::ALL),
+        );
+        frame.render_widget(table, area);
+    }
+
+    fn axis(bounds: [f64; 2], title: &str) -> Axis<'static> {
+        Axis::default()
+            .title(title.to_string())
+            .bounds(bounds)
+            .labels(vec![
+                format!("{:.1}", bounds[0]),
+                format!("{:.1}", (bounds[0] + bounds[1]) / 2.0),
+                format!("{:.1}", bounds[1]),
+            ])
+    }
+
+    fn bounds(series: &[Vec<(f64, f64)>]) -> ([f64; 2], [f64; 2]) {
+        let points = series.iter().flatten();
+        let (mut x_min, mut x_max, mut y_min, mut y_max) = (f64::MAX, f64::MIN, f64::MAX, f64::MIN);
+        for &(x, y) in points {
+            x_min = x_min.min(x);
+            x_max = x_max.max(x);
+            y_min = y_min.min(y);
+            y_max = y_max.max(y);
+        }
+        if x_min > x_max {
+            return ([0.0, 1.0], [0.0, 1.0]);
+        }
+        if x_max - x_min < 1.0 {
+            x_max = x_min + 1.0;
+        }
+        if y_max - y_min < 0.1 {
+            y_max += 0.05;
+            y_min -= 0.05;
+        }
+        ([x_min, x_max], [y_min.min(0.0), y_max])
+    }
+}
@@ -0,0 +1,384 @@
+use crate::engines::scorers::naive_round_score::NaiveRoundScore;
+use crate::engines::scorers::sell_phase_score::SellPhaseScore;
+use crate::models::enums::GamePhase;
+use crate::models::game_record::{GameRecord, RecordedMove};
+use crate::models::game_state::GameState;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::mem;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+const PLAYER_COLOURS: [Color; 6] = [
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+];
+
+// Steps through a recorded game. Position 0 is the deal and position i follows the i-th move
+// of the record, the table showing every seat's coins, bid and holdings with the seats the
+// move belongs to highlighted, next to the move list and the auction pool.
+// Every position is valued on a thread of its own with the scorers MaxN searches with,
+// NaiveRoundScore in the bid phase and SellPhaseScore in the sell phase. The first sell phase
+// positions take a while to solve, so their values fill in as the solves finish.
+// Left/h and Right/l step, Up/k and Down/j jump an auction, Home/End, q or Esc quits.
+pub struct ReplayViewer {
+    record: GameRecord,
+    states: Vec<GameState>,
+    plays: Vec<RecordedMove>,
+    // comments[i] are the comments recorded before the move leading to position i + 1
+    comments: Vec<Vec<String>>,
+    evaluations: Vec<Option<Vec<f32>>>,
+    sell_phase_score: SellPhaseScore,
+    position: usize,
+}
+
+impl ReplayViewer {
+    pub fn new(record: GameRecord) -> Result<Self, String> {
+        let states = record.states()?;
+        let plays: Vec<RecordedMove> = record.plays().cloned().collect();
+        let mut comments: Vec<Vec<String>> = vec![Vec::new(); plays.len() + 1];
+        let mut play = 0;
+        for recorded_move in record.moves.iter() {
+            match recorded_move {
+                RecordedMove::Comment(comment) => comments[play].push(comment.clone()),
+                _ => play += 1,
+            }
+        }
+        let evaluations = vec![None; states.len()];
+        Ok(ReplayViewer {
+            record,
+            states,
+            plays,
+            comments,
+            evaluations,
+            sell_phase_score: SellPhaseScore::default(),
+            position: 0,
+        })
+    }
+    pub fn with_sell_phase_score(mut self, sell_phase_score: SellPhaseScore) -> Self {
+        self.sell_phase_score = sell_phase_score;
+        self
+    }
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+    pub fn seek(&mut self, position: usize) {
+        self.position = position.min(self.states.len() - 1);
+    }
+
+    pub fn run(&mut self) -> io::Result<()> {
+        let evaluations = self.spawn_evaluator();
+        let mut terminal = ratatui::try_init()?;
+        let result = self.browse(&mut terminal, evaluations);
+        ratatui::restore();
+        result
+    }
+
+    // Values every position, the cheap ones first, stopping once the receiver is dropped
+    fn spawn_evaluator(&mut self) -> Receiver<(usize, Vec<f32>)> {
+        let (sender, receiver) = mpsc::channel();
+        let mut states: Vec<(usize, GameState)> = self.states.iter().cloned().enumerate().collect();
+        states.sort_by_key(|(_, game_state)| is_sell_phase(game_state));
+        let mut sell_phase_score = mem::take(&mut self.sell_phase_score);
+        thread::spawn(move || {
+            for (position, game_state) in states.iter() {
+                let evaluation = match is_sell_phase(game_state) {
+                    true => sell_phase_score.round_score_function(game_state),
+                    false => NaiveRoundScore::round_score_function(game_state),
+                };
+                if sender.send((*position, evaluation)).is_err() {
+                    break;
+                }
+            }
+        });
+        receiver
+    }
+
+    fn browse(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        evaluations: Receiver<(usize, Vec<f32>)>,
+    ) -> io::Result<()> {
+        loop {
+            for (position, evaluation) in evaluations.try_iter() {
+                self.evaluations[position] = Some(evaluation);
+            }
+            terminal.draw(|frame| self.draw(frame))?;
+            // Redraws every so often for the evaluations still coming in
+            if !event::poll(Duration::from_millis(250))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let last = self.states.len() - 1;
+            self.position = match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => {
+                    (self.position + 1).min(last)
+                }
+                KeyCode::Left | KeyCode::Char('h') => self.position.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => self.next_reveal(),
+                KeyCode::Up | KeyCode::Char('k') => self.previous_reveal(),
+                KeyCode::Home | KeyCode::Char('g') => 0,
+                KeyCode::End | KeyCode::Char('G') => last,
+                _ => self.position,
+            };
+        }
+    }
+
+    // Position after the next reveal, or the last position
+    fn next_reveal(&self) -> usize {
+        (self.position + 1..self.plays.len())
+            .find(|&play| matches!(self.plays[play], RecordedMove::Reveal(_)))
+            .map_or(self.states.len() - 1, |play| play + 1)
+    }
+
+    // Position after the reveal before the current auction, or the deal
+    fn previous_reveal(&self) -> usize {
+        (0..self.position.saturating_sub(1))
+            .rev()
+            .find(|&play| matches!(self.plays[play], RecordedMove::Reveal(_)))
+            .map_or(0, |play| play + 1)
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let [header_area, body_area, notes_area, status_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(10),
+            Constraint::Length(4),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [table_area, moves_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(24)]).areas(body_area);
+        let [players_area, pool_area] =
+            Layout::vertical([Constraint::Min(4), Constraint::Length(4)]).areas(table_area);
+        self.draw_header(frame, header_area);
+        self.draw_players(frame, players_area);
+        self.draw_pool(frame, pool_area);
+        self.draw_moves(frame, moves_area);
+        self.draw_notes(frame, notes_area);
+        let status = " Left/Right: move | Up/Down: auction | Home/End | q/Esc: quit";
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    fn draw_header(&self, frame: &mut Frame, area: Rect) {
+        let game_state = &self.states[self.position];
+        let phase = match (game_state.game_end(), game_state.game_phase()) {
+            (true, _) => "game over",
+            (false, GamePhase::Bid) => "bid phase",
+            (false, GamePhase::Sell) => "sell phase",
+        };
+        let mut header = format!(
+            "{} | {} | {} | position {}/{} | {} round {}",
+            self.record.event,
+            self.record.date,
+            self.record.rule_set,
+            self.position,
+            self.states.len() - 1,
+            phase,
+            game_state.round_no()
+        );
+        if let Some(result) = &self.record.result {
+            header.push_str(&format!(" | result {}", join(result)));
+        }
+        frame.render_widget(
+            Paragraph::new(header).block(Block::default().title("Replay").borders(Borders::ALL)),
+            area,
+        );
+    }
+
+    fn draw_players(&self, frame: &mut Frame, area: Rect) {
+        let game_state = &self.states[self.position];
+        let last_play = self.position.checked_sub(1).map(|play| &self.plays[play]);
+        let evaluation = self.evaluations[self.position].as_deref().unwrap_or(&[]);
+        let rows: Vec<Row> = (0..game_state.no_players())
+            .map(|player| {
+                let seat = player as usize;
+                let action = match last_play {
+                    Some(RecordedMove::Bid {
+                        player: bidder,
+                        action,
+                    }) if *bidder == player => match action {
+                        0 => "pass".to_string(),
+                        _ => format!("bid {action}"),
+                    },
+                    Some(RecordedMove::Sales(sales)) => format!("sold {}", sales[seat]),
+                    _ => String::new(),
+                };
+                let mut style = Style::default().fg(PLAYER_COLOURS[seat % PLAYER_COLOURS.len()]);
+                if !action.is_empty() {
+                    style = style.add_modifier(Modifier::REVERSED);
+                } else if !game_state.game_end()
+                    && game_state.game_phase() == GamePhase::Bid
+                    && !game_state.auction_end()
+                    && game_state.current_player() == player
+                {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                // Active bids hold the properties being sold in the sell phase
+                let (bid, active) = match game_state.game_phase() {
+                    GamePhase::Bid => (
+                        game_state.get_active_bids()[seat].to_string(),
+                        match game_state.get_active_players()[seat] {
+                            true => "",
+                            false => "out",
+                        },
+                    ),
+                    GamePhase::Sell => (String::new(), ""),
+                };
+                let value = match evaluation.get(seat) {
+                    Some(value) => format!("{:+.3}", value),
+                    None => "...".to_string(),
+                };
+                Row::new(vec![
+                    format!("P{}", player + 1),
+                    self.record.players[seat].clone(),
+                    game_state.get_player_coins(player).to_string(),
+                    bid,
+                    active.to_string(),
+                    join(game_state.get_player_properties(player)),
+                    join(game_state.get_player_checks(player)),
+                    action,
+                    value,
+                ])
+                .style(style)
+            })
+            .collect();
+        let header = Row::new(vec![
+            "Seat",
+            "Name",
+            "Coins",
+            "Bid",
+            "",
+            "Properties",
+            "Checks",
+            "Move",
+            "Eval",
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(4),
+                Constraint::Length(16),
+                Constraint::Length(5),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Length(8),
+                Constraint::Length(7),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .title(format!("Table, evaluated by {}", scorer_name(game_state)))
+                .borders(Borders::ALL),
+        );
+        frame.render_widget(table, area);
+    }
+
+    fn draw_pool(&self, frame: &mut Frame, area: Rect) {
+        let game_state = &self.states[self.position];
+        let pool = format!(
+            "Auction pool: {}\nDeck: {} properties, {} checks",
+            join(game_state.get_auction_pool()),
+            game_state.get_remaining_properties().len(),
+            game_state.get_remaining_checks().len()
+        );
+        frame.render_widget(
+            Paragraph::new(pool).block(Block::default().borders(Borders::ALL)),
+            area,
+        );
+    }
+
+    fn draw_moves(&self, frame: &mut Frame, area: Rect) {
+        let mut auction = 0;
+        let items: Vec<ListItem> = self
+            .plays
+            .iter()
+            .map(|play| {
+                if matches!(play, RecordedMove::Reveal(_)) {
+                    auction += 1;
+                }
+                ListItem::new(format!("{auction}. {}", move_text(play)))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().title("Moves").borders(Borders::ALL))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut list_state = ListState::default().with_selected(self.position.checked_sub(1));
+        frame.render_stateful_widget(list, area, &mut list_state);
+    }
+
+    fn draw_notes(&self, frame: &mut Frame, area: Rect) {
+        let mut lines: Vec<Line> = match self.position.checked_sub(1) {
+            Some(play) => self.comments[play]
+                .iter()
+                .map(|comment| Line::from(comment.clone()))
+                .collect(),
+            None => vec![Line::from("Dealt")],
+        };
+        // Comments after the final move
+        if self.position == self.states.len() - 1 {
+            lines.extend(
+                self.comments[self.plays.len()]
+                    .iter()
+                    .map(|comment| Line::from(comment.clone())),
+            );
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().title("Notes").borders(Borders::ALL)),
+            area,
+        );
+    }
+}
+
+// Finished games are scored on their checks and coins alone
+fn is_sell_phase(game_state: &GameState) -> bool {
+    game_state.game_phase() == GamePhase::Sell && !game_state.game_end()
+}
+
+fn scorer_name(game_state: &GameState) -> &'static str {
+    match is_sell_phase(game_state) {
+        true => "SellPhaseScore",
+        false => "NaiveRoundScore",
+    }
+}
+
+fn move_text(play: &RecordedMove) -> String {
+    match play {
+        RecordedMove::Reveal(cards) => format!("reveal {}", join(cards)),
+        RecordedMove::Bid { player, action: 0 } => format!("P{} pass", player + 1),
+        RecordedMove::Bid { player, action } => format!("P{} bid {}", player + 1, action),
+        RecordedMove::Sales(sales) => format!("sell {}", join(sales)),
+        RecordedMove::Comment(comment) => comment.clone(),
+    }
+}
+
+fn join(values: &[u8]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -1 +1,21 @@
+// 64-bit FNV-1a. Unlike DefaultHasher its output is fixed, so it can be stored in files and
+// compared across builds and machines.
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
 
+pub fn checksum(bytes: &[u8]) -> u64 {
+    update_checksum(FNV_OFFSET_BASIS, bytes)
+}
+
+// Continues a checksum so large files can be hashed line by line
+pub fn update_checksum(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+pub fn empty_checksum() -> u64 {
+    FNV_OFFSET_BASIS
+}
@@ -41,6 +41,6 @@ pub fn init_logger(level: LevelFilter, filename: &str) {
     // Direct logs to the file instead of stdout
     builder.target(env_logger::Target::Pipe(Box::new(log_file)));
 
-    // Initialize the logger
-    builder.init();
+    // Initialize the logger, later calls keep logging to the first file
+    let _ = builder.try_init();
 }